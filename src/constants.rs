@@ -12,6 +12,8 @@ pub const STREAMS_STORAGE_KEY: &str = "streams";
 pub const SEARCH_HISTORY_STORAGE_KEY: &str = "search_history";
 pub const NOTIFICATIONS_STORAGE_KEY: &str = "notifications";
 pub const DISMISSED_EVENTS_STORAGE_KEY: &str = "dismissed_events";
+pub const INTRO_OUTRO_STORAGE_KEY: &str = "intro_outro";
+pub const DOWNLOADS_STORAGE_KEY: &str = "downloads";
 pub const LIBRARY_COLLECTION_NAME: &str = "libraryItem";
 pub const SEARCH_EXTRA_NAME: &str = "search";
 /// `https://{ADDON_UR}/meta/...` resource
@@ -26,14 +28,21 @@ pub const CATALOG_PAGE_SIZE: usize = 100;
 pub const CATALOG_PREVIEW_SIZE: usize = 100;
 pub const LIBRARY_RECENT_COUNT: usize = 200;
 pub const NOTIFICATION_ITEMS_COUNT: usize = 100;
+pub const CALENDAR_ITEMS_COUNT: usize = 100;
+/// The maximum number of meta requests the calendar has in flight at once.
+pub const CALENDAR_REQUESTS_CONCURRENCY: usize = 10;
 
 /// A `LibraryItem` is considered watched once we've watched more than the `duration * threshold`:
 ///
 /// `LibraryItem.state.time_watched` > `LibraryItem.state.duration` * [`WATCHED_THRESHOLD_COEF`]
 pub const WATCHED_THRESHOLD_COEF: f64 = 0.7;
 pub const CREDITS_THRESHOLD_COEF: f64 = 0.9;
+/// The minimum [`Stream::binge_score`] of a stream to be played after another one.
+///
+/// [`Stream::binge_score`]: crate::types::resource::Stream::binge_score
+pub const BINGE_MATCH_MIN_SCORE: u32 = 50;
 /// The latest migration scheme version
-pub const SCHEMA_VERSION: u32 = 14;
+pub const SCHEMA_VERSION: u32 = 18;
 pub const IMDB_LINK_CATEGORY: &str = "imdb";
 pub const GENRES_LINK_CATEGORY: &str = "Genres";
 pub const CINEMETA_TOP_CATALOG_ID: &str = "top";
@@ -6,7 +6,8 @@ use url::Url;
 use crate::{
     constants::URI_COMPONENT_ENCODE_SET,
     models::{
-        installed_addons_with_filters::InstalledAddonsRequest, library_with_filters::LibraryRequest,
+        calendar::CalendarItem, installed_addons_with_filters::InstalledAddonsRequest,
+        library_with_filters::LibraryRequest,
     },
     types::{
         addon::{ExtraValue, ResourcePath, ResourceRequest},
@@ -65,14 +66,12 @@ impl From<(&Stream, &Option<Url>, &Settings)> for ExternalPlayerLink {
 
 impl From<(&Stream, Option<&Url>, &Settings)> for ExternalPlayerLink {
     /// Create an [`ExternalPlayerLink`] using the [`Stream`],
-    /// the server url (from [`StreamingServer::base_url`] which indicates a running or not server)
+    /// the server url (from [`ActiveServer::ready_url`] which indicates a running or not server)
     /// and the user's [`Settings`] in order to use the [`Settings::player_type`] for generating a
     /// player-specific url.
     ///
-    /// [`StreamingServer::base_url`]: crate::models::streaming_server::StreamingServer::base_url
+    /// [`ActiveServer::ready_url`]: crate::types::streaming_server::ActiveServer::ready_url
     fn from((stream, streaming_server_url, settings): (&Stream, Option<&Url>, &Settings)) -> Self {
-        // Use streaming_server_url from settings if streaming_server is reachable
-        let streaming_server_url = streaming_server_url.map(|_| &settings.streaming_server_url);
         let http_regex = Regex::new(r"https?://").unwrap();
         let download = stream.download_url();
         let streaming = stream.streaming_url(streaming_server_url);
@@ -361,6 +360,14 @@ impl From<(&Video, &ResourceRequest, &Option<Url>, &Settings)> for VideoDeepLink
     }
 }
 
+impl From<(&CalendarItem, &Option<Url>, &Settings)> for VideoDeepLinks {
+    fn from(
+        (item, streaming_server_url, settings): (&CalendarItem, &Option<Url>, &Settings),
+    ) -> Self {
+        VideoDeepLinks::from((&item.video, &item.request, streaming_server_url, settings))
+    }
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct StreamDeepLinks {
@@ -370,11 +377,11 @@ pub struct StreamDeepLinks {
 
 impl From<(&Stream, &Option<Url>, &Settings)> for StreamDeepLinks {
     /// Create a [`StreamDeepLinks`] using the [`Stream`],
-    /// the server url (from [`StreamingServer::base_url`] which indicates a running or not server)
+    /// the server url (from [`ActiveServer::ready_url`] which indicates a running or not server)
     /// and the user's [`Settings`] in order to use the [`Settings::player_type`] for generating a
     /// player-specific url.
     ///
-    /// [`StreamingServer::base_url`]: crate::models::streaming_server::StreamingServer::base_url
+    /// [`ActiveServer::ready_url`]: crate::types::streaming_server::ActiveServer::ready_url
     fn from((stream, streaming_server_url, settings): (&Stream, &Option<Url>, &Settings)) -> Self {
         StreamDeepLinks {
             player: stream
@@ -401,11 +408,11 @@ impl
     )> for StreamDeepLinks
 {
     /// Create a [`StreamDeepLinks`] using the [`Stream`], stream request, meta request,
-    /// the server url (from [`StreamingServer::base_url`] which indicates a running or not server)
+    /// the server url (from [`ActiveServer::ready_url`] which indicates a running or not server)
     /// and the user's [`Settings`] in order to use the [`Settings::player_type`] for generating a
     /// player-specific url.
     ///
-    /// [`StreamingServer::base_url`]: crate::models::streaming_server::StreamingServer::base_url
+    /// [`ActiveServer::ready_url`]: crate::types::streaming_server::ActiveServer::ready_url
     fn from(
         (stream, stream_request, meta_request, streaming_server_url, settings): (
             &Stream,
@@ -0,0 +1,381 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use lazysort::SortedBy;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::{
+    constants::{CALENDAR_ITEMS_COUNT, CALENDAR_REQUESTS_CONCURRENCY, META_RESOURCE_NAME},
+    models::{
+        common::{eq_update, resource_update, Loadable, ResourceAction, ResourceLoadable},
+        ctx::Ctx,
+    },
+    runtime::{
+        msg::{Action, ActionLoad, Internal, Msg},
+        Effects, Env, UpdateWithCtx,
+    },
+    types::{
+        addon::{AggrRequest, ResourcePath, ResourceRequest},
+        library::{LibraryBucket, LibraryItem},
+        profile::Profile,
+        resource::{MetaItem, Video},
+    },
+};
+
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Selected {
+    /// The first day of the calendar.
+    pub from: NaiveDate,
+    /// The last day of the calendar (inclusive).
+    pub to: NaiveDate,
+    /// Offset of the user's local time from UTC in minutes,
+    /// used for deciding on which day a video is released.
+    #[serde(default)]
+    pub utc_offset: i32,
+}
+
+impl Selected {
+    fn local_date(&self, time: &DateTime<Utc>) -> NaiveDate {
+        (*time + Duration::minutes(self.utc_offset.into())).date_naive()
+    }
+}
+
+#[derive(Clone, PartialEq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarItem {
+    pub meta_id: String,
+    pub name: String,
+    pub poster: Option<Url>,
+    pub video: Video,
+    /// The meta request of the series, used for building the [`VideoDeepLinks`].
+    ///
+    /// [`VideoDeepLinks`]: crate::deep_links::VideoDeepLinks
+    pub request: ResourceRequest,
+}
+
+#[derive(Clone, PartialEq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarDay {
+    pub date: NaiveDate,
+    /// The videos released on that day ordered by their release time.
+    pub items: Vec<CalendarItem>,
+}
+
+/// Upcoming videos of the series in the user's library.
+///
+/// The meta items pulled for the notifications are used when available,
+/// the rest are requested from the meta addons, [`CALENDAR_REQUESTS_CONCURRENCY`] at a time.
+/// When an addon fails to provide a meta item, the next addon which can provide it is requested.
+#[derive(Default, Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Calendar {
+    pub selected: Option<Selected>,
+    /// Meta requests for the series which are not found in the notification catalogs,
+    /// including the failed requests to the previous addons.
+    pub meta_items: Vec<ResourceLoadable<MetaItem>>,
+    pub days: Vec<CalendarDay>,
+}
+
+impl<E: Env + 'static> UpdateWithCtx<E> for Calendar {
+    fn update(&mut self, msg: &Msg, ctx: &Ctx) -> Effects {
+        match msg {
+            Msg::Action(Action::Load(ActionLoad::Calendar(selected))) => {
+                let selected_effects = eq_update(&mut self.selected, Some(selected.to_owned()));
+                let meta_items_effects = meta_items_update::<E>(
+                    &mut self.meta_items,
+                    &self.selected,
+                    &ctx.library,
+                    &ctx.notification_catalogs,
+                    &ctx.profile,
+                );
+                let days_effects = days_update(
+                    &mut self.days,
+                    &self.selected,
+                    &ctx.library,
+                    &ctx.notification_catalogs,
+                    &self.meta_items,
+                );
+                selected_effects.join(meta_items_effects).join(days_effects)
+            }
+            Msg::Action(Action::Unload) => {
+                let selected_effects = eq_update(&mut self.selected, None);
+                let meta_items_effects = eq_update(&mut self.meta_items, vec![]);
+                let days_effects = eq_update(&mut self.days, vec![]);
+                selected_effects.join(meta_items_effects).join(days_effects)
+            }
+            Msg::Internal(Internal::ResourceRequestResult(request, result))
+                if request.path.resource == META_RESOURCE_NAME =>
+            {
+                let meta_item_effects = match self
+                    .meta_items
+                    .iter_mut()
+                    .find(|meta_item| meta_item.request == *request)
+                {
+                    Some(meta_item) => resource_update::<E, _>(
+                        meta_item,
+                        ResourceAction::ResourceRequestResult { request, result },
+                    ),
+                    None => Effects::none().unchanged(),
+                };
+                if meta_item_effects.has_changed {
+                    let meta_items_effects = meta_items_update::<E>(
+                        &mut self.meta_items,
+                        &self.selected,
+                        &ctx.library,
+                        &ctx.notification_catalogs,
+                        &ctx.profile,
+                    );
+                    let days_effects = days_update(
+                        &mut self.days,
+                        &self.selected,
+                        &ctx.library,
+                        &ctx.notification_catalogs,
+                        &self.meta_items,
+                    );
+                    meta_item_effects
+                        .join(meta_items_effects)
+                        .join(days_effects)
+                } else {
+                    meta_item_effects
+                }
+            }
+            Msg::Internal(Internal::ProfileChanged) => {
+                // the series are requested again from the next addon
+                // when the addon which provided them is uninstalled
+                let next_meta_items = self
+                    .meta_items
+                    .iter()
+                    .filter(|meta_item| {
+                        ctx.profile
+                            .addons
+                            .iter()
+                            .any(|addon| addon.transport_url == meta_item.request.base)
+                    })
+                    .cloned()
+                    .collect::<Vec<_>>();
+                let retain_effects = eq_update(&mut self.meta_items, next_meta_items);
+                let meta_items_effects = meta_items_update::<E>(
+                    &mut self.meta_items,
+                    &self.selected,
+                    &ctx.library,
+                    &ctx.notification_catalogs,
+                    &ctx.profile,
+                );
+                let days_effects = days_update(
+                    &mut self.days,
+                    &self.selected,
+                    &ctx.library,
+                    &ctx.notification_catalogs,
+                    &self.meta_items,
+                );
+                retain_effects.join(meta_items_effects).join(days_effects)
+            }
+            Msg::Internal(Internal::LibraryChanged(_))
+            | Msg::Internal(Internal::NotificationsChanged) => {
+                let meta_items_effects = meta_items_update::<E>(
+                    &mut self.meta_items,
+                    &self.selected,
+                    &ctx.library,
+                    &ctx.notification_catalogs,
+                    &ctx.profile,
+                );
+                let days_effects = days_update(
+                    &mut self.days,
+                    &self.selected,
+                    &ctx.library,
+                    &ctx.notification_catalogs,
+                    &self.meta_items,
+                );
+                meta_items_effects.join(days_effects)
+            }
+            _ => Effects::none().unchanged(),
+        }
+    }
+}
+
+/// The series in the library for which the calendar is shown, the most recently modified first.
+fn calendar_library_items(library: &LibraryBucket) -> impl Iterator<Item = &LibraryItem> {
+    library
+        .items
+        .values()
+        .filter(|library_item| {
+            library_item.r#type == "series" && !library_item.removed && !library_item.temp
+        })
+        .sorted_by(|a, b| b.mtime.cmp(&a.mtime))
+        .take(CALENDAR_ITEMS_COUNT)
+}
+
+fn notification_catalogs_meta_item<'a>(
+    notification_catalogs: &'a [ResourceLoadable<Vec<MetaItem>>],
+    id: &str,
+) -> Option<(&'a ResourceRequest, &'a MetaItem)> {
+    notification_catalogs.iter().find_map(|catalog| {
+        catalog
+            .content
+            .as_ref()
+            .and_then(|content| content.ready())
+            .and_then(|meta_items| {
+                meta_items
+                    .iter()
+                    .find(|meta_item| meta_item.preview.id == id)
+            })
+            .map(|meta_item| (&catalog.request, meta_item))
+    })
+}
+
+fn meta_items_update<E: Env + 'static>(
+    meta_items: &mut Vec<ResourceLoadable<MetaItem>>,
+    selected: &Option<Selected>,
+    library: &LibraryBucket,
+    notification_catalogs: &[ResourceLoadable<Vec<MetaItem>>],
+    profile: &Profile,
+) -> Effects {
+    if selected.is_none() {
+        return Effects::none().unchanged();
+    }
+
+    let loading_count = meta_items
+        .iter()
+        .filter(|meta_item| matches!(meta_item.content, Some(Loadable::Loading)))
+        .count();
+    let next_requests = calendar_library_items(library)
+        .filter(|library_item| {
+            notification_catalogs_meta_item(notification_catalogs, &library_item.id).is_none()
+        })
+        // the addons which can provide the meta item are requested one at a time,
+        // the next one only when all the previous requests failed
+        .filter_map(|library_item| {
+            let requested = meta_items
+                .iter()
+                .filter(|meta_item| meta_item.request.path.id == library_item.id)
+                .collect::<Vec<_>>();
+            if requested
+                .iter()
+                .any(|meta_item| !matches!(meta_item.content, Some(Loadable::Err(_))))
+            {
+                return None;
+            }
+
+            AggrRequest::AllOfResource(ResourcePath::without_extra(
+                META_RESOURCE_NAME,
+                &library_item.r#type,
+                &library_item.id,
+            ))
+            .plan(&profile.addons)
+            .into_iter()
+            .map(|(_, request)| request)
+            .find(|request| {
+                !requested
+                    .iter()
+                    .any(|meta_item| meta_item.request == *request)
+            })
+        })
+        .take(CALENDAR_REQUESTS_CONCURRENCY.saturating_sub(loading_count))
+        .collect::<Vec<_>>();
+    next_requests
+        .into_iter()
+        .map(|request| {
+            let mut meta_item = ResourceLoadable {
+                request: request.to_owned(),
+                content: None,
+            };
+            let effects = resource_update::<E, _>(
+                &mut meta_item,
+                ResourceAction::ResourceRequested { request: &request },
+            );
+            meta_items.push(meta_item);
+            effects
+        })
+        .fold(Effects::none().unchanged(), Effects::join)
+}
+
+fn days_update(
+    days: &mut Vec<CalendarDay>,
+    selected: &Option<Selected>,
+    library: &LibraryBucket,
+    notification_catalogs: &[ResourceLoadable<Vec<MetaItem>>],
+    meta_items: &[ResourceLoadable<MetaItem>],
+) -> Effects {
+    let selected = match selected {
+        Some(selected) => selected,
+        None => return eq_update(days, vec![]),
+    };
+    let next_days = calendar_library_items(library)
+        .filter_map(|library_item| {
+            notification_catalogs_meta_item(notification_catalogs, &library_item.id)
+                .map(|(catalog_request, meta_item)| {
+                    (
+                        ResourceRequest::new(
+                            catalog_request.base.to_owned(),
+                            ResourcePath::without_extra(
+                                META_RESOURCE_NAME,
+                                &meta_item.preview.r#type,
+                                &meta_item.preview.id,
+                            ),
+                        ),
+                        meta_item,
+                    )
+                })
+                .or_else(|| {
+                    meta_items.iter().find_map(|meta_item| {
+                        match (
+                            &meta_item.content,
+                            meta_item.request.path.id == library_item.id,
+                        ) {
+                            (Some(Loadable::Ready(content)), true) => {
+                                Some((meta_item.request.to_owned(), content))
+                            }
+                            _ => None,
+                        }
+                    })
+                })
+                .map(|(request, meta_item)| (library_item, request, meta_item))
+        })
+        .flat_map(|(library_item, request, meta_item)| {
+            meta_item
+                .videos_iter()
+                .filter_map(|video| {
+                    let date = selected.local_date(video.released.as_ref()?);
+                    (selected.from <= date && date <= selected.to)
+                        .then_some(date)
+                        .map(|date| {
+                            (
+                                date,
+                                CalendarItem {
+                                    meta_id: library_item.id.to_owned(),
+                                    name: library_item.name.to_owned(),
+                                    poster: library_item.poster.to_owned(),
+                                    video: video.to_owned(),
+                                    request: request.to_owned(),
+                                },
+                            )
+                        })
+                })
+                .collect::<Vec<_>>()
+        })
+        .fold(
+            BTreeMap::<NaiveDate, Vec<CalendarItem>>::new(),
+            |mut days, (date, item)| {
+                days.entry(date).or_default().push(item);
+                days
+            },
+        )
+        .into_iter()
+        .map(|(date, items)| CalendarDay {
+            date,
+            items: items
+                .into_iter()
+                .sorted_by(|a, b| {
+                    a.video
+                        .released
+                        .cmp(&b.video.released)
+                        .then_with(|| a.name.cmp(&b.name))
+                        .then_with(|| a.video.id.cmp(&b.video.id))
+                })
+                .collect(),
+        })
+        .collect::<Vec<_>>();
+    eq_update(days, next_days)
+}
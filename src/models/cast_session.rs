@@ -0,0 +1,370 @@
+use chrono::{DateTime, Utc};
+use futures::{FutureExt, TryFutureExt};
+use http::Request;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::{
+    constants::WATCHED_THRESHOLD_COEF,
+    models::{
+        common::{eq_update, resource_update, Loadable, ResourceAction, ResourceLoadable},
+        ctx::Ctx,
+        player::{library_item_update as player_library_item_update, push_to_library},
+    },
+    runtime::{
+        msg::{Action, ActionCastSession, ActionLoad, Event, Internal, Msg},
+        Effect, EffectFuture, Effects, Env, EnvError, EnvFutureExt, UpdateWithCtx,
+    },
+    types::{
+        addon::ResourceRequest,
+        library::LibraryItem,
+        resource::{MetaItem, Stream},
+    },
+};
+
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Selected {
+    /// The [`PlaybackDevice`] id.
+    ///
+    /// [`PlaybackDevice`]: crate::models::streaming_server::PlaybackDevice
+    pub device: String,
+    pub stream: Stream,
+    /// The url the device plays the stream from, usually the streaming url of the stream.
+    pub source: Url,
+    /// The meta item of the [`LibraryItem`] which progress is updated while casting,
+    /// the item is created from the meta item if it's not in the library.
+    pub meta_request: Option<ResourceRequest>,
+    pub video_id: Option<String>,
+    /// Where the playback starts, in milliseconds.
+    pub time: Option<u64>,
+}
+
+/// The playback status reported by the device.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CastStatus {
+    /// In milliseconds
+    pub time: u64,
+    /// In milliseconds
+    pub duration: u64,
+    pub paused: bool,
+}
+
+/// Playback of a stream on a device found by the streaming server.
+///
+/// The session outlives the screen it was started from, it ends with
+/// [`ActionCastSession::Stop`] or when the device stops playing.
+/// The device status is polled on every [`ActionCastSession::Refresh`].
+#[derive(Default, Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CastSession {
+    pub selected: Option<Selected>,
+    pub status: Option<Loadable<CastStatus, EnvError>>,
+    pub meta_item: Option<ResourceLoadable<MetaItem>>,
+    #[serde(skip_serializing)]
+    pub library_item: Option<LibraryItem>,
+    #[serde(skip_serializing)]
+    pub status_time: Option<DateTime<Utc>>,
+    #[serde(skip_serializing)]
+    pub push_library_item_time: DateTime<Utc>,
+}
+
+impl<E: Env + 'static> UpdateWithCtx<E> for CastSession {
+    fn update(&mut self, msg: &Msg, ctx: &Ctx) -> Effects {
+        match msg {
+            Msg::Action(Action::Load(ActionLoad::CastSession(selected))) => {
+                let stop_effects = stop_update(
+                    &mut self.selected,
+                    &mut self.status,
+                    &mut self.meta_item,
+                    &mut self.library_item,
+                );
+                let selected_effects = eq_update(&mut self.selected, Some(selected.to_owned()));
+                let status_effects = eq_update(&mut self.status, Some(Loadable::Loading));
+                let meta_item_effects = match &selected.meta_request {
+                    Some(meta_request) => {
+                        let mut meta_item = ResourceLoadable {
+                            request: meta_request.to_owned(),
+                            content: None,
+                        };
+                        let meta_item_effects = resource_update::<E, _>(
+                            &mut meta_item,
+                            ResourceAction::ResourceRequested {
+                                request: meta_request,
+                            },
+                        );
+                        self.meta_item = Some(meta_item);
+                        meta_item_effects
+                    }
+                    None => eq_update(&mut self.meta_item, None),
+                };
+                let library_item_effects = player_library_item_update::<E>(
+                    &mut self.library_item,
+                    selected.meta_request.as_ref(),
+                    &self.meta_item,
+                    &ctx.library,
+                );
+                self.status_time = None;
+                self.push_library_item_time = E::now();
+                Effects::one(play_on_device::<E>(
+                    &ctx.streaming_server.url,
+                    &selected.device,
+                    Command {
+                        source: Some(selected.source.to_owned()),
+                        time: Some(selected.time.unwrap_or(0)),
+                        ..Default::default()
+                    },
+                ))
+                .unchanged()
+                .join(stop_effects)
+                .join(selected_effects)
+                .join(status_effects)
+                .join(meta_item_effects)
+                .join(library_item_effects)
+            }
+            Msg::Action(Action::CastSession(ActionCastSession::SetPaused(paused))) => {
+                match (&self.selected, &mut self.status) {
+                    (Some(selected), Some(Loadable::Ready(status))) => {
+                        status.paused = *paused;
+                        Effects::one(play_on_device::<E>(
+                            &ctx.streaming_server.url,
+                            &selected.device,
+                            Command {
+                                paused: Some(*paused),
+                                ..Default::default()
+                            },
+                        ))
+                    }
+                    _ => Effects::none().unchanged(),
+                }
+            }
+            Msg::Action(Action::CastSession(ActionCastSession::Seek(time))) => {
+                match (&self.selected, &mut self.status) {
+                    (Some(selected), Some(Loadable::Ready(status))) => {
+                        status.time = *time;
+                        Effects::one(play_on_device::<E>(
+                            &ctx.streaming_server.url,
+                            &selected.device,
+                            Command {
+                                time: Some(*time),
+                                ..Default::default()
+                            },
+                        ))
+                    }
+                    _ => Effects::none().unchanged(),
+                }
+            }
+            Msg::Action(Action::CastSession(ActionCastSession::Stop)) => match &self.selected {
+                Some(selected) => Effects::one(play_on_device::<E>(
+                    &ctx.streaming_server.url,
+                    &selected.device,
+                    Command {
+                        stop: Some(true),
+                        ..Default::default()
+                    },
+                ))
+                .unchanged()
+                .join(stop_update(
+                    &mut self.selected,
+                    &mut self.status,
+                    &mut self.meta_item,
+                    &mut self.library_item,
+                )),
+                _ => Effects::none().unchanged(),
+            },
+            Msg::Action(Action::CastSession(ActionCastSession::Refresh)) => match &self.selected {
+                Some(selected) => {
+                    Effects::one(get_status::<E>(&ctx.streaming_server.url, &selected.device))
+                        .unchanged()
+                }
+                _ => Effects::none().unchanged(),
+            },
+            Msg::Internal(Internal::ResourceRequestResult(request, result)) => {
+                let meta_item_effects = match &mut self.meta_item {
+                    Some(meta_item) => resource_update::<E, _>(
+                        meta_item,
+                        ResourceAction::ResourceRequestResult { request, result },
+                    ),
+                    None => Effects::none().unchanged(),
+                };
+                let library_item_effects = if meta_item_effects.has_changed {
+                    player_library_item_update::<E>(
+                        &mut self.library_item,
+                        self.selected
+                            .as_ref()
+                            .and_then(|selected| selected.meta_request.as_ref()),
+                        &self.meta_item,
+                        &ctx.library,
+                    )
+                } else {
+                    Effects::none().unchanged()
+                };
+                meta_item_effects.join(library_item_effects)
+            }
+            Msg::Internal(Internal::CastSessionCommandResult(device, result))
+                if self.selected.as_ref().map(|selected| &selected.device) == Some(device) =>
+            {
+                match (result, &self.status) {
+                    (Ok(_), Some(Loadable::Loading)) => Effects::many(vec![
+                        get_status::<E>(&ctx.streaming_server.url, device),
+                        Effect::Msg(Box::new(Msg::Event(Event::PlayingOnDevice {
+                            device: device.to_owned(),
+                        }))),
+                    ])
+                    .unchanged(),
+                    (Err(error), _) => {
+                        eq_update(&mut self.status, Some(Loadable::Err(error.to_owned())))
+                    }
+                    _ => Effects::none().unchanged(),
+                }
+            }
+            Msg::Internal(Internal::CastSessionStatusResult(device, result))
+                if self.selected.as_ref().map(|selected| &selected.device) == Some(device) =>
+            {
+                match result {
+                    Ok(Some(status)) => {
+                        let library_item_effects = match (&self.selected, &mut self.library_item) {
+                            (Some(selected), Some(library_item)) => library_item_update::<E>(
+                                library_item,
+                                &mut self.status_time,
+                                &mut self.push_library_item_time,
+                                selected,
+                                status,
+                            ),
+                            _ => Effects::none().unchanged(),
+                        };
+                        eq_update(&mut self.status, Some(Loadable::Ready(status.to_owned())))
+                            .join(library_item_effects)
+                    }
+                    // the device is not playing anymore
+                    Ok(None) => stop_update(
+                        &mut self.selected,
+                        &mut self.status,
+                        &mut self.meta_item,
+                        &mut self.library_item,
+                    ),
+                    Err(error) => {
+                        eq_update(&mut self.status, Some(Loadable::Err(error.to_owned())))
+                    }
+                }
+            }
+            _ => Effects::none().unchanged(),
+        }
+    }
+}
+
+/// Ends the session, saving the progress of the [`LibraryItem`].
+fn stop_update(
+    selected: &mut Option<Selected>,
+    status: &mut Option<Loadable<CastStatus, EnvError>>,
+    meta_item: &mut Option<ResourceLoadable<MetaItem>>,
+    library_item: &mut Option<LibraryItem>,
+) -> Effects {
+    let device = match selected.take() {
+        Some(selected) => selected.device,
+        None => return Effects::none().unchanged(),
+    };
+    let library_item_effects = match library_item.take() {
+        Some(library_item) => {
+            Effects::msg(Msg::Internal(Internal::UpdateLibraryItem(library_item))).unchanged()
+        }
+        None => Effects::none().unchanged(),
+    };
+    *status = None;
+    *meta_item = None;
+    Effects::msg(Msg::Event(Event::CastSessionStopped { device })).join(library_item_effects)
+}
+
+/// Updates the progress of the [`LibraryItem`] with the position reported by the device.
+fn library_item_update<E: Env + 'static>(
+    library_item: &mut LibraryItem,
+    status_time: &mut Option<DateTime<Utc>>,
+    push_library_item_time: &mut DateTime<Utc>,
+    selected: &Selected,
+    status: &CastStatus,
+) -> Effects {
+    let now = E::now();
+    // only the time passed since the previous status can be counted as watched
+    let elapsed = status_time
+        .replace(now)
+        .map(|status_time| (now - status_time).num_milliseconds().max(0) as u64)
+        .unwrap_or_default();
+    library_item.state.last_watched = Some(now);
+    if library_item.state.video_id != selected.video_id {
+        library_item.state.video_id = selected.video_id.to_owned();
+        library_item.state.overall_time_watched = library_item
+            .state
+            .overall_time_watched
+            .saturating_add(library_item.state.time_watched);
+        library_item.state.time_watched = 0;
+        library_item.state.flagged_watched = 0;
+    } else {
+        let time_watched = elapsed.min(status.time.saturating_sub(library_item.state.time_offset));
+        library_item.state.time_watched =
+            library_item.state.time_watched.saturating_add(time_watched);
+        library_item.state.overall_time_watched = library_item
+            .state
+            .overall_time_watched
+            .saturating_add(time_watched);
+    };
+    library_item.state.time_offset = status.time;
+    library_item.state.duration = status.duration;
+    if library_item.state.flagged_watched == 0
+        && library_item.state.time_watched as f64
+            > library_item.state.duration as f64 * WATCHED_THRESHOLD_COEF
+    {
+        library_item.state.flagged_watched = 1;
+        library_item.state.times_watched = library_item.state.times_watched.saturating_add(1);
+    };
+    push_to_library::<E>(push_library_item_time, library_item)
+}
+
+/// The body of the requests to the device player endpoint, only the given fields are changed.
+#[derive(Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Command {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<Url>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    paused: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop: Option<bool>,
+}
+
+fn play_on_device<E: Env + 'static>(url: &Url, device: &str, command: Command) -> Effect {
+    let device = device.to_owned();
+    let endpoint = url
+        .join(&format!("casting/{device}/player"))
+        .expect("url builder failed");
+    let request = Request::post(endpoint.as_str())
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .body(command)
+        .expect("request builder failed");
+    EffectFuture::Concurrent(
+        E::fetch::<_, serde_json::Value>(request)
+            .map_ok(|_| ())
+            .map(move |result| Msg::Internal(Internal::CastSessionCommandResult(device, result)))
+            .boxed_env(),
+    )
+    .into()
+}
+
+fn get_status<E: Env + 'static>(url: &Url, device: &str) -> Effect {
+    let device = device.to_owned();
+    let endpoint = url
+        .join(&format!("casting/{device}/player"))
+        .expect("url builder failed");
+    let request = Request::get(endpoint.as_str())
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .body(())
+        .expect("request builder failed");
+    EffectFuture::Concurrent(
+        E::fetch::<_, Option<CastStatus>>(request)
+            .map(move |result| Msg::Internal(Internal::CastSessionStatusResult(device, result)))
+            .boxed_env(),
+    )
+    .into()
+}
@@ -1,23 +1,35 @@
-use crate::constants::LIBRARY_COLLECTION_NAME;
-use crate::models::common::{DescriptorLoadable, Loadable, ResourceLoadable};
+use crate::constants::{
+    DISMISSED_EVENTS_STORAGE_KEY, INTRO_OUTRO_STORAGE_KEY, LIBRARY_COLLECTION_NAME,
+    LIBRARY_RECENT_STORAGE_KEY, LIBRARY_STORAGE_KEY, NOTIFICATIONS_STORAGE_KEY,
+    PROFILE_STORAGE_KEY, SCHEMA_VERSION, SCHEMA_VERSION_STORAGE_KEY, SEARCH_HISTORY_STORAGE_KEY,
+    STREAMS_STORAGE_KEY,
+};
+use crate::models::common::{eq_update, DescriptorLoadable, Loadable, ResourceLoadable};
 use crate::models::ctx::{
-    update_events, update_library, update_notifications, update_profile, update_search_history,
-    update_streams, update_trakt_addon, CtxError,
+    update_events, update_intro_outro, update_library, update_notifications, update_profile,
+    update_search_history, update_streams, update_trakt_addon, CtxError,
+};
+use crate::runtime::msg::{
+    Action, ActionCtx, CtxAuthResponse, DataArchiveImportResponse, Event, Internal, Msg,
 };
-use crate::runtime::msg::{Action, ActionCtx, CtxAuthResponse, Event, Internal, Msg};
-use crate::runtime::{Effect, EffectFuture, Effects, Env, EnvFutureExt, Update};
+use crate::runtime::{Effect, EffectFuture, Effects, Env, EnvError, EnvFutureExt, Update};
 use crate::types::api::{
     fetch_api, APIRequest, APIResult, AuthRequest, AuthResponse, CollectionResponse,
     DatastoreCommand, DatastoreRequest, LibraryItemsResponse, SuccessResponse,
 };
+use crate::types::data_archive::DataArchive;
 use crate::types::events::{DismissedEventsBucket, Events};
+use crate::types::intro_outro::IntroOutroBucket;
 use crate::types::library::LibraryBucket;
 use crate::types::notifications::NotificationsBucket;
 use crate::types::profile::{Auth, AuthKey, Profile};
 use crate::types::resource::MetaItem;
 use crate::types::search_history::SearchHistoryBucket;
+use crate::types::streaming_server::ActiveServer;
 use crate::types::streams::StreamsBucket;
 
+#[cfg(test)]
+use crate::constants::STREAMING_SERVER_URL;
 #[cfg(test)]
 use derivative::Derivative;
 use enclose::enclose;
@@ -52,6 +64,8 @@ pub struct Ctx {
     #[serde(skip)]
     pub dismissed_events: DismissedEventsBucket,
     #[serde(skip)]
+    pub intro_outro: IntroOutroBucket,
+    #[serde(skip)]
     #[cfg_attr(test, derivative(Default(value = "CtxStatus::Ready")))]
     pub status: CtxStatus,
     #[serde(skip)]
@@ -59,6 +73,15 @@ pub struct Ctx {
     pub trakt_addon: Option<DescriptorLoadable>,
     #[serde(skip)]
     pub notification_catalogs: Vec<ResourceLoadable<Vec<MetaItem>>>,
+    /// The streaming server in use, reported by the [`StreamingServer`] model.
+    ///
+    /// [`StreamingServer`]: crate::models::streaming_server::StreamingServer
+    #[serde(skip)]
+    #[cfg_attr(
+        test,
+        derivative(Default(value = "ActiveServer::new(STREAMING_SERVER_URL.to_owned())"))
+    )]
+    pub streaming_server: ActiveServer,
     pub events: Events,
 }
 
@@ -70,13 +93,16 @@ impl Ctx {
         notifications: NotificationsBucket,
         search_history: SearchHistoryBucket,
         dismissed_events: DismissedEventsBucket,
+        intro_outro: IntroOutroBucket,
     ) -> Self {
         Self {
+            streaming_server: ActiveServer::new(profile.settings.streaming_server_url.to_owned()),
             profile,
             library,
             streams,
             search_history,
             dismissed_events,
+            intro_outro,
             notifications,
             trakt_addon: None,
             notification_catalogs: vec![],
@@ -111,6 +137,8 @@ impl<E: Env + 'static> Update<E> for Ctx {
                     update_search_history::<E>(&mut self.search_history, &self.status, msg);
                 let events_effects =
                     update_events::<E>(&mut self.events, &mut self.dismissed_events, msg);
+                let intro_outro_effects =
+                    update_intro_outro::<E>(&mut self.intro_outro, &self.status, msg);
                 let trakt_addon_effects = update_trakt_addon::<E>(
                     &mut self.trakt_addon,
                     &self.profile,
@@ -134,6 +162,7 @@ impl<E: Env + 'static> Update<E> for Ctx {
                     .join(streams_effects)
                     .join(search_history_effects)
                     .join(events_effects)
+                    .join(intro_outro_effects)
                     .join(trakt_addon_effects)
                     .join(notifications_effects)
             }
@@ -161,6 +190,8 @@ impl<E: Env + 'static> Update<E> for Ctx {
                     update_search_history::<E>(&mut self.search_history, &self.status, msg);
                 let events_effects =
                     update_events::<E>(&mut self.events, &mut self.dismissed_events, msg);
+                let intro_outro_effects =
+                    update_intro_outro::<E>(&mut self.intro_outro, &self.status, msg);
                 let ctx_effects = match &self.status {
                     CtxStatus::Loading(loading_auth_request)
                         if loading_auth_request == auth_request =>
@@ -229,8 +260,56 @@ impl<E: Env + 'static> Update<E> for Ctx {
                     .join(notifications_effects)
                     .join(search_history_effects)
                     .join(events_effects)
+                    .join(intro_outro_effects)
                     .join(ctx_effects)
             }
+            Msg::Internal(Internal::ActiveServerChanged(active_server)) => {
+                eq_update(&mut self.streaming_server, active_server.to_owned())
+            }
+            Msg::Action(Action::Ctx(ActionCtx::ImportDataArchive(archive))) => {
+                Effects::one(import_data_archive::<E>(archive.to_owned())).unchanged()
+            }
+            Msg::Internal(Internal::DataArchiveImportResult(result)) => match result {
+                Ok(response) => {
+                    // archives don't contain the auth,
+                    // the imported data is restored for the current session
+                    let uid = self.profile.uid();
+                    self.profile = Profile {
+                        auth: self.profile.auth.to_owned(),
+                        ..response.profile.to_owned()
+                    };
+                    self.library = response.library.to_owned();
+                    self.library.uid = uid.to_owned();
+                    self.streams = response.streams.to_owned();
+                    self.streams.uid = uid.to_owned();
+                    self.search_history = response.search_history.to_owned();
+                    self.search_history.uid = uid.to_owned();
+                    self.notifications = response.notifications.to_owned();
+                    self.notifications.uid = uid.to_owned();
+                    self.dismissed_events = response.dismissed_events.to_owned();
+                    self.dismissed_events.uid = uid.to_owned();
+                    self.intro_outro = IntroOutroBucket::new(uid);
+                    Effects::msg(Msg::Event(Event::DataArchiveImported {
+                        uid: self.profile.uid(),
+                    }))
+                    .join(Effects::msgs(vec![
+                        Msg::Internal(Internal::ProfileChanged),
+                        Msg::Internal(Internal::LibraryChanged(false)),
+                        Msg::Internal(Internal::StreamsChanged(true)),
+                        Msg::Internal(Internal::SearchHistoryChanged),
+                        Msg::Internal(Internal::NotificationsChanged),
+                        Msg::Internal(Internal::DismissedEventsChanged),
+                        Msg::Internal(Internal::IntroOutroChanged),
+                    ]))
+                }
+                Err(error) => Effects::msg(Msg::Event(Event::Error {
+                    error: error.to_owned(),
+                    source: Box::new(Event::DataArchiveImported {
+                        uid: self.profile.uid(),
+                    }),
+                }))
+                .unchanged(),
+            },
             _ => {
                 let profile_effects =
                     update_profile::<E>(&mut self.profile, &mut self.streams, &self.status, msg);
@@ -255,6 +334,8 @@ impl<E: Env + 'static> Update<E> for Ctx {
                     update_search_history::<E>(&mut self.search_history, &self.status, msg);
                 let events_effects =
                     update_events::<E>(&mut self.events, &mut self.dismissed_events, msg);
+                let intro_outro_effects =
+                    update_intro_outro::<E>(&mut self.intro_outro, &self.status, msg);
                 profile_effects
                     .join(library_effects)
                     .join(streams_effects)
@@ -262,6 +343,7 @@ impl<E: Env + 'static> Update<E> for Ctx {
                     .join(notifications_effects)
                     .join(search_history_effects)
                     .join(events_effects)
+                    .join(intro_outro_effects)
             }
         }
     }
@@ -376,3 +458,115 @@ fn delete_session<E: Env + 'static>(auth_key: &AuthKey) -> Effect {
     )
     .into()
 }
+
+/// The storage keys which are overwritten by the import of a [`DataArchive`].
+const DATA_ARCHIVE_STORAGE_KEYS: [&str; 9] = [
+    SCHEMA_VERSION_STORAGE_KEY,
+    PROFILE_STORAGE_KEY,
+    LIBRARY_RECENT_STORAGE_KEY,
+    LIBRARY_STORAGE_KEY,
+    STREAMS_STORAGE_KEY,
+    SEARCH_HISTORY_STORAGE_KEY,
+    NOTIFICATIONS_STORAGE_KEY,
+    DISMISSED_EVENTS_STORAGE_KEY,
+    INTRO_OUTRO_STORAGE_KEY,
+];
+
+/// Writes the archive to the storage, runs the storage migrations on it
+/// and reads back the migrated buckets.
+///
+/// Archives created with a newer schema version are rejected before touching the storage.
+/// The previous values are restored when the archive can't be migrated or read back,
+/// so that the storage is never left with a part of the archive.
+fn import_data_archive<E: Env + 'static>(archive: DataArchive) -> Effect {
+    EffectFuture::Sequential(
+        async move {
+            if archive.schema_version > SCHEMA_VERSION {
+                return Err(EnvError::StorageSchemaVersionDowngrade(
+                    archive.schema_version,
+                    SCHEMA_VERSION,
+                ));
+            }
+
+            let snapshot = future::try_join_all(
+                DATA_ARCHIVE_STORAGE_KEYS
+                    .iter()
+                    .map(|key| E::get_storage::<serde_json::Value>(key)),
+            )
+            .await?;
+            let result: Result<_, EnvError> = async {
+                future::try_join_all(vec![
+                    E::set_storage(SCHEMA_VERSION_STORAGE_KEY, Some(&archive.schema_version)),
+                    E::set_storage(PROFILE_STORAGE_KEY, Some(&archive.profile)),
+                    E::set_storage(LIBRARY_RECENT_STORAGE_KEY, Some(&archive.library)),
+                    E::set_storage::<()>(LIBRARY_STORAGE_KEY, None),
+                    E::set_storage(STREAMS_STORAGE_KEY, Some(&archive.streams)),
+                    E::set_storage(SEARCH_HISTORY_STORAGE_KEY, Some(&archive.search_history)),
+                    E::set_storage(NOTIFICATIONS_STORAGE_KEY, Some(&archive.notifications)),
+                    E::set_storage(
+                        DISMISSED_EVENTS_STORAGE_KEY,
+                        Some(&archive.dismissed_events),
+                    ),
+                    // the intro and outro records are not archived
+                    E::set_storage::<()>(INTRO_OUTRO_STORAGE_KEY, None),
+                ])
+                .await?;
+                E::migrate_storage_schema().await?;
+
+                let profile = E::get_storage::<Profile>(PROFILE_STORAGE_KEY)
+                    .await?
+                    .unwrap_or_default();
+                let mut library = E::get_storage::<LibraryBucket>(LIBRARY_RECENT_STORAGE_KEY)
+                    .await?
+                    .unwrap_or_else(|| LibraryBucket::new(profile.uid(), vec![]));
+                if let Some(other_library) =
+                    E::get_storage::<LibraryBucket>(LIBRARY_STORAGE_KEY).await?
+                {
+                    library.merge_bucket(other_library);
+                }
+                let streams = E::get_storage::<StreamsBucket>(STREAMS_STORAGE_KEY)
+                    .await?
+                    .unwrap_or_else(|| StreamsBucket::new(profile.uid()));
+                let search_history =
+                    E::get_storage::<SearchHistoryBucket>(SEARCH_HISTORY_STORAGE_KEY)
+                        .await?
+                        .unwrap_or_else(|| SearchHistoryBucket::new(profile.uid()));
+                let notifications =
+                    E::get_storage::<NotificationsBucket>(NOTIFICATIONS_STORAGE_KEY)
+                        .await?
+                        .unwrap_or_else(|| NotificationsBucket::new::<E>(profile.uid(), vec![]));
+                let dismissed_events =
+                    E::get_storage::<DismissedEventsBucket>(DISMISSED_EVENTS_STORAGE_KEY)
+                        .await?
+                        .unwrap_or_else(|| DismissedEventsBucket::new(profile.uid()));
+
+                Ok(Box::new(DataArchiveImportResponse {
+                    profile,
+                    library,
+                    streams,
+                    search_history,
+                    notifications,
+                    dismissed_events,
+                }))
+            }
+            .await;
+            if result.is_err() {
+                future::try_join_all(
+                    DATA_ARCHIVE_STORAGE_KEYS
+                        .iter()
+                        .zip(snapshot.iter())
+                        .map(|(key, value)| E::set_storage(key, value.as_ref())),
+                )
+                .await?;
+            }
+            result
+        }
+        .map(|result| {
+            Msg::Internal(Internal::DataArchiveImportResult(
+                result.map_err(CtxError::from),
+            ))
+        })
+        .boxed_env(),
+    )
+    .into()
+}
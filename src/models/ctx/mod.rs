@@ -1,6 +1,9 @@
 mod update_events;
 use update_events::*;
 
+mod update_intro_outro;
+use update_intro_outro::*;
+
 mod update_library;
 use update_library::*;
 
@@ -0,0 +1,79 @@
+use enclose::enclose;
+use futures::FutureExt;
+
+use crate::constants::INTRO_OUTRO_STORAGE_KEY;
+use crate::models::ctx::{CtxError, CtxStatus};
+use crate::runtime::msg::{Action, ActionCtx, CtxAuthResponse, Event, Internal, Msg};
+use crate::runtime::{Effect, EffectFuture, Effects, Env, EnvFutureExt};
+use crate::types::intro_outro::IntroOutroBucket;
+
+pub fn update_intro_outro<E: Env + 'static>(
+    intro_outro: &mut IntroOutroBucket,
+    status: &CtxStatus,
+    msg: &Msg,
+) -> Effects {
+    match msg {
+        Msg::Action(Action::Ctx(ActionCtx::Logout)) | Msg::Internal(Internal::Logout) => {
+            let next_intro_outro = IntroOutroBucket::default();
+            if *intro_outro != next_intro_outro {
+                *intro_outro = next_intro_outro;
+                Effects::msg(Msg::Internal(Internal::IntroOutroChanged))
+            } else {
+                Effects::none().unchanged()
+            }
+        }
+        Msg::Internal(Internal::IntroOutroRecorded {
+            meta_id,
+            video_id,
+            season,
+            duration,
+            seek_history,
+            outro,
+        }) => {
+            if intro_outro.record(
+                meta_id,
+                video_id,
+                *season,
+                *duration,
+                seek_history,
+                *outro,
+                E::now(),
+            ) {
+                Effects::msg(Msg::Internal(Internal::IntroOutroChanged))
+            } else {
+                Effects::none().unchanged()
+            }
+        }
+        Msg::Internal(Internal::CtxAuthResult(auth_request, result)) => match (status, result) {
+            (CtxStatus::Loading(loading_auth_request), Ok(CtxAuthResponse { auth, .. }))
+                if loading_auth_request == auth_request =>
+            {
+                let next_intro_outro = IntroOutroBucket::new(Some(auth.user.id.to_owned()));
+                *intro_outro = next_intro_outro;
+                Effects::msg(Msg::Internal(Internal::IntroOutroChanged))
+            }
+            _ => Effects::none().unchanged(),
+        },
+        Msg::Internal(Internal::IntroOutroChanged) => {
+            Effects::one(push_intro_outro_to_storage::<E>(intro_outro)).unchanged()
+        }
+        _ => Effects::none().unchanged(),
+    }
+}
+
+fn push_intro_outro_to_storage<E: Env + 'static>(intro_outro: &IntroOutroBucket) -> Effect {
+    EffectFuture::Sequential(
+        E::set_storage(INTRO_OUTRO_STORAGE_KEY, Some(&intro_outro))
+            .map(
+                enclose!((intro_outro.uid => uid) move |result| match result {
+                    Ok(_) => Msg::Event(Event::IntroOutroPushedToStorage { uid }),
+                    Err(error) => Msg::Event(Event::Error {
+                        error: CtxError::from(error),
+                        source: Box::new(Event::IntroOutroPushedToStorage { uid }),
+                    })
+                }),
+            )
+            .boxed_env(),
+    )
+    .into()
+}
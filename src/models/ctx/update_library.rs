@@ -132,6 +132,28 @@ pub fn update_library<E: Env + 'static>(
                 .unchanged(),
             }
         }
+        Msg::Action(Action::Ctx(ActionCtx::UpdateLibraryItemNotificationPreferences(
+            id,
+            notif_prefs,
+        ))) => match library.items.get(id) {
+            Some(library_item) => {
+                let mut library_item = library_item.to_owned();
+                library_item.state.notif_prefs = notif_prefs.to_owned();
+
+                Effects::msg(Msg::Internal(Internal::UpdateLibraryItem(library_item)))
+                    .join(Effects::msg(Msg::Event(
+                        Event::LibraryItemNotificationPreferencesUpdated { id: id.to_owned() },
+                    )))
+                    .unchanged()
+            }
+            _ => Effects::msg(Msg::Event(Event::Error {
+                error: CtxError::from(OtherError::LibraryItemNotFound),
+                source: Box::new(Event::LibraryItemNotificationPreferencesUpdated {
+                    id: id.to_owned(),
+                }),
+            }))
+            .unchanged(),
+        },
         Msg::Action(Action::Ctx(ActionCtx::SyncLibraryWithAPI)) => match auth_key {
             Some(auth_key) => Effects::one(plan_sync_with_api::<E>(library, auth_key)).unchanged(),
             _ => Effects::msg(Msg::Event(Event::Error {
@@ -171,6 +193,42 @@ pub fn update_library<E: Env + 'static>(
                 .join(push_to_storage_effects)
                 .join(Effects::msg(Msg::Internal(Internal::LibraryChanged(true))))
         }
+        Msg::Internal(Internal::UpdateLibraryItems(library_items)) => {
+            let library_items = library_items
+                .iter()
+                .filter(|library_item| {
+                    library
+                        .items
+                        .get(&library_item.id)
+                        .map(|prev_library_item| !library_item.eq_no_mtime(prev_library_item))
+                        .unwrap_or(true)
+                })
+                .map(|library_item| LibraryItem {
+                    mtime: E::now(),
+                    ..library_item.to_owned()
+                })
+                .collect::<Vec<_>>();
+            if library_items.is_empty() {
+                return Effects::none().unchanged();
+            }
+
+            let push_to_api_effects = match auth_key {
+                Some(auth_key) => {
+                    Effects::one(push_items_to_api::<E>(library_items.to_owned(), auth_key))
+                        .unchanged()
+                }
+                _ => Effects::none().unchanged(),
+            };
+
+            let push_to_storage_effects = Effects::one(update_and_push_items_to_storage::<E>(
+                library,
+                library_items,
+            ));
+
+            push_to_api_effects
+                .join(push_to_storage_effects)
+                .join(Effects::msg(Msg::Internal(Internal::LibraryChanged(true))))
+        }
         Msg::Internal(Internal::LibraryChanged(persisted)) if !persisted => {
             Effects::one(push_library_to_storage::<E>(library)).unchanged()
         }
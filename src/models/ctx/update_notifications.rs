@@ -1,4 +1,4 @@
-use std::collections::{hash_map::Entry, HashMap};
+use std::collections::{hash_map::Entry, HashMap, HashSet};
 
 use chrono::{DateTime, Duration, Utc};
 use futures::FutureExt;
@@ -22,7 +22,7 @@ use crate::{
     types::{
         addon::{AggrRequest, ExtraType},
         library::LibraryBucket,
-        notifications::{NotificationItem, NotificationsBucket},
+        notifications::{NotificationDigest, NotificationItem, NotificationsBucket},
         profile::Profile,
         resource::{MetaItem, MetaItemId, VideoId},
     },
@@ -74,7 +74,7 @@ pub fn update_notifications<E: Env + 'static>(
             let sorted_library_items_id_types = library
                 .items
                 .values()
-                .filter(|library_item| library_item.should_pull_notifications())
+                .filter(|library_item| library_item.should_pull_notifications::<E>())
                 .sorted_by(|a, b| b.mtime.cmp(&a.mtime))
                 .map(|library_item| (library_item.id.to_owned(), library_item.r#type.to_owned()))
                 .collect::<Vec<_>>();
@@ -112,10 +112,21 @@ pub fn update_notifications<E: Env + 'static>(
                 library,
             );
 
+            // digests held back during the quiet hours are delivered on the next pull
+            let notification_digests_effects =
+                deliver_notification_digests::<E>(notifications, library, profile);
+            let notifications_effects = if notification_digests_effects.has_changed {
+                Effects::msg(Msg::Internal(Internal::NotificationsChanged))
+            } else {
+                Effects::none().unchanged()
+            };
+
             // because notifications are getting loaded by forcing new requests
             // we do not trigger a `NotificationsChanged` as the addons should return results first.
             notifications_catalog_resource_effects
                 .join(notification_items_effects)
+                .join(notification_digests_effects)
+                .join(notifications_effects)
                 .unchanged()
         }
         Msg::Action(Action::Ctx(ActionCtx::DismissNotificationItem(id))) => Effects::msg(
@@ -172,13 +183,21 @@ pub fn update_notifications<E: Env + 'static>(
                 Effects::none().unchanged()
             };
 
-            let notifications_effects = if notification_items_effects.has_changed {
+            let notification_digests_effects = if notification_items_effects.has_changed {
+                deliver_notification_digests::<E>(notifications, library, profile)
+            } else {
+                Effects::none().unchanged()
+            };
+            let notifications_effects = if notification_items_effects.has_changed
+                || notification_digests_effects.has_changed
+            {
                 Effects::msg(Msg::Internal(Internal::NotificationsChanged))
             } else {
                 Effects::none().unchanged()
             };
             notification_catalogs_effects
                 .join(notification_items_effects)
+                .join(notification_digests_effects)
                 .join(notifications_effects)
         }
         Msg::Internal(Internal::DismissNotificationItem(id)) => {
@@ -236,7 +255,7 @@ fn update_notification_items<E: Env + 'static>(
             .iter()
             .fold(HashMap::new(), |mut map, (meta_id, library_item)| {
                 // Exit early if we don't need to pull notifications for the library item
-                if !library_item.should_pull_notifications() {
+                if !library_item.should_pull_notifications::<E>() {
                     return map;
                 }
 
@@ -288,9 +307,26 @@ fn update_notification_items<E: Env + 'static>(
                 let mut meta_notifs: &mut HashMap<_, _> =
                     map.entry(meta_id.to_owned()).or_default();
 
+                // the season and episode of the last watched video
+                let current_series_info = library_item
+                    .state
+                    .video_id
+                    .as_ref()
+                    .and_then(|video_id| {
+                        meta_item.videos.iter().find(|video| &video.id == video_id)
+                    })
+                    .and_then(|video| video.series_info.as_ref());
+
                 // meta items videos
                 meta_item
                     .videos_iter()
+                    .filter(|video| {
+                        library_item.state.notif_prefs.should_notify(
+                            video,
+                            current_series_info,
+                            &meta_item.videos,
+                        )
+                    })
                     .filter_map(
                         |video| match (&library_item.state.last_watched, video.released) {
                             (Some(last_watched), Some(video_released)) => {
@@ -343,6 +379,58 @@ fn update_notification_items<E: Env + 'static>(
     eq_update(notification_items, next_notification_items)
 }
 
+/// Emits the pending [`NotificationDigest`]s unless we are in the quiet hours.
+///
+/// The returned effects are changed only if the digests were delivered,
+/// in which case the bucket should be persisted.
+fn deliver_notification_digests<E: Env + 'static>(
+    notifications: &mut NotificationsBucket,
+    library: &LibraryBucket,
+    profile: &Profile,
+) -> Effects {
+    let now = E::now();
+    let is_quiet = profile
+        .settings
+        .notifications_quiet_hours
+        .map(|quiet_hours| quiet_hours.contains(&now))
+        .unwrap_or_default();
+    if is_quiet {
+        return Effects::none().unchanged();
+    }
+
+    let digests = NotificationDigest::pending(notifications, library);
+    if digests.is_empty() {
+        return Effects::none().unchanged();
+    }
+
+    notifications.last_delivered = Some(now);
+    // only keep track of the videos for which we still have a notification
+    notifications.delivered = notifications
+        .items
+        .iter()
+        .map(|(meta_id, meta_notifications)| {
+            let delivered = meta_notifications
+                .keys()
+                .filter(|video_id| {
+                    notifications
+                        .delivered
+                        .get(meta_id)
+                        .map(|delivered| delivered.contains(*video_id))
+                        .unwrap_or_default()
+                        || digests.iter().any(|digest| {
+                            &digest.meta_id == meta_id
+                                && digest.items.iter().any(|item| &item.video_id == *video_id)
+                        })
+                })
+                .cloned()
+                .collect::<HashSet<_>>();
+            (meta_id.to_owned(), delivered)
+        })
+        .filter(|(_, delivered)| !delivered.is_empty())
+        .collect();
+    Effects::msg(Msg::Event(Event::NotificationDigestsDue { digests }))
+}
+
 fn push_notifications_to_storage<E: Env + 'static>(notifications: &NotificationsBucket) -> Effect {
     let ids = notifications.items.keys().cloned().collect();
     EffectFuture::Sequential(
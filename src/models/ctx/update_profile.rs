@@ -222,7 +222,19 @@ pub fn update_profile<E: Env + 'static>(
             .unchanged(),
         },
         Msg::Action(Action::Ctx(ActionCtx::UpdateSettings(settings))) => {
-            if profile.settings != *settings {
+            let quiet_hours_valid = settings
+                .notifications_quiet_hours
+                .map(|quiet_hours| quiet_hours.is_valid())
+                .unwrap_or(true);
+            if !quiet_hours_valid {
+                Effects::msg(Msg::Event(Event::Error {
+                    error: CtxError::from(OtherError::InvalidQuietHours),
+                    source: Box::new(Event::SettingsUpdated {
+                        settings: settings.to_owned(),
+                    }),
+                }))
+                .unchanged()
+            } else if profile.settings != *settings {
                 profile.settings = settings.to_owned();
                 Effects::msg(Msg::Event(Event::SettingsUpdated {
                     settings: settings.to_owned(),
@@ -6,12 +6,13 @@ use url::Url;
 use crate::{
     constants::URI_COMPONENT_ENCODE_SET,
     runtime::{
-        msg::{Action, ActionLoad, Internal, Msg},
+        msg::{Action, ActionDataExport, ActionLoad, Internal, Msg},
         Effect, EffectFuture, Effects, Env, EnvFutureExt, UpdateWithCtx,
     },
     types::{
         api::{fetch_api, APIRequest, APIResult, DataExportResponse},
-        profile::AuthKey,
+        data_archive::DataArchive,
+        profile::{AuthKey, UID},
     },
 };
 
@@ -25,6 +26,8 @@ use super::{
 pub struct DataExport {
     /// This is the Loading result of the User data export request.
     pub export_url: Option<(AuthKey, Loadable<Url, CtxError>)>,
+    /// Local archive of the user data, available for anonymous users as well.
+    pub archive: Option<(UID, DataArchive)>,
 }
 
 impl<E: Env + 'static> UpdateWithCtx<E> for DataExport {
@@ -50,6 +53,18 @@ impl<E: Env + 'static> UpdateWithCtx<E> for DataExport {
                     }
                 }
             }
+            Msg::Action(Action::DataExport(ActionDataExport::CreateArchive)) => {
+                let archive = DataArchive::new::<E>(
+                    &ctx.profile,
+                    &ctx.library,
+                    &ctx.streams,
+                    &ctx.search_history,
+                    &ctx.notifications,
+                    &ctx.dismissed_events,
+                );
+                eq_update(&mut self.archive, Some((ctx.profile.uid(), archive)))
+            }
+            Msg::Action(Action::Unload) => eq_update(&mut self.archive, None),
             Msg::Internal(Internal::DataExportResult(auth_key, result)) => match self.export_url {
                 Some((ref loading_auth_key, Loadable::Loading)) if loading_auth_key == auth_key => {
                     match result {
@@ -75,14 +90,21 @@ impl<E: Env + 'static> UpdateWithCtx<E> for DataExport {
                 _ => Effects::none().unchanged(),
             },
             Msg::Internal(Internal::ProfileChanged) => {
-                match (self.export_url.as_ref(), ctx.profile.auth_key()) {
+                let export_url_effects = match (self.export_url.as_ref(), ctx.profile.auth_key()) {
                     (Some((export_auth_key, _)), profile_auth_key)
                         if Some(export_auth_key) != profile_auth_key =>
                     {
                         eq_update(&mut self.export_url, None)
                     }
                     _ => Effects::none().unchanged(),
-                }
+                };
+                let archive_effects = match &self.archive {
+                    Some((uid, _)) if *uid != ctx.profile.uid() => {
+                        eq_update(&mut self.archive, None)
+                    }
+                    _ => Effects::none().unchanged(),
+                };
+                export_url_effects.join(archive_effects)
             }
             _ => Effects::none().unchanged(),
         }
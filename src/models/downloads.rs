@@ -0,0 +1,319 @@
+use futures::{FutureExt, TryFutureExt};
+use http::Request;
+use serde::Serialize;
+use url::Url;
+
+use crate::{
+    constants::DOWNLOADS_STORAGE_KEY,
+    models::ctx::{Ctx, CtxError},
+    runtime::{
+        msg::{Action, ActionDownloads, Event, Internal, Msg},
+        Effect, EffectFuture, Effects, Env, EnvFutureExt, UpdateWithCtx,
+    },
+    types::{
+        downloads::{DownloadItem, DownloadState, DownloadsBucket},
+        resource::{Stream, StreamSource},
+        streaming_server::Statistics,
+    },
+};
+
+/// Full downloads of streams on the streaming server for offline playback.
+///
+/// The downloads are started one at a time in the order they were queued
+/// and their progress is polled from the statistics endpoint of the server
+/// on every [`ActionDownloads::Refresh`].
+/// Once completed, the download can be played for the linked [`LibraryItem`] without
+/// a connection with the [`Downloads::local_stream`] served by the streaming server.
+///
+/// [`LibraryItem`]: crate::types::library::LibraryItem
+#[derive(Default, Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Downloads {
+    #[serde(flatten)]
+    pub bucket: DownloadsBucket,
+}
+
+impl Downloads {
+    pub fn new(bucket: DownloadsBucket) -> Self {
+        Downloads { bucket }
+    }
+    /// The stream of a completed download, served by the streaming server it was downloaded on.
+    pub fn local_stream(&self, id: &str, ctx: &Ctx) -> Option<Stream> {
+        self.bucket
+            .items
+            .iter()
+            .find(|item| item.id == id && item.state == DownloadState::Completed)
+            .map(|item| local_stream(item_streaming_server_url(item, ctx), item))
+    }
+}
+
+impl<E: Env + 'static> UpdateWithCtx<E> for Downloads {
+    fn update(&mut self, msg: &Msg, ctx: &Ctx) -> Effects {
+        match msg {
+            Msg::Action(Action::Downloads(ActionDownloads::Add {
+                stream,
+                library_item_id,
+                video_id,
+            })) => {
+                let item = match DownloadItem::new(
+                    stream.to_owned(),
+                    library_item_id.to_owned(),
+                    video_id.to_owned(),
+                    E::now(),
+                ) {
+                    Some(item) if !self.bucket.items.iter().any(|other| other.id == item.id) => {
+                        item
+                    }
+                    _ => return Effects::none().unchanged(),
+                };
+                let id = item.id.to_owned();
+                self.bucket.items.push(item);
+                Effects::msg(Msg::Event(Event::DownloadQueued { id }))
+                    .join(next_download_update::<E>(&mut self.bucket, ctx))
+                    .join(Effects::one(push_downloads_to_storage::<E>(&self.bucket)))
+            }
+            Msg::Action(Action::Downloads(ActionDownloads::Refresh)) => {
+                let statistics_effects = Effects::many(
+                    self.bucket
+                        .items
+                        .iter()
+                        .filter(|item| item.is_active())
+                        .map(|item| get_statistics::<E>(item_streaming_server_url(item, ctx), item))
+                        .collect(),
+                )
+                .unchanged();
+                let next_download_effects = next_download_update::<E>(&mut self.bucket, ctx);
+                if next_download_effects.has_changed {
+                    statistics_effects
+                        .join(next_download_effects)
+                        .join(Effects::one(push_downloads_to_storage::<E>(&self.bucket)))
+                } else {
+                    statistics_effects
+                }
+            }
+            Msg::Action(Action::Downloads(ActionDownloads::Retry(id))) => {
+                match self.bucket.items.iter_mut().find(|item| item.id == *id) {
+                    Some(item) if matches!(item.state, DownloadState::Failed { .. }) => {
+                        item.state = DownloadState::Queued;
+                    }
+                    _ => return Effects::none().unchanged(),
+                };
+                next_download_update::<E>(&mut self.bucket, ctx)
+                    .join(Effects::one(push_downloads_to_storage::<E>(&self.bucket)))
+            }
+            Msg::Action(Action::Downloads(ActionDownloads::Remove(id))) => {
+                let items_len = self.bucket.items.len();
+                self.bucket.items.retain(|item| item.id != *id);
+                if self.bucket.items.len() == items_len {
+                    return Effects::none().unchanged();
+                };
+                next_download_update::<E>(&mut self.bucket, ctx)
+                    .join(Effects::one(push_downloads_to_storage::<E>(&self.bucket)))
+            }
+            Msg::Internal(Internal::DownloadStartResult(id, Err(error))) => {
+                match self
+                    .bucket
+                    .items
+                    .iter_mut()
+                    .find(|item| item.id == *id && item.is_active())
+                {
+                    Some(item) => {
+                        item.state = DownloadState::Failed {
+                            error: error.message(),
+                        };
+                    }
+                    None => return Effects::none().unchanged(),
+                };
+                next_download_update::<E>(&mut self.bucket, ctx)
+                    .join(Effects::one(push_downloads_to_storage::<E>(&self.bucket)))
+            }
+            Msg::Internal(Internal::DownloadStatisticsResult(id, result)) => {
+                let item = match self
+                    .bucket
+                    .items
+                    .iter_mut()
+                    .find(|item| item.id == *id && item.is_active())
+                {
+                    Some(item) => item,
+                    None => return Effects::none().unchanged(),
+                };
+                let next_state = match result {
+                    // the whole file has been downloaded
+                    Ok(Some(statistics)) if statistics.stream_progress >= 1.0 => {
+                        DownloadState::Completed
+                    }
+                    Ok(Some(statistics)) => DownloadState::Downloading {
+                        progress: statistics.stream_progress,
+                        downloaded: statistics.downloaded,
+                        download_speed: statistics.download_speed,
+                    },
+                    // e.g. the server was restarted and the download is gone
+                    Ok(None) => DownloadState::Failed {
+                        error: "The download was not found on the streaming server".to_owned(),
+                    },
+                    Err(error) => DownloadState::Failed {
+                        error: error.message(),
+                    },
+                };
+                if item.state == next_state {
+                    return Effects::none().unchanged();
+                };
+                item.state = next_state;
+                // the progress is not persisted, it will be polled again after a restart
+                let completed_effects = match &item.state {
+                    DownloadState::Downloading { .. } => return Effects::none(),
+                    DownloadState::Completed => {
+                        Effects::msg(Msg::Event(Event::DownloadCompleted {
+                            id: item.id.to_owned(),
+                            library_item_id: item.library_item_id.to_owned(),
+                        }))
+                    }
+                    _ => Effects::none(),
+                };
+                completed_effects
+                    .join(next_download_update::<E>(&mut self.bucket, ctx))
+                    .join(Effects::one(push_downloads_to_storage::<E>(&self.bucket)))
+            }
+            _ => Effects::none().unchanged(),
+        }
+    }
+}
+
+/// The server the item is downloaded on, the downloads started before
+/// it was recorded are assumed to be on the [`Ctx::streaming_server`].
+fn item_streaming_server_url<'a>(item: &'a DownloadItem, ctx: &'a Ctx) -> &'a Url {
+    item.streaming_server_url
+        .as_ref()
+        .unwrap_or(&ctx.streaming_server.url)
+}
+
+/// Starts the first queued download on the [`Ctx::streaming_server`] when no other download is active.
+fn next_download_update<E: Env + 'static>(bucket: &mut DownloadsBucket, ctx: &Ctx) -> Effects {
+    if bucket.items.iter().any(|item| item.is_active()) {
+        return Effects::none().unchanged();
+    };
+    match bucket
+        .items
+        .iter_mut()
+        .find(|item| item.state == DownloadState::Queued)
+    {
+        Some(item) => {
+            item.state = DownloadState::Downloading {
+                progress: 0.0,
+                downloaded: 0,
+                download_speed: 0.0,
+            };
+            item.streaming_server_url = Some(ctx.streaming_server.url.to_owned());
+            Effects::one(start_download::<E>(&ctx.streaming_server.url, item))
+        }
+        None => Effects::none().unchanged(),
+    }
+}
+
+/// The path of the downloaded file on the streaming server.
+fn download_path(item: &DownloadItem) -> String {
+    match &item.stream.source {
+        StreamSource::Torrent { .. } => item.id.to_owned(),
+        _ => format!("download/{}", item.id),
+    }
+}
+
+fn local_stream(streaming_server_url: &Url, item: &DownloadItem) -> Stream {
+    let url = streaming_server_url
+        .join(&download_path(item))
+        .expect("url builder failed");
+    Stream {
+        source: StreamSource::Url { url },
+        ..item.stream.to_owned()
+    }
+}
+
+fn start_download<E: Env + 'static>(url: &Url, item: &DownloadItem) -> Effect {
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Torrent {
+        info_hash: String,
+        file_idx: Option<u16>,
+        announce: Vec<String>,
+    }
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Body {
+        id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        torrent: Option<Torrent>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        url: Option<Url>,
+    }
+    let (torrent, source_url) = match &item.stream.source {
+        StreamSource::Torrent {
+            info_hash,
+            file_idx,
+            announce,
+        } => (
+            Some(Torrent {
+                info_hash: hex::encode(info_hash),
+                file_idx: *file_idx,
+                announce: announce.to_owned(),
+            }),
+            None,
+        ),
+        StreamSource::Url { url } => (None, Some(url.to_owned())),
+        _ => (None, None),
+    };
+    let id = item.id.to_owned();
+    let endpoint = url.join("download").expect("url builder failed");
+    let request = Request::post(endpoint.as_str())
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .body(Body {
+            id: id.to_owned(),
+            torrent,
+            url: source_url,
+        })
+        .expect("request builder failed");
+    EffectFuture::Concurrent(
+        E::fetch::<_, serde_json::Value>(request)
+            .map_ok(|_| ())
+            .map(move |result| Msg::Internal(Internal::DownloadStartResult(id, result)))
+            .boxed_env(),
+    )
+    .into()
+}
+
+fn get_statistics<E: Env + 'static>(url: &Url, item: &DownloadItem) -> Effect {
+    let id = item.id.to_owned();
+    let endpoint = url
+        .join(&format!("{}/stats.json", download_path(item)))
+        .expect("url builder failed");
+    let request = Request::get(endpoint.as_str())
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .body(())
+        .expect("request builder failed");
+    EffectFuture::Concurrent(
+        E::fetch::<_, Option<Statistics>>(request)
+            .map(move |result| Msg::Internal(Internal::DownloadStatisticsResult(id, result)))
+            .boxed_env(),
+    )
+    .into()
+}
+
+fn push_downloads_to_storage<E: Env + 'static>(bucket: &DownloadsBucket) -> Effect {
+    let ids = bucket
+        .items
+        .iter()
+        .map(|item| &item.id)
+        .cloned()
+        .collect::<Vec<_>>();
+    EffectFuture::Sequential(
+        E::set_storage(DOWNLOADS_STORAGE_KEY, Some(bucket))
+            .map(move |result| match result {
+                Ok(_) => Msg::Event(Event::DownloadsPushedToStorage { ids }),
+                Err(error) => Msg::Event(Event::Error {
+                    error: CtxError::from(error),
+                    source: Box::new(Event::DownloadsPushedToStorage { ids }),
+                }),
+            })
+            .boxed_env(),
+    )
+    .into()
+}
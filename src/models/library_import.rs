@@ -0,0 +1,290 @@
+use std::marker::PhantomData;
+
+use serde::Serialize;
+
+use crate::{
+    constants::{CATALOG_RESOURCE_NAME, META_RESOURCE_NAME, SEARCH_EXTRA_NAME},
+    models::{
+        common::{
+            eq_update, resources_update, resources_update_with_vector_content, Loadable,
+            ResourceLoadable, ResourcesAction,
+        },
+        ctx::Ctx,
+    },
+    runtime::{
+        msg::{Action, ActionLibraryImport, Event, Internal, Msg},
+        Effects, Env, UpdateWithCtx,
+    },
+    types::{
+        addon::{AggrRequest, ExtraValue, ResourcePath},
+        library::{
+            parse_library_import, LibraryBucket, LibraryImportEntry, LibraryImportError,
+            LibraryImportSource, LibraryItem,
+        },
+        profile::Profile,
+        resource::{MetaItem, MetaItemPreview, Video},
+    },
+};
+
+#[derive(Clone, PartialEq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryImportItem {
+    pub entry: LibraryImportEntry,
+    /// Requests to the meta addons for entries with a known IMDb id.
+    pub meta_items: Vec<ResourceLoadable<MetaItem>>,
+    /// Search requests to the catalog addons for entries without an id.
+    pub search_results: Vec<ResourceLoadable<Vec<MetaItemPreview>>>,
+    /// The [`LibraryItem`] that will be saved on commit,
+    /// already merged with the existing item in the library (if any).
+    ///
+    /// `None` while the entry is being resolved or if it can't be resolved.
+    pub library_item: Option<LibraryItem>,
+}
+
+/// Dry-run of a library import.
+///
+/// Parsing the export file and resolving its entries through the addons
+/// does not modify the library until [`ActionLibraryImport::Commit`] is dispatched.
+#[derive(Default, Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryImport {
+    pub source: Option<LibraryImportSource>,
+    pub items: Option<Loadable<Vec<LibraryImportItem>, LibraryImportError>>,
+}
+
+impl<E: Env + 'static> UpdateWithCtx<E> for LibraryImport {
+    fn update(&mut self, msg: &Msg, ctx: &Ctx) -> Effects {
+        match msg {
+            Msg::Action(Action::LibraryImport(ActionLibraryImport::Preview {
+                source,
+                content,
+            })) => {
+                let source_effects = eq_update(&mut self.source, Some(*source));
+                let (next_items, requests_effects) = match parse_library_import(*source, content) {
+                    Ok(entries) => {
+                        let (items, effects) = entries
+                            .into_iter()
+                            .map(|entry| import_item_request::<E>(entry, &ctx.profile))
+                            .unzip::<_, _, Vec<_>, Vec<_>>();
+                        (
+                            Loadable::Ready(items),
+                            effects
+                                .into_iter()
+                                .fold(Effects::none().unchanged(), Effects::join),
+                        )
+                    }
+                    Err(error) => (Loadable::Err(error), Effects::none().unchanged()),
+                };
+                let items_effects = eq_update(&mut self.items, Some(next_items));
+                source_effects.join(items_effects).join(requests_effects)
+            }
+            Msg::Action(Action::LibraryImport(ActionLibraryImport::Commit)) => {
+                let library_items = match &self.items {
+                    Some(Loadable::Ready(items)) => items
+                        .iter()
+                        .filter_map(|item| item.library_item.as_ref())
+                        .cloned()
+                        .collect::<Vec<_>>(),
+                    _ => return Effects::none().unchanged(),
+                };
+                let ids = library_items
+                    .iter()
+                    .map(|library_item| &library_item.id)
+                    .cloned()
+                    .collect();
+                let source_effects = eq_update(&mut self.source, None);
+                let items_effects = eq_update(&mut self.items, None);
+                Effects::msg(Msg::Internal(Internal::UpdateLibraryItems(library_items)))
+                    .join(Effects::msg(Msg::Event(Event::LibraryItemsImported {
+                        ids,
+                    })))
+                    .unchanged()
+                    .join(source_effects)
+                    .join(items_effects)
+            }
+            Msg::Action(Action::Unload) => {
+                let source_effects = eq_update(&mut self.source, None);
+                let items_effects = eq_update(&mut self.items, None);
+                source_effects.join(items_effects)
+            }
+            Msg::Internal(Internal::ResourceRequestResult(request, result))
+                if request.path.resource == META_RESOURCE_NAME
+                    || request.path.resource == CATALOG_RESOURCE_NAME =>
+            {
+                let items = match &mut self.items {
+                    Some(Loadable::Ready(items)) => items,
+                    _ => return Effects::none().unchanged(),
+                };
+                items
+                    .iter_mut()
+                    .map(|item| {
+                        let resources_effects = if request.path.resource == META_RESOURCE_NAME {
+                            resources_update::<E, _>(
+                                &mut item.meta_items,
+                                ResourcesAction::ResourceRequestResult { request, result },
+                            )
+                        } else {
+                            resources_update_with_vector_content::<E, _>(
+                                &mut item.search_results,
+                                ResourcesAction::ResourceRequestResult { request, result },
+                            )
+                        };
+                        if resources_effects.has_changed {
+                            let library_item_effects = library_item_update::<E>(item, &ctx.library);
+                            resources_effects.join(library_item_effects)
+                        } else {
+                            resources_effects
+                        }
+                    })
+                    .fold(Effects::none().unchanged(), Effects::join)
+            }
+            _ => Effects::none().unchanged(),
+        }
+    }
+}
+
+fn import_item_request<E: Env + 'static>(
+    entry: LibraryImportEntry,
+    profile: &Profile,
+) -> (LibraryImportItem, Effects) {
+    let mut item = LibraryImportItem {
+        entry,
+        meta_items: vec![],
+        search_results: vec![],
+        library_item: None,
+    };
+    let effects = match &item.entry.imdb_id {
+        Some(imdb_id) => resources_update::<E, _>(
+            &mut item.meta_items,
+            ResourcesAction::request(
+                &AggrRequest::AllOfResource(ResourcePath::without_extra(
+                    META_RESOURCE_NAME,
+                    &item.entry.r#type,
+                    imdb_id,
+                )),
+                &profile.addons,
+            ),
+        ),
+        None => resources_update_with_vector_content::<E, _>(
+            &mut item.search_results,
+            ResourcesAction::request(
+                &AggrRequest::AllCatalogs {
+                    extra: &vec![ExtraValue {
+                        name: SEARCH_EXTRA_NAME.to_owned(),
+                        value: item.entry.name.to_owned(),
+                    }],
+                    r#type: &Some(item.entry.r#type.to_owned()),
+                },
+                &profile.addons,
+            ),
+        ),
+    };
+    (item, effects)
+}
+
+fn library_item_update<E: Env + 'static>(
+    item: &mut LibraryImportItem,
+    library: &LibraryBucket,
+) -> Effects {
+    let next_library_item = item
+        .meta_items
+        .iter()
+        .find_map(|meta_item| match &meta_item.content {
+            Some(Loadable::Ready(meta_item)) => {
+                Some((&meta_item.preview, meta_item.videos.as_slice()))
+            }
+            _ => None,
+        })
+        .or_else(|| {
+            item.search_results
+                .iter()
+                .filter_map(|search_result| match &search_result.content {
+                    Some(Loadable::Ready(meta_items)) => Some(meta_items),
+                    _ => None,
+                })
+                .flatten()
+                .find(|meta_item| is_search_match(&item.entry, meta_item))
+                .map(|meta_item| (meta_item, [].as_slice()))
+        })
+        .map(|(meta_item, videos)| {
+            imported_library_item::<E>(
+                &item.entry,
+                meta_item,
+                videos,
+                library.items.get(&meta_item.id),
+            )
+        });
+    eq_update(&mut item.library_item, next_library_item)
+}
+
+fn is_search_match(entry: &LibraryImportEntry, meta_item: &MetaItemPreview) -> bool {
+    let normalize = |name: &str| {
+        name.chars()
+            .filter(|char| char.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect::<String>()
+    };
+    let year_matches = match (entry.year, &meta_item.release_info) {
+        (Some(year), Some(release_info)) => release_info.starts_with(&year.to_string()),
+        _ => true,
+    };
+    meta_item.r#type == entry.r#type
+        && normalize(&meta_item.name) == normalize(&entry.name)
+        && year_matches
+}
+
+fn imported_library_item<E: Env + 'static>(
+    entry: &LibraryImportEntry,
+    meta_item: &MetaItemPreview,
+    videos: &[Video],
+    existing_library_item: Option<&LibraryItem>,
+) -> LibraryItem {
+    let mut library_item = match existing_library_item {
+        Some(library_item) => LibraryItem::from((meta_item, library_item)),
+        None => LibraryItem::from((meta_item, PhantomData::<E>)),
+    };
+    library_item.removed = false;
+    library_item.temp = false;
+    if !entry.watched {
+        return library_item;
+    }
+
+    // a new item is last watched when the entry was, not when it was imported
+    library_item.state.last_watched = existing_library_item
+        .and_then(|library_item| library_item.state.last_watched)
+        .max(entry.last_watched)
+        .or_else(|| Some(E::now()));
+    let watched_videos = videos
+        .iter()
+        .filter(|video| {
+            video
+                .series_info
+                .as_ref()
+                .map(|series_info| entry.episodes.contains(series_info))
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+    if watched_videos.is_empty() {
+        // movies and series for which we only know they've been watched as a whole
+        library_item.state.times_watched = library_item.state.times_watched.max(1);
+        library_item.state.flagged_watched = 1;
+    } else {
+        let mut watched = library_item.state.watched_bitfield(videos);
+        for video in &watched_videos {
+            watched.set_video(&video.id, true);
+        }
+        library_item.state.watched = Some(watched.into());
+        if library_item.state.video_id.is_none() {
+            library_item.state.video_id = watched_videos
+                .iter()
+                .max_by_key(|video| {
+                    video
+                        .series_info
+                        .as_ref()
+                        .map(|series_info| (series_info.season, series_info.episode))
+                })
+                .map(|video| video.id.to_owned());
+        }
+    }
+    library_item
+}
@@ -1,6 +1,6 @@
-use std::{borrow::Cow, marker::PhantomData};
+use std::{borrow::Cow, cmp::Reverse, marker::PhantomData};
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, Serializer};
 
 use stremio_watched_bitfield::WatchedBitField;
 
@@ -22,7 +22,7 @@ use crate::{
         api::{DatastoreCommand, DatastoreRequest},
         library::{LibraryBucket, LibraryItem},
         profile::Profile,
-        resource::{MetaItem, Stream},
+        resource::{MetaItem, ReleaseSource, Stream, StreamMetadata, StreamSource},
         streams::StreamsBucket,
     },
 };
@@ -38,14 +38,84 @@ pub struct Selected {
     pub guess_stream: bool,
 }
 
+/// Why a stream is hidden by the [`StreamPreferences`].
+///
+/// [`StreamPreferences`]: crate::types::profile::StreamPreferences
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Debug)]
+pub enum HiddenStreamReason {
+    /// The stream is larger than the [`StreamPreferences::max_size`].
+    ///
+    /// [`StreamPreferences::max_size`]: crate::types::profile::StreamPreferences::max_size
+    Size,
+    /// The stream is a CAM or a telesync release.
+    Cam,
+    /// The addon of the stream uses P2P.
+    P2P,
+}
+
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SortedStream {
+    pub stream: Stream,
+    pub metadata: StreamMetadata,
+    /// The request of the addon which returned the stream.
+    pub request: ResourceRequest,
+    /// The stream is hidden unless empty.
+    pub hidden_reasons: Vec<HiddenStreamReason>,
+}
+
+/// A [`Stream`] along with its parsed [`StreamMetadata`].
+#[derive(Serialize)]
+struct StreamWithMetadata<'a> {
+    #[serde(flatten)]
+    stream: &'a Stream,
+    metadata: StreamMetadata,
+}
+
+fn serialize_streams_with_metadata<S>(
+    streams: &[ResourceLoadable<Vec<Stream>>],
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.collect_seq(streams.iter().map(|resource| ResourceLoadable {
+        request: resource.request.to_owned(),
+        content: resource.content.as_ref().map(|content| {
+            match content {
+                Loadable::Ready(streams) => Loadable::Ready(
+                    streams
+                        .iter()
+                        .map(|stream| StreamWithMetadata {
+                            stream,
+                            metadata: stream.metadata(),
+                        })
+                        .collect::<Vec<_>>(),
+                ),
+                Loadable::Loading => Loadable::Loading,
+                Loadable::Err(error) => Loadable::Err(error.to_owned()),
+            }
+        }),
+    }))
+}
+
 #[derive(Default, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct MetaDetails {
     pub selected: Option<Selected>,
     pub meta_items: Vec<ResourceLoadable<MetaItem>>,
+    /// Serialized with the [`StreamMetadata`] of every stream.
+    #[serde(serialize_with = "serialize_streams_with_metadata")]
     pub meta_streams: Vec<ResourceLoadable<Vec<Stream>>>,
+    /// Serialized with the [`StreamMetadata`] of every stream.
+    #[serde(serialize_with = "serialize_streams_with_metadata")]
     pub streams: Vec<ResourceLoadable<Vec<Stream>>>,
     pub suggested_stream: Option<ResourceLoadable<Option<Stream>>>,
+    /// The ready streams of all addons, including the [`MetaDetails::meta_streams`],
+    /// sorted and filtered by the [`StreamPreferences`].
+    ///
+    /// [`StreamPreferences`]: crate::types::profile::StreamPreferences
+    pub sorted_streams: Vec<SortedStream>,
     pub library_item: Option<LibraryItem>,
     #[serde(skip_serializing)]
     pub watched: Option<WatchedBitField>,
@@ -72,6 +142,12 @@ impl<E: Env + 'static> UpdateWithCtx<E> for MetaDetails {
                     &self.streams,
                     &ctx.streams,
                 );
+                let sorted_streams_effects = sorted_streams_update(
+                    &mut self.sorted_streams,
+                    &self.meta_streams,
+                    &self.streams,
+                    &ctx.profile,
+                );
                 let library_item_effects = library_item_update::<E>(
                     &mut self.library_item,
                     &self.selected,
@@ -88,6 +164,7 @@ impl<E: Env + 'static> UpdateWithCtx<E> for MetaDetails {
                     .join(meta_streams_effects)
                     .join(streams_effects)
                     .join(suggested_stream_effects)
+                    .join(sorted_streams_effects)
                     .join(library_item_effects)
                     .join(watched_effects)
             }
@@ -98,12 +175,14 @@ impl<E: Env + 'static> UpdateWithCtx<E> for MetaDetails {
                 let streams_effects = eq_update(&mut self.streams, vec![]);
                 let library_item_effects = eq_update(&mut self.library_item, None);
                 let suggested_stream_effects = eq_update(&mut self.suggested_stream, None);
+                let sorted_streams_effects = eq_update(&mut self.sorted_streams, vec![]);
                 let watched_effects = eq_update(&mut self.watched, None);
                 selected_effects
                     .join(meta_items_effects)
                     .join(meta_streams_effects)
                     .join(streams_effects)
                     .join(suggested_stream_effects)
+                    .join(sorted_streams_effects)
                     .join(library_item_effects)
                     .join(watched_effects)
             }
@@ -172,6 +251,12 @@ impl<E: Env + 'static> UpdateWithCtx<E> for MetaDetails {
                     &self.streams,
                     &ctx.streams,
                 );
+                let sorted_streams_effects = sorted_streams_update(
+                    &mut self.sorted_streams,
+                    &self.meta_streams,
+                    &self.streams,
+                    &ctx.profile,
+                );
                 let library_item_effects = library_item_update::<E>(
                     &mut self.library_item,
                     &self.selected,
@@ -185,6 +270,7 @@ impl<E: Env + 'static> UpdateWithCtx<E> for MetaDetails {
                     .join(meta_streams_effects)
                     .join(streams_effects)
                     .join(suggested_stream_effects)
+                    .join(sorted_streams_effects)
                     .join(library_item_effects)
                     .join(watched_effects)
             }
@@ -203,7 +289,15 @@ impl<E: Env + 'static> UpdateWithCtx<E> for MetaDetails {
                     &self.streams,
                     &ctx.streams,
                 );
-                streams_effects.join(suggested_stream_effects)
+                let sorted_streams_effects = sorted_streams_update(
+                    &mut self.sorted_streams,
+                    &self.meta_streams,
+                    &self.streams,
+                    &ctx.profile,
+                );
+                streams_effects
+                    .join(suggested_stream_effects)
+                    .join(sorted_streams_effects)
             }
             Msg::Internal(Internal::LibraryChanged(_)) => {
                 let library_item_effects = library_item_update::<E>(
@@ -231,6 +325,12 @@ impl<E: Env + 'static> UpdateWithCtx<E> for MetaDetails {
                     &self.streams,
                     &ctx.streams,
                 );
+                let sorted_streams_effects = sorted_streams_update(
+                    &mut self.sorted_streams,
+                    &self.meta_streams,
+                    &self.streams,
+                    &ctx.profile,
+                );
                 let library_item_effects = library_item_update::<E>(
                     &mut self.library_item,
                     &self.selected,
@@ -243,6 +343,7 @@ impl<E: Env + 'static> UpdateWithCtx<E> for MetaDetails {
                     .join(meta_streams_effects)
                     .join(streams_effects)
                     .join(suggested_stream_effects)
+                    .join(sorted_streams_effects)
                     .join(library_item_effects)
                     .join(watched_effects)
             }
@@ -433,7 +534,8 @@ fn streams_update<E: Env + 'static>(
 /// Then with the stream item we try to find a stream from addon responses (including the streams inside the meta itself `meta_streams`) -
 /// we find the responses from the addon based on `StreamItem.stream_transport_url`,
 /// then first we try to find the stream based on equality (as otherwise stored stream might be expired/no longer valid),
-/// if not found we try to find a stream based on it's `StreamBehaviorHints.bingeGroup`.
+/// if not found we try to find the best matching release based on `Stream::binge_score`,
+/// first from the same addon and then from the rest of the addons.
 /// One note, why we cannot return `StreamItem.stream` directly if it's for the same episode,
 /// is that user might have played a stream from an addon which he no longer has due to some constrains (ie p2p addon),
 /// that's why we have to try to find it first and verify that's it's still available.
@@ -457,28 +559,48 @@ fn suggested_stream_update(
                 Some(Loadable::Ready(meta_item)) => stream_bucket
                     .last_stream_item(&stream_path.id, meta_item)
                     .and_then(|stream_item| {
+                        let other_addons_match = || {
+                            all_streams
+                                .iter()
+                                .filter(|resource| {
+                                    resource.request.base != stream_item.stream_transport_url
+                                })
+                                .filter_map(|resource| match &resource.content {
+                                    Some(Loadable::Ready(streams)) => stream_item
+                                        .stream
+                                        .best_binge_match(streams)
+                                        .map(|stream| (resource, stream)),
+                                    _ => None,
+                                })
+                                .min_by_key(|(_, stream)| {
+                                    Reverse(stream.binge_score(&stream_item.stream))
+                                })
+                                .map(|(resource, stream)| ResourceLoadable {
+                                    request: resource.request.clone(),
+                                    content: Some(Loadable::Ready(Some(stream.clone()))),
+                                })
+                        };
                         all_streams
                             .iter()
                             .find(|resource| {
                                 resource.request.base == stream_item.stream_transport_url
                             })
                             .and_then(|resource| match &resource.content {
-                                Some(Loadable::Ready(streams)) => Some(ResourceLoadable {
-                                    request: resource.request.clone(),
-                                    content: Some(Loadable::Ready(
-                                        streams
-                                            .iter()
-                                            .find(|stream| {
-                                                stream.is_source_match(&stream_item.stream)
-                                            })
-                                            .or_else(|| {
-                                                streams.iter().find(|stream| {
-                                                    stream.is_binge_match(&stream_item.stream)
-                                                })
-                                            })
-                                            .cloned(),
-                                    )),
-                                }),
+                                Some(Loadable::Ready(streams)) => streams
+                                    .iter()
+                                    .find(|stream| stream.is_source_match(&stream_item.stream))
+                                    .or_else(|| stream_item.stream.best_binge_match(streams))
+                                    .map(|stream| ResourceLoadable {
+                                        request: resource.request.clone(),
+                                        content: Some(Loadable::Ready(Some(stream.clone()))),
+                                    })
+                                    .or_else(other_addons_match)
+                                    .or_else(|| {
+                                        Some(ResourceLoadable {
+                                            request: resource.request.clone(),
+                                            content: Some(Loadable::Ready(None)),
+                                        })
+                                    }),
                                 Some(Loadable::Loading) => Some(ResourceLoadable {
                                     request: resource.request.clone(),
                                     content: Some(Loadable::Loading),
@@ -489,6 +611,8 @@ fn suggested_stream_update(
                                 }),
                                 _ => None,
                             })
+                            // the addon of the stored stream is no longer installed
+                            .or_else(other_addons_match)
                     })
                     .or_else(|| {
                         Some(ResourceLoadable {
@@ -503,6 +627,97 @@ fn suggested_stream_update(
     eq_update(suggested_stream, next_suggested_stream)
 }
 
+/// Flattens the streams of all addons, hides the ones excluded by the [`StreamPreferences`]
+/// and sorts the rest by the preferred resolution, language and source.
+///
+/// The sort is stable, so equally preferred streams are kept in the addons order.
+///
+/// [`StreamPreferences`]: crate::types::profile::StreamPreferences
+fn sorted_streams_update(
+    sorted_streams: &mut Vec<SortedStream>,
+    meta_streams: &[ResourceLoadable<Vec<Stream>>],
+    streams: &[ResourceLoadable<Vec<Stream>>],
+    profile: &Profile,
+) -> Effects {
+    let preferences = &profile.settings.stream_preferences;
+    let mut next_sorted_streams = meta_streams
+        .iter()
+        .chain(streams.iter())
+        .filter_map(|resource| match &resource.content {
+            Some(Loadable::Ready(streams)) => Some((resource, streams)),
+            _ => None,
+        })
+        .flat_map(|(resource, streams)| {
+            let is_p2p = profile
+                .addons
+                .iter()
+                .find(|addon| addon.transport_url == resource.request.base)
+                .map(|addon| addon.manifest.behavior_hints.p2p)
+                .unwrap_or_default();
+            streams.iter().map(move |stream| {
+                let metadata = stream.metadata();
+                let hidden_reasons = [
+                    (
+                        HiddenStreamReason::Size,
+                        matches!(
+                            (metadata.size, preferences.max_size),
+                            (Some(size), Some(max_size)) if size > max_size
+                        ),
+                    ),
+                    (
+                        HiddenStreamReason::Cam,
+                        preferences.exclude_cam
+                            && matches!(
+                                metadata.source,
+                                Some(ReleaseSource::Cam | ReleaseSource::Telesync)
+                            ),
+                    ),
+                    (HiddenStreamReason::P2P, preferences.hide_p2p && is_p2p),
+                ]
+                .into_iter()
+                .filter(|(_, is_hidden)| *is_hidden)
+                .map(|(reason, _)| reason)
+                .collect();
+                SortedStream {
+                    stream: stream.to_owned(),
+                    metadata,
+                    request: resource.request.to_owned(),
+                    hidden_reasons,
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    next_sorted_streams.sort_by_cached_key(|sorted_stream| {
+        // the streams in none of the preferred resolutions or languages are listed last
+        let resolution_rank = sorted_stream
+            .metadata
+            .resolution
+            .and_then(|resolution| {
+                preferences
+                    .preferred_resolutions
+                    .iter()
+                    .position(|preferred| *preferred == resolution)
+            })
+            .unwrap_or(preferences.preferred_resolutions.len());
+        let language_rank = preferences
+            .preferred_languages
+            .iter()
+            .position(|preferred| sorted_stream.metadata.languages.contains(preferred))
+            .unwrap_or(preferences.preferred_languages.len());
+        let is_direct_url = matches!(
+            &sorted_stream.stream.source,
+            StreamSource::Url { url } if url.scheme() != "magnet"
+        );
+        (
+            !sorted_stream.hidden_reasons.is_empty(),
+            resolution_rank,
+            language_rank,
+            preferences.prefer_direct_urls && !is_direct_url,
+        )
+    });
+    eq_update(sorted_streams, next_sorted_streams)
+}
+
 fn library_item_update<E: Env + 'static>(
     library_item: &mut Option<LibraryItem>,
     selected: &Option<Selected>,
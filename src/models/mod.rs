@@ -2,15 +2,21 @@ pub mod common;
 pub mod ctx;
 
 pub mod addon_details;
+pub mod calendar;
+pub mod cast_session;
 pub mod catalog_with_filters;
 pub mod catalogs_with_extra;
 pub mod continue_watching_preview;
 pub mod data_export;
+pub mod downloads;
 pub mod installed_addons_with_filters;
 pub mod library_by_type;
+pub mod library_import;
 pub mod library_with_filters;
 pub mod link;
 pub mod local_search;
 pub mod meta_details;
 pub mod player;
 pub mod streaming_server;
+pub mod subtitles_converter;
+pub mod torrent_details;
@@ -4,6 +4,8 @@ use std::ops::Div;
 use base64::Engine;
 use futures::{future, FutureExt, TryFutureExt};
 use num::rational::Ratio;
+use percent_encoding::percent_decode_str;
+use url::Url;
 
 use crate::constants::{
     BASE64, CREDITS_THRESHOLD_COEF, META_RESOURCE_NAME, PLAYER_IGNORE_SEEK_AFTER,
@@ -16,18 +18,27 @@ use crate::models::common::{
     ResourcesAction,
 };
 use crate::models::ctx::{Ctx, CtxError};
+use crate::models::streaming_server::{get_torrent_files, StatisticsRequest};
+use crate::models::subtitles_converter::fetch_subtitles;
 use crate::runtime::msg::{Action, ActionLoad, ActionPlayer, Event, Internal, Msg};
-use crate::runtime::{Effect, EffectFuture, Effects, Env, EnvFutureExt, UpdateWithCtx};
+use crate::runtime::{Effect, EffectFuture, Effects, Env, EnvError, EnvFutureExt, UpdateWithCtx};
 use crate::types::addon::{AggrRequest, Descriptor, ExtraExt, ResourcePath, ResourceRequest};
 use crate::types::api::{
     fetch_api, APIRequest, APIResult, SeekLog, SeekLogRequest, SkipGapsRequest, SkipGapsResponse,
     SuccessResponse,
 };
+use crate::types::intro_outro::IntroOutroBucket;
 use crate::types::library::{LibraryBucket, LibraryItem};
-use crate::types::player::{IntroData, IntroOutro};
-use crate::types::profile::{Profile, Settings as ProfileSettings};
-use crate::types::resource::{MetaItem, SeriesInfo, Stream, StreamSource, Subtitles, Video};
+use crate::types::player::{IntroData, IntroOutro, QoeSummary, ResumeDecision};
+use crate::types::profile::{BingeRules, Profile, Settings as ProfileSettings};
+use crate::types::resource::{
+    MetaItem, PlaybackMethod, SeriesInfo, Stream, StreamPlayback, StreamSource, Subtitles, Video,
+};
+use crate::types::streaming_server::{
+    find_video_file, ActiveServer, File, ServerFeature, StatisticsHistory,
+};
 use crate::types::streams::{StreamItemState, StreamsBucket, StreamsItemKey};
+use crate::types::subtitles::{SubtitlesError, SubtitlesFile, SubtitlesReference, SubtitlesSync};
 
 use stremio_watched_bitfield::WatchedBitField;
 
@@ -37,9 +48,29 @@ use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
 use once_cell::sync::Lazy;
+use regex::Regex;
 
 /// The duration that must have passed in order for a library item to be updated.
 pub static PUSH_TO_LIBRARY_EVERY: Lazy<Duration> = Lazy::new(|| Duration::seconds(90));
+pub static POLL_STATISTICS_EVERY: Lazy<Duration> = Lazy::new(|| Duration::seconds(5));
+/// The `OP` and `ED` abbreviations are matched only as the whole title,
+/// otherwise they would match names like `Ed` in the title of an episode.
+static INTRO_CHAPTER_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(intro|opening)\b|^\s*op\s*$").expect("INTRO_CHAPTER_REGEX parse failed")
+});
+static OUTRO_CHAPTER_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(outro|ending|credits)\b|^\s*ed\s*$")
+        .expect("OUTRO_CHAPTER_REGEX parse failed")
+});
+/// How much the playback is rewound (in milliseconds) when resuming
+/// after a break of at least the given duration.
+static RESUME_REWINDS: Lazy<[(Duration, u64); 3]> = Lazy::new(|| {
+    [
+        (Duration::weeks(1), 30_000),
+        (Duration::days(1), 15_000),
+        (Duration::minutes(10), 5_000),
+    ]
+});
 
 #[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -73,6 +104,19 @@ pub struct VideoParams {
     pub hash: Option<String>,
     pub size: Option<u64>,
     pub filename: Option<String>,
+    /// The chapters of the video, if the container provides them.
+    #[serde(default)]
+    pub chapters: Vec<VideoChapter>,
+}
+
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VideoChapter {
+    pub title: String,
+    /// In milliseconds
+    pub start: u64,
+    /// In milliseconds
+    pub end: Option<u64>,
 }
 
 #[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
@@ -83,6 +127,10 @@ pub struct Selected {
     /// A request to fetch the selected [`MetaItem`].
     pub meta_request: Option<ResourceRequest>,
     pub subtitles_path: Option<ResourcePath>,
+    /// How the [`Selected::stream`] should be played, decided by core
+    /// from the [`Env::playback_capabilities`] when the player is loaded.
+    #[serde(default, skip_deserializing)]
+    pub playback: Option<StreamPlayback>,
 }
 
 #[derive(Clone, Derivative, Serialize, Debug)]
@@ -93,6 +141,19 @@ pub struct Player {
     pub video_params: Option<VideoParams>,
     pub meta_item: Option<ResourceLoadable<MetaItem>>,
     pub subtitles: Vec<ResourceLoadable<Vec<Subtitles>>>,
+    /// The subtitles recommended to be shown when the stream starts playing.
+    ///
+    /// `None` when no subtitles match the user's languages
+    /// or when the user had selected an embedded subtitle track for this stream.
+    pub selected_subtitles: Option<Subtitles>,
+    /// The suggested timing correction of the [`Player::selected_subtitles`]
+    /// against the reference set with [`ActionPlayer::SubtitlesReferenceChanged`].
+    pub subtitles_sync: Option<SubtitlesSync>,
+    #[serde(skip_serializing)]
+    pub subtitles_reference: Option<SubtitlesReference>,
+    /// The subtitles files fetched for estimating the [`Player::subtitles_sync`].
+    #[serde(skip_serializing)]
+    pub subtitles_files: Vec<(Url, Loadable<SubtitlesFile, SubtitlesError>)>,
     pub next_video: Option<Video>,
     pub next_streams: Option<ResourceLoadable<Vec<Stream>>>,
     pub next_stream: Option<Stream>,
@@ -101,8 +162,33 @@ pub struct Player {
     pub stream_state: Option<StreamItemState>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub intro_outro: Option<IntroOutro>,
+    /// Where the playback of the selected video should start.
+    ///
+    /// It's decided before the playback starts, from the stored offset, the time passed
+    /// since the video was last watched and the [`Player::intro_outro`].
+    pub resume: Option<ResumeDecision>,
+    /// The quality of experience of the current playback session,
+    /// emitted with [`Event::PlayerQoe`] when the session ends.
+    pub qoe: Option<QoeSummary>,
+    /// The streaming server statistics of the selected torrent stream,
+    /// polled while it's playing, with a verdict whether the playback will buffer.
+    pub statistics: Option<StatisticsHistory>,
+    #[serde(skip_serializing)]
+    pub statistics_polled_at: Option<DateTime<Utc>>,
+    /// The files of the selected torrent stream, fetched when its file index is unknown.
+    ///
+    /// When they can't be fetched the streaming server plays the largest file of the torrent.
+    pub torrent_files: Option<Loadable<Vec<File>, EnvError>>,
     #[serde(skip_serializing)]
     pub watched: Option<WatchedBitField>,
+    /// The number of videos played in a row by advancing to the [`Player::next_video`],
+    /// used for the [`BingeRules::play_next_count`].
+    #[serde(skip_serializing)]
+    pub binge_count: u32,
+    /// The [`Player::next_video`] id kept when unloading the player,
+    /// so that the [`Player::binge_count`] continues when the next video is loaded.
+    #[serde(skip_serializing)]
+    pub unloaded_next_video_id: Option<String>,
     #[serde(skip_serializing)]
     pub analytics_context: Option<AnalyticsContext>,
     #[serde(skip_serializing)]
@@ -116,6 +202,9 @@ pub struct Player {
     pub ended: bool,
     #[serde(skip_serializing)]
     pub paused: Option<bool>,
+    /// When the current stall started, buffering before the playback has started is not a stall.
+    #[serde(skip_serializing)]
+    pub buffering_since: Option<DateTime<Utc>>,
     #[serde(skip_serializing)]
     pub seek_history: Vec<SeekLog>,
     #[serde(skip_serializing)]
@@ -126,6 +215,12 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Player {
     fn update(&mut self, msg: &Msg, ctx: &Ctx) -> Effects {
         match msg {
             Msg::Action(Action::Load(ActionLoad::Player(selected))) => {
+                let qoe_effects = qoe_summary_update::<E>(
+                    &mut self.qoe,
+                    &mut self.buffering_since,
+                    self.load_time,
+                    self.analytics_context.as_ref(),
+                );
                 let switch_to_next_video_effects = if self
                     .selected
                     .as_ref()
@@ -140,7 +235,27 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Player {
                 } else {
                     Effects::none().unchanged()
                 };
-                let selected_effects = eq_update(&mut self.selected, Some(*selected.to_owned()));
+                let next_video_id = self
+                    .next_video
+                    .as_ref()
+                    .map(|next_video| &next_video.id)
+                    .or(self.unloaded_next_video_id.as_ref());
+                let stream_video_id = selected
+                    .stream_request
+                    .as_ref()
+                    .map(|stream_request| &stream_request.path.id);
+                self.binge_count = match (next_video_id, stream_video_id) {
+                    (Some(next_video_id), Some(video_id)) if next_video_id == video_id => {
+                        self.binge_count.saturating_add(1)
+                    }
+                    _ => 0,
+                };
+                self.unloaded_next_video_id = None;
+                let selected = Selected {
+                    playback: stream_playback::<E>(&selected.stream, &ctx.streaming_server),
+                    ..*selected.to_owned()
+                };
+                let selected_effects = eq_update(&mut self.selected, Some(selected.to_owned()));
                 let meta_item_effects = match &selected.meta_request {
                     Some(meta_request) => match &mut self.meta_item {
                         Some(meta_item) => resource_update::<E, _>(
@@ -168,17 +283,46 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Player {
                 };
                 let stream_state_effects = eq_update(&mut self.stream_state, None);
                 let video_params_effects = eq_update(&mut self.video_params, None);
+                let subtitles_reference_effects = eq_update(&mut self.subtitles_reference, None);
                 let subtitles_effects = subtitles_update::<E>(
                     &mut self.subtitles,
                     &self.selected,
                     &self.video_params,
                     &ctx.profile.addons,
                 );
+                let selected_subtitles_effects = selected_subtitles_update(
+                    &mut self.selected_subtitles,
+                    &self.subtitles,
+                    &self.selected,
+                    &self.video_params,
+                    &self.stream_state,
+                    &ctx.profile.settings,
+                );
+                let subtitles_sync_effects = subtitles_sync_update::<E>(
+                    &mut self.subtitles_sync,
+                    &mut self.subtitles_files,
+                    &self.selected_subtitles,
+                    &self.subtitles_reference,
+                );
+                let series_info_effects =
+                    series_info_update(&mut self.series_info, &self.selected, &self.meta_item);
+                let library_item_effects = library_item_update::<E>(
+                    &mut self.library_item,
+                    self.selected
+                        .as_ref()
+                        .and_then(|selected| selected.meta_request.as_ref()),
+                    &self.meta_item,
+                    &ctx.library,
+                );
+                let watched_effects =
+                    watched_update(&mut self.watched, &self.meta_item, &self.library_item);
                 let next_video_effects = next_video_update(
                     &mut self.next_video,
                     &self.next_stream,
                     &self.selected,
                     &self.meta_item,
+                    &self.watched,
+                    self.binge_count,
                     &ctx.profile.settings,
                 );
                 let next_streams_effects = next_streams_update::<E>(
@@ -205,27 +349,30 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Player {
                     }
                     _ => Effects::none().unchanged(),
                 };
-                let series_info_effects =
-                    series_info_update(&mut self.series_info, &self.selected, &self.meta_item);
-                let library_item_effects = library_item_update::<E>(
-                    &mut self.library_item,
-                    &self.selected,
-                    &self.meta_item,
-                    &ctx.library,
-                );
-                let watched_effects =
-                    watched_update(&mut self.watched, &self.meta_item, &self.library_item);
-
                 let skip_gaps_effects = eq_update(&mut self.skip_gaps, None);
                 let intro_outro_update_effects = intro_outro_update::<E>(
                     &mut self.intro_outro,
                     &ctx.profile,
+                    &ctx.intro_outro,
                     self.selected.as_ref(),
                     self.video_params.as_ref(),
                     self.series_info.as_ref(),
                     self.library_item.as_ref(),
                     &mut self.skip_gaps,
                 );
+                // the decision is made again for the newly selected video
+                let resume_effects = eq_update(&mut self.resume, None).join(resume_update::<E>(
+                    &mut self.resume,
+                    &mut self.library_item,
+                    &mut self.watched,
+                    ResumeState {
+                        selected: &self.selected,
+                        next_video: &self.next_video,
+                        meta_item: &self.meta_item,
+                        intro_outro: &self.intro_outro,
+                        loaded: false,
+                    },
+                ));
 
                 // dismiss LibraryItem notification if we have a LibraryItem to begin with
                 let notification_effects = match &self.library_item {
@@ -259,16 +406,46 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Player {
                     has_trakt: ctx.profile.has_trakt::<E>(),
                     ..Default::default()
                 });
+                let statistics_effects = eq_update(
+                    &mut self.statistics,
+                    StatisticsRequest::from_stream(&selected.stream)
+                        .map(|_| StatisticsHistory::default()),
+                );
+                self.statistics_polled_at = None;
+                let torrent_files_effects = match &selected.stream.source {
+                    StreamSource::Torrent {
+                        info_hash,
+                        file_idx: None,
+                        announce,
+                    } => {
+                        self.torrent_files = Some(Loadable::Loading);
+                        Effects::one(get_torrent_files::<E>(
+                            &ctx.streaming_server.url,
+                            &hex::encode(info_hash),
+                            announce,
+                        ))
+                        .unchanged()
+                    }
+                    _ => {
+                        self.torrent_files = None;
+                        Effects::none().unchanged()
+                    }
+                };
                 self.load_time = Some(E::now());
+                self.qoe = Some(QoeSummary::default());
                 self.loaded = false;
                 self.ended = false;
                 self.paused = None;
-                switch_to_next_video_effects
+                qoe_effects
+                    .join(switch_to_next_video_effects)
                     .join(selected_effects)
                     .join(meta_item_effects)
                     .join(stream_state_effects)
                     .join(video_params_effects)
+                    .join(subtitles_reference_effects)
                     .join(subtitles_effects)
+                    .join(selected_subtitles_effects)
+                    .join(subtitles_sync_effects)
                     .join(next_video_effects)
                     .join(next_streams_effects)
                     .join(next_stream_effects)
@@ -278,7 +455,10 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Player {
                     .join(watched_effects)
                     .join(skip_gaps_effects)
                     .join(intro_outro_update_effects)
+                    .join(resume_effects)
                     .join(notification_effects)
+                    .join(statistics_effects)
+                    .join(torrent_files_effects)
             }
             Msg::Action(Action::Unload) => {
                 let ended_effects = if !self.ended && self.selected.is_some() {
@@ -309,11 +489,19 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Player {
                     .unchanged(),
                     _ => Effects::none().unchanged(),
                 };
+                self.unloaded_next_video_id = self
+                    .next_video
+                    .as_ref()
+                    .map(|next_video| next_video.id.to_owned());
                 let selected_effects = eq_update(&mut self.selected, None);
                 let video_params_effects = eq_update(&mut self.video_params, None);
                 let meta_item_effects = eq_update(&mut self.meta_item, None);
                 let stream_state_effects = eq_update(&mut self.stream_state, None);
                 let subtitles_effects = eq_update(&mut self.subtitles, vec![]);
+                let selected_subtitles_effects = eq_update(&mut self.selected_subtitles, None);
+                let subtitles_reference_effects = eq_update(&mut self.subtitles_reference, None);
+                let subtitles_files_effects = eq_update(&mut self.subtitles_files, vec![]);
+                let subtitles_sync_effects = eq_update(&mut self.subtitles_sync, None);
                 let next_video_effects = eq_update(&mut self.next_video, None);
                 let next_streams_effects = eq_update(&mut self.next_streams, None);
                 let next_stream_effects = eq_update(&mut self.next_stream, None);
@@ -321,6 +509,16 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Player {
                 let library_item_effects = eq_update(&mut self.library_item, None);
                 let watched_effects = eq_update(&mut self.watched, None);
                 let skip_gaps_effects = eq_update(&mut self.skip_gaps, None);
+                let resume_effects = eq_update(&mut self.resume, None);
+                let qoe_effects = qoe_summary_update::<E>(
+                    &mut self.qoe,
+                    &mut self.buffering_since,
+                    self.load_time,
+                    self.analytics_context.as_ref(),
+                );
+                let statistics_effects = eq_update(&mut self.statistics, None);
+                self.statistics_polled_at = None;
+                self.torrent_files = None;
                 self.analytics_context = None;
                 self.load_time = None;
                 self.loaded = false;
@@ -335,6 +533,10 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Player {
                     .join(meta_item_effects)
                     .join(stream_state_effects)
                     .join(subtitles_effects)
+                    .join(selected_subtitles_effects)
+                    .join(subtitles_reference_effects)
+                    .join(subtitles_files_effects)
+                    .join(subtitles_sync_effects)
                     .join(next_video_effects)
                     .join(next_streams_effects)
                     .join(next_stream_effects)
@@ -342,6 +544,9 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Player {
                     .join(library_item_effects)
                     .join(watched_effects)
                     .join(skip_gaps_effects)
+                    .join(resume_effects)
+                    .join(qoe_effects)
+                    .join(statistics_effects)
                     .join(ended_effects)
             }
             Msg::Action(Action::Player(ActionPlayer::VideoParamsChanged { video_params })) => {
@@ -353,8 +558,24 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Player {
                     &self.video_params,
                     &ctx.profile.addons,
                 );
-                let skip_gaps_effects = skip_gaps_update::<E>(
+                let selected_subtitles_effects = selected_subtitles_update(
+                    &mut self.selected_subtitles,
+                    &self.subtitles,
+                    &self.selected,
+                    &self.video_params,
+                    &self.stream_state,
+                    &ctx.profile.settings,
+                );
+                let subtitles_sync_effects = subtitles_sync_update::<E>(
+                    &mut self.subtitles_sync,
+                    &mut self.subtitles_files,
+                    &self.selected_subtitles,
+                    &self.subtitles_reference,
+                );
+                let intro_outro_effects = intro_outro_update::<E>(
+                    &mut self.intro_outro,
                     &ctx.profile,
+                    &ctx.intro_outro,
                     self.selected.as_ref(),
                     self.video_params.as_ref(),
                     self.series_info.as_ref(),
@@ -364,7 +585,9 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Player {
 
                 video_params_effects
                     .join(subtitles_effects)
-                    .join(skip_gaps_effects)
+                    .join(selected_subtitles_effects)
+                    .join(subtitles_sync_effects)
+                    .join(intro_outro_effects)
             }
             Msg::Action(Action::Player(ActionPlayer::StreamStateChanged { state })) => {
                 Effects::msg(Msg::Internal(Internal::StreamStateChanged {
@@ -380,6 +603,52 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Player {
                 }))
                 .unchanged()
             }
+            Msg::Action(Action::Player(ActionPlayer::SubtitlesReferenceChanged { reference }))
+                if self.selected.is_some() =>
+            {
+                let subtitles_reference_effects =
+                    eq_update(&mut self.subtitles_reference, reference.to_owned());
+                let subtitles_sync_effects = subtitles_sync_update::<E>(
+                    &mut self.subtitles_sync,
+                    &mut self.subtitles_files,
+                    &self.selected_subtitles,
+                    &self.subtitles_reference,
+                );
+                subtitles_reference_effects.join(subtitles_sync_effects)
+            }
+            Msg::Action(Action::Player(ActionPlayer::ApplySubtitlesSync)) => {
+                match &self.subtitles_sync {
+                    Some(subtitles_sync) => {
+                        let state = match &self.stream_state {
+                            Some(state) => StreamItemState {
+                                subtitle_delay: Some(subtitles_sync.delay),
+                                ..state.to_owned()
+                            },
+                            None => StreamItemState {
+                                subtitle_track: None,
+                                subtitle_delay: Some(subtitles_sync.delay),
+                                audio_track: None,
+                                audio_delay: None,
+                                playback_speed: None,
+                                player_type: None,
+                            },
+                        };
+                        Effects::msg(Msg::Internal(Internal::StreamStateChanged {
+                            state,
+                            stream_request: self
+                                .selected
+                                .as_ref()
+                                .and_then(|selected| selected.stream_request.to_owned()),
+                            meta_request: self
+                                .selected
+                                .as_ref()
+                                .and_then(|selected| selected.meta_request.to_owned()),
+                        }))
+                        .unchanged()
+                    }
+                    _ => Effects::none().unchanged(),
+                }
+            }
             Msg::Action(Action::Player(ActionPlayer::TimeChanged {
                 time,
                 duration,
@@ -486,9 +755,23 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Player {
                     let push_to_library_effects =
                         push_to_library::<E>(&mut self.push_library_item_time, library_item);
 
-                    trakt_event_effects.join(push_to_library_effects)
+                    trakt_event_effects
+                        .join(push_to_library_effects)
+                        .join(statistics_update::<E>(
+                            &mut self.statistics,
+                            &mut self.statistics_polled_at,
+                            &self.selected,
+                            *time,
+                            *duration,
+                        ))
                 }
-                _ => Effects::none().unchanged(),
+                _ => statistics_update::<E>(
+                    &mut self.statistics,
+                    &mut self.statistics_polled_at,
+                    &self.selected,
+                    *time,
+                    *duration,
+                ),
             },
             Msg::Action(Action::Player(ActionPlayer::PausedChanged { paused }))
                 if self.selected.is_some() =>
@@ -496,16 +779,22 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Player {
                 self.paused = Some(*paused);
                 let trakt_event_effects = if !self.loaded {
                     self.loaded = true;
+                    let load_time = self.load_time.map(|load_time| {
+                        E::now().timestamp_millis() - load_time.timestamp_millis()
+                    });
+                    let qoe_effects = match &mut self.qoe {
+                        Some(qoe) => {
+                            qoe.startup_time = load_time;
+                            Effects::none()
+                        }
+                        None => Effects::none().unchanged(),
+                    };
                     Effects::msg(Msg::Event(Event::PlayerPlaying {
-                        load_time: self
-                            .load_time
-                            .map(|load_time| {
-                                E::now().timestamp_millis() - load_time.timestamp_millis()
-                            })
-                            .unwrap_or(-1),
+                        load_time: load_time.unwrap_or(-1),
                         context: self.analytics_context.as_ref().cloned().unwrap_or_default(),
                     }))
                     .unchanged()
+                    .join(qoe_effects)
                 } else if *paused {
                     Effects::msg(Msg::Event(Event::TraktPaused {
                         context: self.analytics_context.as_ref().cloned().unwrap_or_default(),
@@ -526,6 +815,62 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Player {
                 };
                 trakt_event_effects.join(update_library_item_effects)
             }
+            Msg::Action(Action::Player(ActionPlayer::BufferingChanged { buffering }))
+                if self.selected.is_some() =>
+            {
+                match (&mut self.qoe, *buffering, self.buffering_since) {
+                    (Some(qoe), true, None) if self.loaded => {
+                        qoe.stalls = qoe.stalls.saturating_add(1);
+                        self.buffering_since = Some(E::now());
+                        Effects::none()
+                    }
+                    (Some(qoe), false, Some(buffering_since)) => {
+                        stall_ended::<E>(qoe, buffering_since, self.load_time);
+                        self.buffering_since = None;
+                        Effects::none()
+                    }
+                    _ => Effects::none().unchanged(),
+                }
+            }
+            Msg::Action(Action::Player(ActionPlayer::QualityChanged {
+                width,
+                height,
+                bitrate,
+            })) if self.selected.is_some() => {
+                let previous_size = self
+                    .analytics_context
+                    .as_ref()
+                    .map(|context| (context.player_video_width, context.player_video_height))
+                    .filter(|size| *size != (0, 0));
+                if let Some(analytics_context) = &mut self.analytics_context {
+                    analytics_context.player_video_width = *width;
+                    analytics_context.player_video_height = *height;
+                };
+                match &mut self.qoe {
+                    Some(qoe) => {
+                        // the first reported quality is not a change
+                        let changed = matches!(previous_size, Some(size) if size != (*width, *height))
+                            || (qoe.bitrate.is_some() && qoe.bitrate != *bitrate);
+                        if changed {
+                            qoe.quality_changes = qoe.quality_changes.saturating_add(1);
+                        };
+                        qoe.bitrate = bitrate.to_owned();
+                        Effects::none()
+                    }
+                    None => Effects::none().unchanged(),
+                }
+            }
+            Msg::Action(Action::Player(ActionPlayer::PlaybackError { code }))
+                if self.selected.is_some() =>
+            {
+                match &mut self.qoe {
+                    Some(qoe) => {
+                        qoe.error_codes.push(*code);
+                        Effects::none()
+                    }
+                    None => Effects::none().unchanged(),
+                }
+            }
             Msg::Action(Action::Player(ActionPlayer::NextVideo)) => {
                 let seek_history_effects = seek_update::<E>(
                     self.selected.as_ref(),
@@ -560,7 +905,25 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Player {
                 .unchanged()
             }
             Msg::Internal(Internal::StreamsChanged(_)) => {
-                stream_state_update(&mut self.stream_state, &self.selected, &ctx.streams)
+                let stream_state_effects =
+                    stream_state_update(&mut self.stream_state, &self.selected, &ctx.streams);
+                let selected_subtitles_effects = selected_subtitles_update(
+                    &mut self.selected_subtitles,
+                    &self.subtitles,
+                    &self.selected,
+                    &self.video_params,
+                    &self.stream_state,
+                    &ctx.profile.settings,
+                );
+                let subtitles_sync_effects = subtitles_sync_update::<E>(
+                    &mut self.subtitles_sync,
+                    &mut self.subtitles_files,
+                    &self.selected_subtitles,
+                    &self.subtitles_reference,
+                );
+                stream_state_effects
+                    .join(selected_subtitles_effects)
+                    .join(subtitles_sync_effects)
             }
             Msg::Internal(Internal::ResourceRequestResult(request, result)) => {
                 let meta_item_effects = match &mut self.meta_item {
@@ -587,6 +950,20 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Player {
                     &mut self.subtitles,
                     ResourcesAction::ResourceRequestResult { request, result },
                 );
+                let selected_subtitles_effects = selected_subtitles_update(
+                    &mut self.selected_subtitles,
+                    &self.subtitles,
+                    &self.selected,
+                    &self.video_params,
+                    &self.stream_state,
+                    &ctx.profile.settings,
+                );
+                let subtitles_sync_effects = subtitles_sync_update::<E>(
+                    &mut self.subtitles_sync,
+                    &mut self.subtitles_files,
+                    &self.selected_subtitles,
+                    &self.subtitles_reference,
+                );
                 let next_streams_effects = match self.next_streams.as_mut() {
                     Some(next_streams) => resource_update_with_vector_content::<E, _>(
                         next_streams,
@@ -595,11 +972,34 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Player {
                     None => Effects::none().unchanged(),
                 };
 
+                let series_info_effects =
+                    series_info_update(&mut self.series_info, &self.selected, &self.meta_item);
+                let torrent_file_effects = torrent_file_update::<E>(
+                    &mut self.selected,
+                    &mut self.video_params,
+                    &mut self.statistics,
+                    &self.torrent_files,
+                    self.series_info.as_ref(),
+                    &self.meta_item,
+                    &ctx.streaming_server,
+                );
+                let library_item_effects = library_item_update::<E>(
+                    &mut self.library_item,
+                    self.selected
+                        .as_ref()
+                        .and_then(|selected| selected.meta_request.as_ref()),
+                    &self.meta_item,
+                    &ctx.library,
+                );
+                let watched_effects =
+                    watched_update(&mut self.watched, &self.meta_item, &self.library_item);
                 let next_video_effects = next_video_update(
                     &mut self.next_video,
                     &self.next_stream,
                     &self.selected,
                     &self.meta_item,
+                    &self.watched,
+                    self.binge_count,
                     &ctx.profile.settings,
                 );
                 let next_streams_effects = next_streams_effects.join(next_streams_update::<E>(
@@ -614,25 +1014,28 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Player {
                     &ctx.profile.settings,
                 );
 
-                let series_info_effects =
-                    series_info_update(&mut self.series_info, &self.selected, &self.meta_item);
-                let library_item_effects = library_item_update::<E>(
-                    &mut self.library_item,
-                    &self.selected,
-                    &self.meta_item,
-                    &ctx.library,
-                );
-                let watched_effects =
-                    watched_update(&mut self.watched, &self.meta_item, &self.library_item);
-
-                let skip_gaps_effects = skip_gaps_update::<E>(
+                let intro_outro_effects = intro_outro_update::<E>(
+                    &mut self.intro_outro,
                     &ctx.profile,
+                    &ctx.intro_outro,
                     self.selected.as_ref(),
                     self.video_params.as_ref(),
                     self.series_info.as_ref(),
                     self.library_item.as_ref(),
                     &mut self.skip_gaps,
                 );
+                let resume_effects = resume_update::<E>(
+                    &mut self.resume,
+                    &mut self.library_item,
+                    &mut self.watched,
+                    ResumeState {
+                        selected: &self.selected,
+                        next_video: &self.next_video,
+                        meta_item: &self.meta_item,
+                        intro_outro: &self.intro_outro,
+                        loaded: self.loaded,
+                    },
+                );
 
                 let (id, r#type, name, video_id, time, duration) = self
                     .library_item
@@ -659,13 +1062,82 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Player {
                 meta_item_effects
                     .join(update_streams_effects)
                     .join(subtitles_effects)
+                    .join(selected_subtitles_effects)
+                    .join(subtitles_sync_effects)
                     .join(next_video_effects)
                     .join(next_streams_effects)
                     .join(next_stream_effects)
                     .join(series_info_effects)
+                    .join(torrent_file_effects)
                     .join(library_item_effects)
                     .join(watched_effects)
-                    .join(skip_gaps_effects)
+                    .join(intro_outro_effects)
+                    .join(resume_effects)
+            }
+            Msg::Internal(Internal::SubtitlesFileResult(url, result)) => {
+                match self
+                    .subtitles_files
+                    .iter_mut()
+                    .find(|(file_url, file)| file_url == url && file.is_loading())
+                {
+                    Some((_, file)) => {
+                        *file = match result {
+                            Ok(subtitles_file) => Loadable::Ready(subtitles_file.to_owned()),
+                            Err(error) => Loadable::Err(error.to_owned()),
+                        };
+                        let subtitles_sync_effects = subtitles_sync_update::<E>(
+                            &mut self.subtitles_sync,
+                            &mut self.subtitles_files,
+                            &self.selected_subtitles,
+                            &self.subtitles_reference,
+                        );
+                        Effects::none().join(subtitles_sync_effects)
+                    }
+                    None => Effects::none().unchanged(),
+                }
+            }
+            Msg::Internal(Internal::StreamingServerStatisticsResult(
+                (_, request),
+                Ok(statistics),
+            )) if self
+                .selected
+                .as_ref()
+                .and_then(|selected| StatisticsRequest::from_stream(&selected.stream))
+                .as_ref()
+                == Some(request) =>
+            {
+                match &mut self.statistics {
+                    Some(statistics_history)
+                        if statistics_history.push(E::now(), statistics.as_ref()) =>
+                    {
+                        Effects::none()
+                    }
+                    _ => Effects::none().unchanged(),
+                }
+            }
+            Msg::Internal(Internal::TorrentFilesResult(info_hash, result))
+                if matches!(
+                    self.selected.as_ref().map(|selected| &selected.stream.source),
+                    Some(StreamSource::Torrent {
+                        info_hash: selected_info_hash,
+                        file_idx: None,
+                        ..
+                    }) if hex::encode(selected_info_hash) == *info_hash
+                ) =>
+            {
+                self.torrent_files = Some(match result {
+                    Ok(files) => Loadable::Ready(files.to_owned()),
+                    Err(error) => Loadable::Err(error.to_owned()),
+                });
+                Effects::none().join(torrent_file_update::<E>(
+                    &mut self.selected,
+                    &mut self.video_params,
+                    &mut self.statistics,
+                    &self.torrent_files,
+                    self.series_info.as_ref(),
+                    &self.meta_item,
+                    &ctx.streaming_server,
+                ))
             }
             Msg::Internal(Internal::SkipGapsResult(skip_gaps_request, result)) => {
                 let skip_gaps_next = match result.to_owned() {
@@ -681,20 +1153,49 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Player {
                 let intro_outro_effects = intro_outro_update::<E>(
                     &mut self.intro_outro,
                     &ctx.profile,
+                    &ctx.intro_outro,
                     self.selected.as_ref(),
                     self.video_params.as_ref(),
                     self.series_info.as_ref(),
                     self.library_item.as_ref(),
                     &mut self.skip_gaps,
                 );
+                let resume_effects = resume_update::<E>(
+                    &mut self.resume,
+                    &mut self.library_item,
+                    &mut self.watched,
+                    ResumeState {
+                        selected: &self.selected,
+                        next_video: &self.next_video,
+                        meta_item: &self.meta_item,
+                        intro_outro: &self.intro_outro,
+                        loaded: self.loaded,
+                    },
+                );
 
-                skip_gaps_effects.join(intro_outro_effects)
+                skip_gaps_effects
+                    .join(intro_outro_effects)
+                    .join(resume_effects)
             }
             Msg::Internal(Internal::ProfileChanged) => {
                 if let Some(analytics_context) = &mut self.analytics_context {
                     analytics_context.has_trakt = ctx.profile.has_trakt::<E>();
                 };
-                Effects::none().unchanged()
+                let selected_subtitles_effects = selected_subtitles_update(
+                    &mut self.selected_subtitles,
+                    &self.subtitles,
+                    &self.selected,
+                    &self.video_params,
+                    &self.stream_state,
+                    &ctx.profile.settings,
+                );
+                let subtitles_sync_effects = subtitles_sync_update::<E>(
+                    &mut self.subtitles_sync,
+                    &mut self.subtitles_files,
+                    &self.selected_subtitles,
+                    &self.subtitles_reference,
+                );
+                selected_subtitles_effects.join(subtitles_sync_effects)
             }
             _ => Effects::none().unchanged(),
         }
@@ -703,7 +1204,7 @@ impl<E: Env + 'static> UpdateWithCtx<E> for Player {
 
 /// We will push an [`Internal::UpdateLibraryItem`] message only if
 /// at least [`PUSH_TO_LIBRARY_EVERY`] time has passed since the last update.
-fn push_to_library<E: Env + 'static>(
+pub(crate) fn push_to_library<E: Env + 'static>(
     push_library_item_time: &mut DateTime<Utc>,
     library_item: &mut LibraryItem,
 ) -> Effects {
@@ -728,23 +1229,121 @@ fn switch_to_next_video(
             if library_item.state.time_offset as f64
                 > library_item.state.duration as f64 * CREDITS_THRESHOLD_COEF =>
         {
-            library_item.state.time_offset = 0;
-            if let Some(next_video) = next_video {
-                library_item.state.video_id = Some(next_video.id.to_owned());
-                library_item.state.overall_time_watched = library_item
-                    .state
-                    .overall_time_watched
-                    .saturating_add(library_item.state.time_watched);
-                library_item.state.time_watched = 0;
-                library_item.state.flagged_watched = 0;
-                library_item.state.time_offset = 1;
-            };
+            finish_video(library_item, next_video);
         }
         _ => {}
     };
     Effects::none().unchanged()
 }
 
+/// Resets the offset of the finished video and advances the item to the next video, if any.
+fn finish_video(library_item: &mut LibraryItem, next_video: &Option<Video>) {
+    library_item.state.time_offset = 0;
+    if let Some(next_video) = next_video {
+        library_item.state.video_id = Some(next_video.id.to_owned());
+        library_item.state.overall_time_watched = library_item
+            .state
+            .overall_time_watched
+            .saturating_add(library_item.state.time_watched);
+        library_item.state.time_watched = 0;
+        library_item.state.flagged_watched = 0;
+        library_item.state.time_offset = 1;
+    };
+}
+
+/// The state of the [`Player`] which the [`ResumeDecision`] is made from.
+struct ResumeState<'a> {
+    selected: &'a Option<Selected>,
+    next_video: &'a Option<Video>,
+    meta_item: &'a Option<ResourceLoadable<MetaItem>>,
+    intro_outro: &'a Option<IntroOutro>,
+    /// Whether the playback has started.
+    loaded: bool,
+}
+
+/// Decides where the playback of the selected video starts, see [`ResumeDecision`].
+///
+/// The decision is kept once the playback has started or once the video is finished,
+/// as finishing it resets the stored offset and advances the [`LibraryItem`] to the next video.
+/// It's deferred while the meta item is loading, as the next video and the watched videos
+/// are known only from the meta item.
+fn resume_update<E: Env + 'static>(
+    resume: &mut Option<ResumeDecision>,
+    library_item: &mut Option<LibraryItem>,
+    watched: &mut Option<WatchedBitField>,
+    resume_state: ResumeState,
+) -> Effects {
+    let ResumeState {
+        selected,
+        next_video,
+        meta_item,
+        intro_outro,
+        loaded,
+    } = resume_state;
+    let meta_item_loading = matches!(
+        meta_item,
+        Some(ResourceLoadable {
+            content: None | Some(Loadable::Loading),
+            ..
+        })
+    );
+    if loaded || meta_item_loading || *resume == Some(ResumeDecision::Finished) {
+        return Effects::none().unchanged();
+    }
+    let video_id = selected
+        .as_ref()
+        .and_then(|selected| selected.stream_request.as_ref())
+        .map(|stream_request| &stream_request.path.id);
+    let library_item = match (video_id, library_item) {
+        (Some(video_id), Some(library_item))
+            if library_item.state.video_id.as_ref() == Some(video_id)
+                && library_item.state.time_offset > 0 =>
+        {
+            library_item
+        }
+        _ => {
+            let next_resume = selected.as_ref().map(|_| ResumeDecision::Start);
+            return eq_update(resume, next_resume);
+        }
+    };
+    let state = &library_item.state;
+    let credits_time = intro_outro
+        .as_ref()
+        .and_then(|intro_outro| intro_outro.outro)
+        .unwrap_or((state.duration as f64 * CREDITS_THRESHOLD_COEF) as u64);
+    if state.duration > 0 && state.time_offset >= credits_time {
+        if library_item.state.flagged_watched == 0 {
+            library_item.state.flagged_watched = 1;
+            library_item.state.times_watched = library_item.state.times_watched.saturating_add(1);
+        }
+        if let (Some(watched), Some(video_id)) = (watched.as_mut(), video_id) {
+            watched.set_video(video_id, true);
+            library_item.state.watched = Some(watched.to_owned().into());
+        }
+        finish_video(library_item, next_video);
+        let update_library_item_effects = Effects::msg(Msg::Internal(Internal::UpdateLibraryItem(
+            library_item.to_owned(),
+        )))
+        .unchanged();
+        return eq_update(resume, Some(ResumeDecision::Finished)).join(update_library_item_effects);
+    }
+    let break_duration = state
+        .last_watched
+        .map(|last_watched| E::now() - last_watched)
+        .unwrap_or_else(Duration::zero);
+    let rewound = RESUME_REWINDS
+        .iter()
+        .find(|(after, _)| break_duration >= *after)
+        .map(|(_, rewind)| *rewind)
+        .unwrap_or_default()
+        .min(state.time_offset);
+    let next_resume = match state.time_offset - rewound {
+        0 => ResumeDecision::Start,
+        time => ResumeDecision::Resume { time, rewound },
+    };
+    eq_update(resume, Some(next_resume))
+}
+
 fn stream_state_update(
     state: &mut Option<StreamItemState>,
     selected: &Option<Selected>,
@@ -775,8 +1374,11 @@ fn next_video_update(
     stream: &Option<Stream>,
     selected: &Option<Selected>,
     meta_item: &Option<ResourceLoadable<MetaItem>>,
+    watched: &Option<WatchedBitField>,
+    binge_count: u32,
     settings: &ProfileSettings,
 ) -> Effects {
+    let rules = &settings.binge_rules;
     let next_video = match (selected, meta_item) {
         (
             Some(Selected {
@@ -791,36 +1393,61 @@ fn next_video_update(
                 content: Some(Loadable::Ready(meta_item)),
                 ..
             }),
-        ) if settings.binge_watching => meta_item
-            .videos
-            .iter()
-            .find_position(|video| video.id == *video_id)
-            .and_then(|(position, current_video)| {
-                meta_item
-                    .videos
-                    .get(position + 1)
-                    .map(|next_video| (current_video, next_video))
-            })
-            .filter(|(current_video, next_video)| {
-                let current_season = current_video
-                    .series_info
-                    .as_ref()
-                    .map(|info| info.season)
-                    .unwrap_or_default();
-                let next_season = next_video
+        ) if settings.binge_watching
+            && rules
+                .play_next_count
+                .map(|play_next_count| binge_count < play_next_count)
+                .unwrap_or(true) =>
+        {
+            let season = |video: &Video| {
+                video
                     .series_info
                     .as_ref()
                     .map(|info| info.season)
-                    .unwrap_or_default();
-                next_season != 0 || current_season == next_season
-            })
-            .map(|(_, next_video)| {
-                let mut next_video = next_video.clone();
-                if let Some(stream) = stream {
-                    next_video.streams = vec![stream.clone()];
-                }
-                next_video
-            }),
+                    .unwrap_or_default()
+            };
+            meta_item
+                .videos
+                .iter()
+                .find(|video| video.id == *video_id)
+                .and_then(|current_video| {
+                    let videos = rules.binge_order(&meta_item.videos);
+                    let position = videos
+                        .iter()
+                        .position(|video| video.id == current_video.id)?;
+                    let current_season = season(current_video);
+                    videos
+                        .into_iter()
+                        .skip(position + 1)
+                        // specials are played only while watching one of them,
+                        // unless they are ordered in between the episodes
+                        .take_while(|next_video| {
+                            rules.specials_in_aired_order
+                                || season(*next_video) != 0
+                                || current_season == 0
+                        })
+                        .take_while(|next_video| {
+                            !rules.stop_at_season_end
+                                || season(*next_video) == current_season
+                                // specials in between the episodes of the season
+                                || (rules.specials_in_aired_order && season(*next_video) == 0)
+                        })
+                        .find(|next_video| {
+                            !rules.skip_watched
+                                || !watched
+                                    .as_ref()
+                                    .map(|watched| watched.get_video(&next_video.id))
+                                    .unwrap_or_default()
+                        })
+                })
+                .map(|next_video| {
+                    let mut next_video = next_video.clone();
+                    if let Some(stream) = stream {
+                        next_video.streams = vec![stream.clone()];
+                    }
+                    next_video
+                })
+        }
         _ => None,
     };
     eq_update(video, next_video)
@@ -907,10 +1534,7 @@ fn next_stream_update(
                 content: Some(Loadable::Ready(streams)),
                 ..
             }),
-        ) if settings.binge_watching => streams
-            .iter()
-            .find(|next_stream| next_stream.is_binge_match(stream))
-            .cloned(),
+        ) if settings.binge_watching => stream.best_binge_match(streams).cloned(),
         _ => None,
     };
 
@@ -947,17 +1571,99 @@ fn series_info_update(
     eq_update(series_info, next_series_info)
 }
 
-fn library_item_update<E: Env + 'static>(
+/// How the client plays the stream, the streaming servers which are too old
+/// for the HLS remuxing and transcoding only serve the stream as it is.
+///
+/// The streaming server is used only once it has responded.
+fn stream_playback<E: Env + 'static>(
+    stream: &Stream,
+    streaming_server: &ActiveServer,
+) -> Option<StreamPlayback> {
+    let capabilities = E::playback_capabilities()?;
+    let streaming_server_url = streaming_server.ready_url();
+    let playback = stream.playback(streaming_server_url, &capabilities)?;
+    match playback.method {
+        PlaybackMethod::Remux | PlaybackMethod::Transcode
+            if !streaming_server.supports(ServerFeature::Transcoding) =>
+        {
+            Some(StreamPlayback {
+                method: PlaybackMethod::DirectPlay,
+                url: stream.streaming_url(streaming_server_url)?,
+            })
+        }
+        _ => Some(playback),
+    }
+}
+
+/// Picks the file to play from a torrent stream without a file index,
+/// e.g. the episode from a season pack instead of its sample or first episode.
+///
+/// For series the file is matched once the [`SeriesInfo`] of the video is known.
+fn torrent_file_update<E: Env + 'static>(
+    selected: &mut Option<Selected>,
+    video_params: &mut Option<VideoParams>,
+    statistics: &mut Option<StatisticsHistory>,
+    torrent_files: &Option<Loadable<Vec<File>, EnvError>>,
+    series_info: Option<&SeriesInfo>,
+    meta_item: &Option<ResourceLoadable<MetaItem>>,
+    streaming_server: &ActiveServer,
+) -> Effects {
+    let meta_item_loading = matches!(
+        meta_item,
+        Some(ResourceLoadable {
+            content: None | Some(Loadable::Loading),
+            ..
+        })
+    );
+    let (selected, files) = match (selected, torrent_files) {
+        (Some(selected), Some(Loadable::Ready(files)))
+            if series_info.is_some() || !meta_item_loading =>
+        {
+            (selected, files)
+        }
+        _ => return Effects::none().unchanged(),
+    };
+    let file = match &mut selected.stream.source {
+        StreamSource::Torrent { file_idx, .. } if file_idx.is_none() => {
+            match find_video_file(files, series_info)
+                .and_then(|idx| Some((u16::try_from(idx).ok()?, files.get(idx)?)))
+            {
+                Some((idx, file)) => {
+                    *file_idx = Some(idx);
+                    file
+                }
+                None => return Effects::none().unchanged(),
+            }
+        }
+        _ => return Effects::none().unchanged(),
+    };
+    selected.playback = stream_playback::<E>(&selected.stream, streaming_server);
+    match video_params {
+        Some(video_params) => video_params.filename = Some(file.name.to_owned()),
+        None => {
+            *video_params = Some(VideoParams {
+                hash: None,
+                size: Some(file.length),
+                filename: Some(file.name.to_owned()),
+                chapters: vec![],
+            })
+        }
+    };
+    // the statistics are requested by file index
+    *statistics = Some(StatisticsHistory::default());
+    Effects::none()
+}
+
+/// Selects the [`LibraryItem`] of the meta item,
+/// it's created from the meta item when it's not in the library.
+pub(crate) fn library_item_update<E: Env + 'static>(
     library_item: &mut Option<LibraryItem>,
-    selected: &Option<Selected>,
+    meta_request: Option<&ResourceRequest>,
     meta_item: &Option<ResourceLoadable<MetaItem>>,
     library: &LibraryBucket,
 ) -> Effects {
-    let next_library_item = match selected {
-        Some(Selected {
-            meta_request: Some(meta_request),
-            ..
-        }) => {
+    let next_library_item = match meta_request {
+        Some(meta_request) => {
             let library_item = library_item
                 .as_ref()
                 .filter(|library_item| library_item.id == meta_request.path.id)
@@ -1062,6 +1768,254 @@ fn subtitles_update<E: Env + 'static>(
     }
 }
 
+/// Recommends one of the stream's or the addons' subtitles to be shown.
+///
+/// Subtitles with identical urls are considered only once and are ranked by:
+/// - the addon subtitle track remembered in the [`StreamItemState`]
+/// - the [`ProfileSettings::subtitles_language`] over the
+///   [`ProfileSettings::secondary_subtitles_language`]
+/// - whether the subtitles url matches the video hash or filename
+///
+/// Subtitles in any other language are recommended only if they were remembered.
+fn selected_subtitles_update(
+    selected_subtitles: &mut Option<Subtitles>,
+    subtitles: &[ResourceLoadable<Vec<Subtitles>>],
+    selected: &Option<Selected>,
+    video_params: &Option<VideoParams>,
+    stream_state: &Option<StreamItemState>,
+    settings: &ProfileSettings,
+) -> Effects {
+    let subtitle_track = stream_state
+        .as_ref()
+        .and_then(|stream_state| stream_state.subtitle_track.as_ref());
+    let next_selected_subtitles = match (selected, subtitle_track) {
+        // the player will restore the embedded track by itself
+        (_, Some(subtitle_track)) if subtitle_track.embedded => None,
+        (Some(selected), _) => selected
+            .stream
+            .subtitles
+            .iter()
+            .chain(
+                subtitles
+                    .iter()
+                    .filter_map(|subtitles| match &subtitles.content {
+                        Some(Loadable::Ready(subtitles)) => Some(subtitles),
+                        _ => None,
+                    })
+                    .flatten(),
+            )
+            .unique_by(|subtitles| subtitles.url.to_owned())
+            .map(|subtitles| {
+                let remembered = subtitle_track
+                    .map(|subtitle_track| subtitle_track.id == subtitles.url.as_str())
+                    .unwrap_or_default();
+                let language_rank = if is_subtitles_language(
+                    &subtitles.lang,
+                    settings.subtitles_language.as_deref(),
+                ) {
+                    2
+                } else if is_subtitles_language(
+                    &subtitles.lang,
+                    settings.secondary_subtitles_language.as_deref(),
+                ) {
+                    1
+                } else {
+                    0
+                };
+                let video_match = video_params
+                    .as_ref()
+                    .map(|video_params| is_video_match(&subtitles.url, video_params))
+                    .unwrap_or_default();
+                ((remembered, language_rank, video_match), subtitles)
+            })
+            .filter(|((remembered, language_rank, _), _)| *remembered || *language_rank > 0)
+            // `min_by_key` keeps the first of the equally ranked subtitles
+            .min_by_key(|(rank, _)| std::cmp::Reverse(*rank))
+            .map(|(_, subtitles)| subtitles.to_owned()),
+        _ => None,
+    };
+    eq_update(selected_subtitles, next_selected_subtitles)
+}
+
+/// Estimates the [`SubtitlesSync`] of the selected subtitles against the reference.
+///
+/// The subtitles files are fetched only while there is a reference to sync against.
+fn subtitles_sync_update<E: Env + 'static>(
+    subtitles_sync: &mut Option<SubtitlesSync>,
+    subtitles_files: &mut Vec<(Url, Loadable<SubtitlesFile, SubtitlesError>)>,
+    selected_subtitles: &Option<Subtitles>,
+    subtitles_reference: &Option<SubtitlesReference>,
+) -> Effects {
+    let urls = match subtitles_reference {
+        Some(subtitles_reference) => selected_subtitles
+            .iter()
+            .map(|selected_subtitles| &selected_subtitles.url)
+            .chain(match subtitles_reference {
+                SubtitlesReference::Url(url) => Some(url),
+                SubtitlesReference::Embedded(_) => None,
+            })
+            .unique()
+            .collect::<Vec<_>>(),
+        None => vec![],
+    };
+    let mut fetch_effects = vec![];
+    let next_subtitles_files = urls
+        .into_iter()
+        .map(
+            |url| match subtitles_files.iter().find(|(file_url, _)| file_url == url) {
+                Some(subtitles_file) => subtitles_file.to_owned(),
+                None => {
+                    fetch_effects.push(fetch_subtitles::<E>(url));
+                    (url.to_owned(), Loadable::Loading)
+                }
+            },
+        )
+        .collect::<Vec<_>>();
+    let subtitles_files_effects = eq_update(subtitles_files, next_subtitles_files);
+
+    let ready_cues = |url: &Url| {
+        subtitles_files
+            .iter()
+            .find_map(|(file_url, subtitles_file)| match subtitles_file {
+                Loadable::Ready(subtitles_file) if file_url == url => {
+                    Some(subtitles_file.cues.as_slice())
+                }
+                _ => None,
+            })
+    };
+    let next_subtitles_sync = match (selected_subtitles, subtitles_reference) {
+        (Some(selected_subtitles), Some(subtitles_reference)) => {
+            let reference_cues = match subtitles_reference {
+                SubtitlesReference::Embedded(cues) => Some(cues.as_slice()),
+                SubtitlesReference::Url(url) => ready_cues(url),
+            };
+            ready_cues(&selected_subtitles.url)
+                .zip(reference_cues)
+                .and_then(|(cues, reference_cues)| SubtitlesSync::estimate(cues, reference_cues))
+        }
+        _ => None,
+    };
+    let subtitles_sync_effects = eq_update(subtitles_sync, next_subtitles_sync);
+
+    subtitles_files_effects
+        .join(Effects::many(fetch_effects).unchanged())
+        .join(subtitles_sync_effects)
+}
+
+fn is_subtitles_language(lang: &str, language: Option<&str>) -> bool {
+    language
+        .map(|language| lang.eq_ignore_ascii_case(language))
+        .unwrap_or_default()
+}
+
+/// Whether the subtitles url contains the video hash or the video filename (without extension).
+fn is_video_match(url: &Url, video_params: &VideoParams) -> bool {
+    let url = percent_decode_str(url.as_str())
+        .decode_utf8_lossy()
+        .to_lowercase();
+    let hash_match = video_params
+        .hash
+        .as_ref()
+        .filter(|hash| !hash.is_empty())
+        .map(|hash| url.contains(&hash.to_lowercase()))
+        .unwrap_or_default();
+    let filename_match = video_params
+        .filename
+        .as_ref()
+        .map(|filename| {
+            filename
+                .rsplit_once('.')
+                .map(|(name, _)| name)
+                .unwrap_or(filename)
+                .to_lowercase()
+        })
+        .filter(|name| !name.is_empty())
+        .map(|name| url.contains(&name))
+        .unwrap_or_default();
+    hash_match || filename_match
+}
+
+/// Adds the stall to the summary and updates the share of the time spent stalled.
+fn stall_ended<E: Env + 'static>(
+    qoe: &mut QoeSummary,
+    buffering_since: DateTime<Utc>,
+    load_time: Option<DateTime<Utc>>,
+) {
+    let now = E::now();
+    qoe.stall_time = qoe
+        .stall_time
+        .saturating_add((now - buffering_since).num_milliseconds());
+    let playback_time = load_time
+        .zip(qoe.startup_time)
+        .map(|(load_time, startup_time)| (now - load_time).num_milliseconds() - startup_time)
+        .unwrap_or_default();
+    qoe.stall_permille = if playback_time > 0 {
+        (qoe.stall_time.saturating_mul(1000) / playback_time).clamp(0, 1000) as u32
+    } else {
+        0
+    };
+}
+
+/// Updates the buffering verdict with the playback time
+/// and asks for the statistics of the torrent stream every [`POLL_STATISTICS_EVERY`].
+fn statistics_update<E: Env + 'static>(
+    statistics: &mut Option<StatisticsHistory>,
+    statistics_polled_at: &mut Option<DateTime<Utc>>,
+    selected: &Option<Selected>,
+    time: u64,
+    duration: u64,
+) -> Effects {
+    let (statistics_history, request) = match (
+        statistics,
+        selected
+            .as_ref()
+            .and_then(|selected| StatisticsRequest::from_stream(&selected.stream)),
+    ) {
+        (Some(statistics_history), Some(request)) => (statistics_history, request),
+        _ => return Effects::none().unchanged(),
+    };
+    let playback_effects = if statistics_history.set_playback(time, duration) {
+        Effects::none()
+    } else {
+        Effects::none().unchanged()
+    };
+    let now = E::now();
+    let poll_effects = match statistics_polled_at {
+        Some(polled_at) if now - *polled_at < *POLL_STATISTICS_EVERY => Effects::none().unchanged(),
+        _ => {
+            *statistics_polled_at = Some(now);
+            Effects::msg(Msg::Internal(Internal::PollStreamingServerStatistics {
+                request,
+                time,
+                duration,
+            }))
+            .unchanged()
+        }
+    };
+    playback_effects.join(poll_effects)
+}
+
+/// Ends the playback session and emits its summary.
+fn qoe_summary_update<E: Env + 'static>(
+    qoe: &mut Option<QoeSummary>,
+    buffering_since: &mut Option<DateTime<Utc>>,
+    load_time: Option<DateTime<Utc>>,
+    analytics_context: Option<&AnalyticsContext>,
+) -> Effects {
+    match qoe.take() {
+        Some(mut qoe) => {
+            if let Some(buffering_since) = buffering_since.take() {
+                stall_ended::<E>(&mut qoe, buffering_since, load_time);
+            };
+            Effects::msg(Msg::Event(Event::PlayerQoe {
+                context: analytics_context.cloned().unwrap_or_default(),
+                qoe,
+            }))
+        }
+        None => Effects::none().unchanged(),
+    }
+}
+
 fn seek_update<E: Env + 'static>(
     selected: Option<&Selected>,
     video_params: Option<&VideoParams>,
@@ -1071,6 +2025,23 @@ fn seek_update<E: Env + 'static>(
     outro: Option<u64>,
 ) -> Effects {
     let has_seeks_or_outro = !seek_history.is_empty() || matches!(outro, Some(outro) if outro > 0);
+    let intro_outro_recorded_effects = match (has_seeks_or_outro, series_info, library_item) {
+        (true, Some(series_info), Some(library_item)) if library_item.r#type == "series" => {
+            match &library_item.state.video_id {
+                Some(video_id) => Effects::msg(Msg::Internal(Internal::IntroOutroRecorded {
+                    meta_id: library_item.id.to_owned(),
+                    video_id: video_id.to_owned(),
+                    season: series_info.season,
+                    duration: library_item.state.duration,
+                    seek_history: seek_history.to_owned(),
+                    outro,
+                }))
+                .unchanged(),
+                None => Effects::none().unchanged(),
+            }
+        }
+        _ => Effects::none().unchanged(),
+    };
     let seek_request_effects = match (
         has_seeks_or_outro,
         selected,
@@ -1112,7 +2083,9 @@ fn seek_update<E: Env + 'static>(
         _ => Effects::none().unchanged(),
     };
 
-    seek_request_effects.join(eq_update(seek_history, vec![]))
+    intro_outro_recorded_effects
+        .join(seek_request_effects)
+        .join(eq_update(seek_history, vec![]))
 }
 
 fn push_seek_to_api<E: Env + 'static>(seek_log_req: SeekLogRequest) -> Effect {
@@ -1131,9 +2104,11 @@ fn push_seek_to_api<E: Env + 'static>(seek_log_req: SeekLogRequest) -> Effect {
     .into()
 }
 
+#[allow(clippy::too_many_arguments)]
 fn intro_outro_update<E: Env + 'static>(
     intro_outro: &mut Option<IntroOutro>,
     profile: &Profile,
+    intro_outro_bucket: &IntroOutroBucket,
     selected: Option<&Selected>,
     video_params: Option<&VideoParams>,
     series_info: Option<&SeriesInfo>,
@@ -1149,7 +2124,7 @@ fn intro_outro_update<E: Env + 'static>(
         skip_gaps,
     );
 
-    let intro_outro_effects = match (skip_gaps, library_item) {
+    let api_intro_outro = match (skip_gaps, library_item) {
         (Some((_, Loadable::Ready(response))), Some(library_item)) => {
             let outro_time = {
                 let outro_durations = response.gaps.iter().filter_map(|(duration, skip_gaps)| {
@@ -1211,20 +2186,88 @@ fn intro_outro_update<E: Env + 'static>(
               })
             };
 
-            eq_update(
-                intro_outro,
-                Some(IntroOutro {
-                    intro: intro_time,
-                    outro: outro_time,
-                }),
-            )
+            Some(IntroOutro {
+                intro: intro_time,
+                outro: outro_time,
+            })
         }
-        _ => Effects::none().unchanged(),
+        _ => None,
+    };
+    // the chapters are exact for the played file, while the locally recorded
+    // skips of the other episodes are used only when nothing else is known
+    let chapters_intro_outro = video_params
+        .map(|video_params| chapters_intro_outro(&video_params.chapters))
+        .unwrap_or(IntroOutro {
+            intro: None,
+            outro: None,
+        });
+    let local_item = library_item
+        .filter(|library_item| library_item.r#type == "series")
+        .and_then(|library_item| intro_outro_bucket.items.get(&library_item.id));
+    let local_intro = local_item.and_then(|item| item.intro());
+    let local_outro = match (local_item, series_info, library_item) {
+        (Some(item), Some(series_info), Some(library_item)) => {
+            item.outro(series_info.season, library_item.state.duration)
+        }
+        _ => None,
+    };
+    let next_intro_outro = IntroOutro {
+        intro: chapters_intro_outro
+            .intro
+            .or_else(|| {
+                api_intro_outro
+                    .as_ref()
+                    .and_then(|api| api.intro.to_owned())
+            })
+            .or(local_intro),
+        outro: chapters_intro_outro
+            .outro
+            .or_else(|| api_intro_outro.as_ref().and_then(|api| api.outro))
+            .or(local_outro),
+    };
+    let intro_outro_effects = if api_intro_outro.is_some()
+        || next_intro_outro.intro.is_some()
+        || next_intro_outro.outro.is_some()
+    {
+        eq_update(intro_outro, Some(next_intro_outro))
+    } else {
+        Effects::none().unchanged()
     };
 
     skip_gaps_effects.join(intro_outro_effects)
 }
 
+/// Finds the intro and the outro by the titles of the chapters.
+fn chapters_intro_outro(chapters: &[VideoChapter]) -> IntroOutro {
+    let chapters = chapters
+        .iter()
+        .sorted_by_key(|chapter| chapter.start)
+        .collect::<Vec<_>>();
+    let intro = chapters
+        .iter()
+        .enumerate()
+        .find(|(_, chapter)| INTRO_CHAPTER_REGEX.is_match(&chapter.title))
+        .and_then(|(index, chapter)| {
+            chapter
+                .end
+                .or_else(|| chapters.get(index + 1).map(|next| next.start))
+                .filter(|end| *end > chapter.start)
+                .map(|end| IntroData {
+                    from: chapter.start,
+                    to: end,
+                    duration: None,
+                })
+        });
+    let outro = chapters
+        .iter()
+        .find(|chapter| {
+            !INTRO_CHAPTER_REGEX.is_match(&chapter.title)
+                && OUTRO_CHAPTER_REGEX.is_match(&chapter.title)
+        })
+        .map(|chapter| chapter.start);
+    IntroOutro { intro, outro }
+}
+
 fn skip_gaps_update<E: Env + 'static>(
     profile: &Profile,
     selected: Option<&Selected>,
@@ -1331,12 +2374,14 @@ mod test {
         models::common::{Loadable, ResourceLoadable},
         types::{
             addon::{ResourcePath, ResourceRequest},
-            resource::{SeriesInfo, Stream, Video},
+            profile::Settings,
+            resource::{SeriesInfo, Stream, Subtitles, Video},
+            streams::{StreamItemState, SubtitleTrack},
         },
         unit_tests::TestEnv,
     };
 
-    use super::{next_streams_update, Selected};
+    use super::{next_streams_update, selected_subtitles_update, Selected, VideoParams};
 
     #[test]
     fn next_streams_update_with_a_stream_from_next_video() {
@@ -1364,6 +2409,7 @@ mod test {
             }),
             meta_request: None,
             subtitles_path: None,
+            playback: None,
         };
 
         // Test that it should update the next_streams from the next_video if Video has one stream
@@ -1484,4 +2530,125 @@ mod test {
             );
         }
     }
+
+    #[test]
+    fn selected_subtitles_update_ranking() {
+        let subtitles = |lang: &str, url: &str| Subtitles {
+            lang: lang.to_owned(),
+            url: url.parse().unwrap(),
+        };
+        let addon_subtitles = ResourceLoadable {
+            request: ResourceRequest {
+                base: "https://subtitles.addon".parse().unwrap(),
+                path: ResourcePath::without_extra("subtitles", "movie", "tt1"),
+            },
+            content: Some(Loadable::Ready(vec![
+                subtitles("bul", "https://subs/1.srt"),
+                subtitles("eng", "https://subs/2.srt"),
+                subtitles("eng", "https://subs/Movie.2020.1080p.srt"),
+                subtitles("eng", "https://subs/2.srt"),
+            ])),
+        };
+        let selected = Some(Selected {
+            stream: Stream::youtube("yt_id:1").unwrap(),
+            stream_request: None,
+            meta_request: None,
+            subtitles_path: None,
+            playback: None,
+        });
+        let video_params = Some(VideoParams {
+            hash: None,
+            size: None,
+            filename: Some("Movie.2020.1080p.mkv".to_owned()),
+            chapters: vec![],
+        });
+        let settings = Settings {
+            subtitles_language: Some("eng".to_owned()),
+            secondary_subtitles_language: Some("bul".to_owned()),
+            ..Default::default()
+        };
+        let stream_state = |id: &str, embedded: bool| {
+            Some(StreamItemState {
+                subtitle_track: Some(SubtitleTrack {
+                    id: id.to_owned(),
+                    embedded,
+                    language: None,
+                }),
+                subtitle_delay: None,
+                audio_track: None,
+                audio_delay: None,
+                playback_speed: None,
+                player_type: None,
+            })
+        };
+
+        // the subtitles matching the filename are preferred over the rest in the same language
+        let mut selected_subtitles = None;
+        selected_subtitles_update(
+            &mut selected_subtitles,
+            &[addon_subtitles.to_owned()],
+            &selected,
+            &video_params,
+            &None,
+            &settings,
+        );
+        assert_eq!(
+            selected_subtitles,
+            Some(subtitles("eng", "https://subs/Movie.2020.1080p.srt"))
+        );
+
+        // without video params the first subtitles in the preferred language are selected
+        selected_subtitles_update(
+            &mut selected_subtitles,
+            &[addon_subtitles.to_owned()],
+            &selected,
+            &None,
+            &None,
+            &settings,
+        );
+        assert_eq!(
+            selected_subtitles,
+            Some(subtitles("eng", "https://subs/2.srt"))
+        );
+
+        // the remembered track wins over the preferred language
+        selected_subtitles_update(
+            &mut selected_subtitles,
+            &[addon_subtitles.to_owned()],
+            &selected,
+            &video_params,
+            &stream_state("https://subs/1.srt", false),
+            &settings,
+        );
+        assert_eq!(
+            selected_subtitles,
+            Some(subtitles("bul", "https://subs/1.srt"))
+        );
+
+        // an embedded track was selected by the user
+        selected_subtitles_update(
+            &mut selected_subtitles,
+            &[addon_subtitles.to_owned()],
+            &selected,
+            &video_params,
+            &stream_state("1", true),
+            &settings,
+        );
+        assert_eq!(selected_subtitles, None);
+
+        // none of the subtitles are in the user's languages
+        selected_subtitles_update(
+            &mut selected_subtitles,
+            &[addon_subtitles],
+            &selected,
+            &video_params,
+            &None,
+            &Settings {
+                subtitles_language: Some("fre".to_owned()),
+                secondary_subtitles_language: None,
+                ..Default::default()
+            },
+        );
+        assert_eq!(selected_subtitles, None);
+    }
 }
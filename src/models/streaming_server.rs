@@ -4,19 +4,24 @@ use crate::models::ctx::{Ctx, CtxError};
 use crate::runtime::msg::{
     Action, ActionStreamingServer, CreateTorrentArgs, Event, Internal, Msg, PlayOnDeviceArgs,
 };
-use crate::runtime::{Effect, EffectFuture, Effects, Env, EnvError, EnvFutureExt, UpdateWithCtx};
+use crate::runtime::{
+    ConditionalSend, Effect, EffectFuture, Effects, Env, EnvError, EnvFutureExt, UpdateWithCtx,
+};
 use crate::types::addon::ResourcePath;
 use crate::types::api::SuccessResponse;
-use crate::types::profile::{AuthKey, Profile};
+use crate::types::profile::{AuthKey, Profile, Settings as ProfileSettings};
+use crate::types::resource::{Stream, StreamSource};
 use crate::types::streaming_server::{
-    GetHTTPSResponse, NetworkInfo, Settings, SettingsResponse, Statistics,
+    ActiveServer, GetHTTPSResponse, NetworkInfo, RemoteAccess, RemoteAccessError, RemoteAccessStep,
+    ServerCompatibility, ServerFeature, Settings, SettingsError, SettingsResponse, Statistics,
+    StatisticsHistory,
 };
+use crate::types::torrent::TorrentInfo;
 use enclose::enclose;
 use futures::{FutureExt, TryFutureExt};
 use http::request::Request;
-use magnet_url::{Magnet, MagnetError};
+use itertools::Itertools;
 use serde::{Deserialize, Serialize};
-use sha1::{Digest, Sha1};
 use std::iter;
 use url::Url;
 
@@ -35,6 +40,23 @@ pub struct StatisticsRequest {
     pub file_idx: u16,
 }
 
+impl StatisticsRequest {
+    /// Only torrent streams with a known file index have statistics.
+    pub fn from_stream(stream: &Stream) -> Option<Self> {
+        match &stream.source {
+            StreamSource::Torrent {
+                info_hash,
+                file_idx: Some(file_idx),
+                ..
+            } => Some(StatisticsRequest {
+                info_hash: hex::encode(info_hash),
+                file_idx: *file_idx,
+            }),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Selected {
@@ -42,87 +64,188 @@ pub struct Selected {
     pub statistics: Option<StatisticsRequest>,
 }
 
+/// The result of probing a known streaming server for its settings.
+#[derive(Clone, PartialEq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerHealth {
+    pub url: Url,
+    pub settings: Loadable<Settings, EnvError>,
+}
+
 #[derive(Clone, Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct StreamingServer {
+    /// The server in use, the first known server unless it was unreachable.
     pub selected: Selected,
+    /// The health of the [`ProfileSettings::streaming_server_url`]
+    /// followed by the [`ProfileSettings::streaming_servers`].
+    pub servers: Vec<ServerHealth>,
     pub settings: Loadable<Settings, EnvError>,
+    /// Why the last [`ActionStreamingServer::UpdateSettings`] was rejected.
+    pub settings_errors: Vec<SettingsError>,
+    /// The features the selected server should be updated for,
+    /// the requests for them are not sent once its version is known.
+    pub compatibility: Option<ServerCompatibility>,
     pub base_url: Option<Url>,
     pub remote_url: Option<Url>,
+    /// The setup started by [`ActionStreamingServer::SetupRemoteAccess`].
+    pub remote_access: Option<RemoteAccess>,
     pub playback_devices: Loadable<Vec<PlaybackDevice>, EnvError>,
     pub network_info: Loadable<NetworkInfo, EnvError>,
     pub torrent: Option<(String, Loadable<ResourcePath, EnvError>)>,
     /// [`Loadable::Loading`] is used only on the first statistics request.
     pub statistics: Option<Loadable<Statistics, EnvError>>,
+    /// The statistics of the [`Selected::statistics`] request, polled while the torrent is playing.
+    pub statistics_history: Option<StatisticsHistory>,
 }
 
 impl StreamingServer {
     pub fn new<E: Env + 'static>(profile: &Profile) -> (Self, Effects) {
-        let effects = Effects::many(vec![
-            get_settings::<E>(&profile.settings.streaming_server_url),
-            get_playback_devices::<E>(&profile.settings.streaming_server_url),
-            get_network_info::<E>(&profile.settings.streaming_server_url),
-        ]);
+        let servers = servers(&profile.settings);
+        let effects = Effects::many(
+            servers
+                .iter()
+                .map(|server| get_settings::<E>(&server.url))
+                .chain([
+                    get_playback_devices::<E>(&profile.settings.streaming_server_url),
+                    get_network_info::<E>(&profile.settings.streaming_server_url),
+                ])
+                .collect(),
+        );
         (
             Self {
                 selected: Selected {
                     transport_url: profile.settings.streaming_server_url.to_owned(),
                     statistics: None,
                 },
+                servers,
                 settings: Loadable::Loading,
+                settings_errors: vec![],
+                compatibility: None,
                 base_url: None,
                 remote_url: None,
+                remote_access: None,
                 playback_devices: Loadable::Loading,
                 network_info: Loadable::Loading,
                 torrent: None,
                 statistics: None,
+                statistics_history: None,
             },
             effects.unchanged(),
         )
     }
+    /// Switches to the given server, loading its settings, playback devices and network info.
+    fn select<E: Env + 'static>(&mut self, transport_url: Url) -> Effects {
+        self.selected = Selected {
+            transport_url,
+            statistics: None,
+        };
+        self.settings = Loadable::Loading;
+        self.settings_errors = vec![];
+        self.compatibility = None;
+        self.playback_devices = Loadable::Loading;
+        self.network_info = Loadable::Loading;
+        self.base_url = None;
+        self.remote_url = None;
+        self.remote_access = None;
+        self.torrent = None;
+        self.statistics = None;
+        self.statistics_history = None;
+        Effects::many(vec![
+            get_settings::<E>(&self.selected.transport_url),
+            get_playback_devices::<E>(&self.selected.transport_url),
+            get_network_info::<E>(&self.selected.transport_url),
+        ])
+    }
 }
 
 impl<E: Env + 'static> UpdateWithCtx<E> for StreamingServer {
     fn update(&mut self, msg: &Msg, ctx: &Ctx) -> Effects {
-        match msg {
+        let effects = match msg {
             Msg::Action(Action::StreamingServer(ActionStreamingServer::Reload)) => {
-                let settings_effects = eq_update(&mut self.settings, Loadable::Loading);
-                let network_info_effects = eq_update(&mut self.network_info, Loadable::Loading);
-                let base_url_effects = eq_update(&mut self.base_url, None);
-                let remote_url_effects = eq_update(&mut self.remote_url, None);
-                Effects::many(vec![
-                    get_settings::<E>(&self.selected.transport_url),
-                    get_playback_devices::<E>(&self.selected.transport_url),
-                    get_network_info::<E>(&self.selected.transport_url),
-                ])
-                .unchanged()
-                .join(settings_effects)
-                .join(network_info_effects)
-                .join(base_url_effects)
-                .join(remote_url_effects)
-            }
-            Msg::Action(Action::StreamingServer(ActionStreamingServer::UpdateSettings(
-                settings,
-            ))) if self.settings.is_ready() => {
-                let settings_effects =
-                    eq_update(&mut self.settings, Loadable::Ready(settings.to_owned()));
-                let remote_url_effects =
-                    update_remote_url::<E>(&mut self.remote_url, &self.selected, settings, ctx);
-                Effects::one(set_settings::<E>(&self.selected.transport_url, settings))
+                // the first known server is preferred again, once it's reachable
+                let primary_url = ctx.profile.settings.streaming_server_url.to_owned();
+                let select_effects = if self.selected.transport_url != primary_url {
+                    self.select::<E>(primary_url)
+                } else {
+                    let settings_effects = eq_update(&mut self.settings, Loadable::Loading);
+                    let network_info_effects = eq_update(&mut self.network_info, Loadable::Loading);
+                    let base_url_effects = eq_update(&mut self.base_url, None);
+                    let remote_url_effects = eq_update(&mut self.remote_url, None);
+                    let remote_access_effects = eq_update(&mut self.remote_access, None);
+                    let playback_devices_effects =
+                        match supports(&self.compatibility, ServerFeature::Casting) {
+                            true => Effects::one(get_playback_devices::<E>(
+                                &self.selected.transport_url,
+                            )),
+                            false => Effects::none(),
+                        };
+                    Effects::many(vec![
+                        get_settings::<E>(&self.selected.transport_url),
+                        get_network_info::<E>(&self.selected.transport_url),
+                    ])
+                    .join(playback_devices_effects)
                     .unchanged()
                     .join(settings_effects)
+                    .join(network_info_effects)
+                    .join(base_url_effects)
                     .join(remote_url_effects)
+                    .join(remote_access_effects)
+                };
+                let servers_effects = eq_update(&mut self.servers, servers(&ctx.profile.settings));
+                let probe_effects = Effects::many(
+                    self.servers
+                        .iter()
+                        .filter(|server| server.url != self.selected.transport_url)
+                        .map(|server| get_settings::<E>(&server.url))
+                        .collect(),
+                )
+                .unchanged();
+                select_effects.join(servers_effects).join(probe_effects)
             }
+            Msg::Action(Action::StreamingServer(ActionStreamingServer::UpdateSettings(
+                settings,
+            ))) if self.settings.is_ready() => settings_update::<E>(
+                &mut self.settings,
+                &mut self.settings_errors,
+                &mut self.remote_url,
+                &self.selected,
+                &self.compatibility,
+                settings,
+                ctx,
+            ),
+            Msg::Action(Action::StreamingServer(ActionStreamingServer::ApplySettingsPreset(
+                preset,
+            ))) => match &self.settings {
+                Loadable::Ready(settings) => {
+                    let settings = preset.apply(settings);
+                    settings_update::<E>(
+                        &mut self.settings,
+                        &mut self.settings_errors,
+                        &mut self.remote_url,
+                        &self.selected,
+                        &self.compatibility,
+                        &settings,
+                        ctx,
+                    )
+                }
+                _ => Effects::none().unchanged(),
+            },
             Msg::Action(Action::StreamingServer(ActionStreamingServer::CreateTorrent(
                 CreateTorrentArgs::Magnet(magnet),
-            ))) => match parse_magnet(magnet) {
-                Ok((info_hash, announce)) => {
+            ))) => match TorrentInfo::from_magnet(magnet) {
+                Ok(info) => {
+                    let info_hash = hex::encode(info.info_hash);
                     let torrent_effects = eq_update(
                         &mut self.torrent,
                         Some((info_hash.to_owned(), Loadable::Loading)),
                     );
                     Effects::many(vec![
-                        create_magnet::<E>(&self.selected.transport_url, &info_hash, &announce),
+                        create_magnet::<E>(
+                            &self.selected.transport_url,
+                            &info_hash,
+                            &info.announce,
+                        ),
                         Effect::Msg(Box::new(Msg::Event(Event::MagnetParsed {
                             magnet: magnet.to_owned(),
                         }))),
@@ -146,8 +269,9 @@ impl<E: Env + 'static> UpdateWithCtx<E> for StreamingServer {
             },
             Msg::Action(Action::StreamingServer(ActionStreamingServer::CreateTorrent(
                 CreateTorrentArgs::File(torrent),
-            ))) => match parse_torrent(torrent) {
-                Ok((info_hash, _)) => {
+            ))) => match TorrentInfo::from_torrent(torrent) {
+                Ok(info) => {
+                    let info_hash = hex::encode(info.info_hash);
                     let torrent_effects = eq_update(
                         &mut self.torrent,
                         Some((info_hash.to_owned(), Loadable::Loading)),
@@ -176,23 +300,48 @@ impl<E: Env + 'static> UpdateWithCtx<E> for StreamingServer {
                 }
             },
             Msg::Action(Action::StreamingServer(ActionStreamingServer::GetStatistics(request))) => {
-                let selected_effects =
-                    eq_update(&mut self.selected.statistics, Some(request.to_owned()));
-                let is_different_request = self.selected.statistics.as_ref() != Some(request);
-                //set the Loading state only on the first fetch of the statistics
-                let statistics_effects = match (&self.statistics, is_different_request) {
-                    (None, _) | (_, true) => {
-                        eq_update(&mut self.statistics, Some(Loadable::Loading))
+                let selected_effects = statistics_request_update(
+                    &mut self.selected.statistics,
+                    &mut self.statistics,
+                    &mut self.statistics_history,
+                    request,
+                );
+                let statistics_effects = statistics_fetch_update::<E>(
+                    &mut self.statistics,
+                    &self.selected,
+                    &self.compatibility,
+                    request,
+                );
+                selected_effects.join(statistics_effects)
+            }
+            Msg::Internal(Internal::PollStreamingServerStatistics {
+                request,
+                time,
+                duration,
+            }) => {
+                let selected_effects = statistics_request_update(
+                    &mut self.selected.statistics,
+                    &mut self.statistics,
+                    &mut self.statistics_history,
+                    request,
+                );
+                let playback_effects = match &mut self.statistics_history {
+                    Some(statistics_history)
+                        if statistics_history.set_playback(*time, *duration) =>
+                    {
+                        Effects::none()
                     }
                     _ => Effects::none().unchanged(),
                 };
-                Effects::one(get_torrent_statistics::<E>(
-                    &self.selected.transport_url,
+                let statistics_effects = statistics_fetch_update::<E>(
+                    &mut self.statistics,
+                    &self.selected,
+                    &self.compatibility,
                     request,
-                ))
-                .unchanged()
-                .join(selected_effects)
-                .join(statistics_effects)
+                );
+                selected_effects
+                    .join(statistics_effects)
+                    .join(playback_effects)
             }
             Msg::Action(Action::StreamingServer(ActionStreamingServer::PlayOnDevice(args))) => {
                 match Url::parse(&args.source).is_ok() {
@@ -215,64 +364,162 @@ impl<E: Env + 'static> UpdateWithCtx<E> for StreamingServer {
                     _ => Effects::none().unchanged(),
                 }
             }
-            Msg::Internal(Internal::ProfileChanged)
-                if self.selected.transport_url != ctx.profile.settings.streaming_server_url =>
-            {
-                self.selected = Selected {
-                    transport_url: ctx.profile.settings.streaming_server_url.to_owned(),
-                    statistics: None,
+            Msg::Action(Action::StreamingServer(ActionStreamingServer::SetupRemoteAccess(
+                interface,
+            ))) if self.settings.is_ready() => {
+                let interface_available = match &self.network_info {
+                    Loadable::Ready(network_info) => {
+                        network_info.available_interfaces.contains(interface)
+                    }
+                    _ => false,
                 };
-                self.settings = Loadable::Loading;
-                self.network_info = Loadable::Loading;
-                self.base_url = None;
-                self.remote_url = None;
-                self.torrent = None;
-                self.statistics = None;
-                Effects::many(vec![
-                    get_settings::<E>(&self.selected.transport_url),
-                    get_playback_devices::<E>(&self.selected.transport_url),
-                    get_network_info::<E>(&self.selected.transport_url),
-                ])
+                let mut remote_access = RemoteAccess::new(interface.to_owned());
+                let certificate_effects = match (interface_available, ctx.profile.auth_key()) {
+                    _ if !supports(&self.compatibility, ServerFeature::RemoteHttps) => {
+                        remote_access.step =
+                            RemoteAccessStep::Failed(RemoteAccessError::UnsupportedServer);
+                        Effects::none().unchanged()
+                    }
+                    (true, Some(auth_key)) => Effects::one(get_https_endpoint::<E>(
+                        &self.selected.transport_url,
+                        auth_key,
+                        interface,
+                    ))
+                    .unchanged(),
+                    (true, None) => {
+                        remote_access.step =
+                            RemoteAccessStep::Failed(RemoteAccessError::NotLoggedIn);
+                        Effects::none().unchanged()
+                    }
+                    (false, _) => {
+                        remote_access.step = RemoteAccessStep::Failed(
+                            RemoteAccessError::UnavailableInterface(interface.to_owned()),
+                        );
+                        Effects::none().unchanged()
+                    }
+                };
+                eq_update(&mut self.remote_access, Some(remote_access)).join(certificate_effects)
             }
-            Msg::Internal(Internal::StreamingServerSettingsResult(url, result))
-                if self.selected.transport_url == *url && self.settings.is_loading() =>
+            Msg::Internal(Internal::ProfileChanged)
+                if self
+                    .servers
+                    .iter()
+                    .map(|server| &server.url)
+                    .ne(server_urls(&ctx.profile.settings)) =>
             {
-                match result {
-                    Ok(settings) => {
-                        let settings_effects = eq_update(
-                            &mut self.settings,
-                            Loadable::Ready(settings.values.to_owned()),
-                        );
-                        let base_url_effects =
-                            eq_update(&mut self.base_url, Some(settings.base_url.to_owned()));
-                        let remote_url_effects = update_remote_url::<E>(
-                            &mut self.remote_url,
-                            &self.selected,
-                            &settings.values,
-                            ctx,
-                        );
-                        settings_effects
-                            .join(base_url_effects)
-                            .join(remote_url_effects)
+                self.servers = servers(&ctx.profile.settings);
+                let select_effects =
+                    self.select::<E>(ctx.profile.settings.streaming_server_url.to_owned());
+                let probe_effects = Effects::many(
+                    self.servers
+                        .iter()
+                        .skip(1)
+                        .map(|server| get_settings::<E>(&server.url))
+                        .collect(),
+                )
+                .unchanged();
+                select_effects.join(probe_effects)
+            }
+            Msg::Internal(Internal::StreamingServerSettingsResult(url, result)) => {
+                let servers_effects = match self
+                    .servers
+                    .iter_mut()
+                    .find(|server| server.url == *url && server.settings.is_loading())
+                {
+                    Some(server) => {
+                        server.settings = match result {
+                            Ok(settings) => Loadable::Ready(settings.values.to_owned()),
+                            Err(error) => Loadable::Err(error.to_owned()),
+                        };
+                        Effects::none()
                     }
-                    Err(error) => {
-                        let base_url_effects = eq_update(&mut self.base_url, None);
-                        let remote_url_effects = eq_update(&mut self.remote_url, None);
-                        let playback_devices_effects =
-                            eq_update(&mut self.playback_devices, Loadable::Err(error.to_owned()));
-                        let network_info_effects =
-                            eq_update(&mut self.network_info, Loadable::Err(error.to_owned()));
-                        let settings_effects =
-                            eq_update(&mut self.settings, Loadable::Err(error.to_owned()));
-                        let torrent_effects = eq_update(&mut self.torrent, None);
-                        base_url_effects
-                            .join(remote_url_effects)
-                            .join(playback_devices_effects)
-                            .join(network_info_effects)
-                            .join(settings_effects)
-                            .join(torrent_effects)
+                    None => Effects::none().unchanged(),
+                };
+                let selected_effects = if self.selected.transport_url == *url
+                    && self.settings.is_loading()
+                {
+                    // fail over to the next known server which responded to the probe,
+                    // or to the next one which is still probed if none did
+                    let next_url = self
+                        .servers
+                        .iter()
+                        .filter(|server| server.url != *url && !server.settings.is_err())
+                        .min_by_key(|server| !server.settings.is_ready())
+                        .map(|server| server.url.to_owned());
+                    match (result, next_url) {
+                        (Ok(settings), _) => {
+                            let settings_effects = eq_update(
+                                &mut self.settings,
+                                Loadable::Ready(settings.values.to_owned()),
+                            );
+                            let compatibility_effects = eq_update(
+                                &mut self.compatibility,
+                                Some(ServerCompatibility::new(&settings.values.server_version)),
+                            );
+                            let base_url_effects =
+                                eq_update(&mut self.base_url, Some(settings.base_url.to_owned()));
+                            let remote_url_effects = update_remote_url::<E>(
+                                &mut self.remote_url,
+                                &self.selected,
+                                &self.compatibility,
+                                &settings.values,
+                                ctx,
+                            );
+                            // the casting devices were requested before the version was known
+                            let playback_devices_effects =
+                                match supports(&self.compatibility, ServerFeature::Casting) {
+                                    true => Effects::none().unchanged(),
+                                    false => eq_update(
+                                        &mut self.playback_devices,
+                                        Loadable::Err(unsupported_feature_error(
+                                            ServerFeature::Casting,
+                                        )),
+                                    ),
+                                };
+                            settings_effects
+                                .join(compatibility_effects)
+                                .join(base_url_effects)
+                                .join(remote_url_effects)
+                                .join(playback_devices_effects)
+                        }
+                        (Err(_), Some(next_url)) => self.select::<E>(next_url),
+                        (Err(error), None) => {
+                            let base_url_effects = eq_update(&mut self.base_url, None);
+                            let remote_url_effects = eq_update(&mut self.remote_url, None);
+                            let playback_devices_effects = eq_update(
+                                &mut self.playback_devices,
+                                Loadable::Err(error.to_owned()),
+                            );
+                            let network_info_effects =
+                                eq_update(&mut self.network_info, Loadable::Err(error.to_owned()));
+                            let settings_effects =
+                                eq_update(&mut self.settings, Loadable::Err(error.to_owned()));
+                            let torrent_effects = eq_update(&mut self.torrent, None);
+                            base_url_effects
+                                .join(remote_url_effects)
+                                .join(playback_devices_effects)
+                                .join(network_info_effects)
+                                .join(settings_effects)
+                                .join(torrent_effects)
+                        }
                     }
-                }
+                } else {
+                    Effects::none().unchanged()
+                };
+                let remote_access_effects = remote_access_probe_update::<E>(
+                    &mut self.remote_access,
+                    &mut self.settings,
+                    &mut self.settings_errors,
+                    &mut self.remote_url,
+                    &self.selected,
+                    &self.compatibility,
+                    url,
+                    result,
+                    ctx,
+                );
+                servers_effects
+                    .join(selected_effects)
+                    .join(remote_access_effects)
             }
             Msg::Internal(Internal::StreamingServerPlaybackDevicesResult(url, result))
                 if self.selected.transport_url == *url && self.playback_devices.is_loading() =>
@@ -303,8 +550,53 @@ impl<E: Env + 'static> UpdateWithCtx<E> for StreamingServer {
             Msg::Internal(Internal::StreamingServerUpdateSettingsResult(url, result))
                 if self.selected.transport_url == *url =>
             {
-                match result {
+                let remote_access_saving = matches!(
+                    &self.remote_access,
+                    Some(RemoteAccess {
+                        step: RemoteAccessStep::Saving { .. },
+                        ..
+                    })
+                );
+                let remote_access_effects = match &mut self.remote_access {
+                    Some(remote_access) => match (&remote_access.step, result) {
+                        (RemoteAccessStep::Saving { remote_url, .. }, Ok(_)) => {
+                            let remote_url = remote_url.to_owned();
+                            remote_access.step = RemoteAccessStep::Ready(remote_url.to_owned());
+                            Effects::none().join(eq_update(&mut self.remote_url, Some(remote_url)))
+                        }
+                        (
+                            RemoteAccessStep::Saving {
+                                previous_remote_https,
+                                ..
+                            },
+                            Err(error),
+                        ) => {
+                            let previous_remote_https = previous_remote_https.to_owned();
+                            remote_access.step =
+                                RemoteAccessStep::Failed(RemoteAccessError::Save(error.message()));
+                            let settings_effects = match &mut self.settings {
+                                Loadable::Ready(settings) => {
+                                    settings.remote_https = previous_remote_https;
+                                    Effects::none().join(update_remote_url::<E>(
+                                        &mut self.remote_url,
+                                        &self.selected,
+                                        &self.compatibility,
+                                        settings,
+                                        ctx,
+                                    ))
+                                }
+                                _ => Effects::none().unchanged(),
+                            };
+                            Effects::none().join(settings_effects)
+                        }
+                        _ => Effects::none().unchanged(),
+                    },
+                    None => Effects::none().unchanged(),
+                };
+                let settings_effects = match result {
                     Ok(_) => Effects::none().unchanged(),
+                    // the server responded to the probe, so only the remote access is reverted
+                    Err(_) if remote_access_saving => Effects::none().unchanged(),
                     Err(error) => {
                         let base_url_effects = eq_update(&mut self.base_url, None);
                         let remote_url_effects = eq_update(&mut self.remote_url, None);
@@ -322,19 +614,33 @@ impl<E: Env + 'static> UpdateWithCtx<E> for StreamingServer {
                             .join(settings_effects)
                             .join(torrent_effects)
                     }
-                }
+                };
+                remote_access_effects.join(settings_effects)
             }
             Msg::Internal(Internal::StreamingServerStatisticsResult((url, request), result))
                 if self.selected.transport_url == *url
                     && self.selected.statistics.as_ref() == Some(request) =>
             {
-                let loadable = match result {
-                    Ok(Some(statistics)) => Loadable::Ready(statistics.to_owned()),
+                let history_effects = match (&mut self.statistics_history, result) {
+                    (Some(statistics_history), Ok(statistics))
+                        if statistics_history.push(E::now(), statistics.as_ref()) =>
+                    {
+                        Effects::none()
+                    }
+                    _ => Effects::none().unchanged(),
+                };
+                let statistics_effects = match result {
+                    Ok(Some(statistics)) => eq_update(
+                        &mut self.statistics,
+                        Some(Loadable::Ready(statistics.to_owned())),
+                    ),
                     // we've loaded the whole stream, no need to update the statistics.
-                    Ok(None) => return Effects::none().unchanged(),
-                    Err(error) => Loadable::Err(error.to_owned()),
+                    Ok(None) => Effects::none().unchanged(),
+                    Err(error) => {
+                        eq_update(&mut self.statistics, Some(Loadable::Err(error.to_owned())))
+                    }
                 };
-                eq_update(&mut self.statistics, Some(loadable))
+                statistics_effects.join(history_effects)
             }
             Msg::Internal(Internal::StreamingServerPlayOnDeviceResult(device, result)) => {
                 match result {
@@ -350,12 +656,40 @@ impl<E: Env + 'static> UpdateWithCtx<E> for StreamingServer {
             Msg::Internal(Internal::StreamingServerGetHTTPSResult(url, result))
                 if self.selected.transport_url == *url =>
             {
-                match result {
-                    Ok(GetHTTPSResponse { domain, port, .. }) => {
+                match (&mut self.remote_access, result) {
+                    // the remote url is set only once the setup is verified and saved
+                    (Some(remote_access), _)
+                        if matches!(remote_access.step, RemoteAccessStep::Saving { .. }) =>
+                    {
+                        Effects::none().unchanged()
+                    }
+                    (Some(remote_access), result)
+                        if remote_access.step == RemoteAccessStep::RequestingCertificate =>
+                    {
+                        let remote_url = result.as_ref().map_err(EnvError::message).and_then(
+                            |GetHTTPSResponse { domain, port, .. }| {
+                                Url::parse(&format!("https://{domain}:{port}"))
+                                    .map_err(|error| error.to_string())
+                            },
+                        );
+                        match remote_url {
+                            Ok(remote_url) => {
+                                remote_access.step =
+                                    RemoteAccessStep::Probing(remote_url.to_owned());
+                                Effects::one(get_settings::<E>(&remote_url))
+                            }
+                            Err(error) => {
+                                remote_access.step =
+                                    RemoteAccessStep::Failed(RemoteAccessError::Certificate(error));
+                                Effects::none()
+                            }
+                        }
+                    }
+                    (_, Ok(GetHTTPSResponse { domain, port, .. })) => {
                         let remote_url = Url::parse(&format!("https://{domain}:{port}")).ok();
                         eq_update(&mut self.remote_url, remote_url)
                     }
-                    Err(_) => Effects::none().unchanged(),
+                    (_, Err(_)) => Effects::none().unchanged(),
                 }
             }
             Msg::Internal(Internal::StreamingServerCreateTorrentResult(
@@ -379,10 +713,186 @@ impl<E: Env + 'static> UpdateWithCtx<E> for StreamingServer {
                 _ => Effects::none().unchanged(),
             },
             _ => Effects::none().unchanged(),
+        };
+        let active_server_effects =
+            active_server_update(&self.selected, &self.settings, &self.compatibility, ctx);
+        effects.join(active_server_effects)
+    }
+}
+
+/// Shares the server in use with the other models through the [`Ctx::streaming_server`],
+/// whenever it's switched or its settings are loaded.
+fn active_server_update(
+    selected: &Selected,
+    settings: &Loadable<Settings, EnvError>,
+    compatibility: &Option<ServerCompatibility>,
+    ctx: &Ctx,
+) -> Effects {
+    let active_server = ActiveServer {
+        url: selected.transport_url.to_owned(),
+        compatibility: match settings {
+            Loadable::Ready(_) => compatibility.to_owned(),
+            _ => None,
+        },
+    };
+    if ctx.streaming_server == active_server {
+        return Effects::none().unchanged();
+    };
+    Effects::msg(Msg::Internal(Internal::ActiveServerChanged(active_server))).unchanged()
+}
+
+/// Sends the settings to the server, unless the changed values are invalid.
+fn settings_update<E: Env + 'static>(
+    settings: &mut Loadable<Settings, EnvError>,
+    settings_errors: &mut Vec<SettingsError>,
+    remote_url: &mut Option<Url>,
+    selected: &Selected,
+    compatibility: &Option<ServerCompatibility>,
+    next_settings: &Settings,
+    ctx: &Ctx,
+) -> Effects {
+    if let Err(errors) = next_settings.validate(settings.ready()) {
+        return eq_update(settings_errors, errors);
+    };
+    let settings_errors_effects = eq_update(settings_errors, vec![]);
+    let settings_effects = eq_update(settings, Loadable::Ready(next_settings.to_owned()));
+    let remote_url_effects =
+        update_remote_url::<E>(remote_url, selected, compatibility, next_settings, ctx);
+    Effects::one(set_settings::<E>(&selected.transport_url, next_settings))
+        .unchanged()
+        .join(settings_errors_effects)
+        .join(settings_effects)
+        .join(remote_url_effects)
+}
+
+/// Saves the interface in the settings of the server once the remote url responded to the probe.
+#[allow(clippy::too_many_arguments)]
+fn remote_access_probe_update<E: Env + 'static>(
+    remote_access: &mut Option<RemoteAccess>,
+    settings: &mut Loadable<Settings, EnvError>,
+    settings_errors: &mut Vec<SettingsError>,
+    remote_url: &mut Option<Url>,
+    selected: &Selected,
+    compatibility: &Option<ServerCompatibility>,
+    url: &Url,
+    result: &Result<SettingsResponse, EnvError>,
+    ctx: &Ctx,
+) -> Effects {
+    let remote_access = match remote_access {
+        Some(remote_access) if remote_access.step == RemoteAccessStep::Probing(url.to_owned()) => {
+            remote_access
         }
+        _ => return Effects::none().unchanged(),
+    };
+    match (result, &*settings) {
+        (Ok(_), Loadable::Ready(current_settings)) => {
+            let previous_remote_https = current_settings.remote_https.to_owned();
+            let next_settings = Settings {
+                remote_https: Some(remote_access.interface.to_owned()),
+                ..current_settings.to_owned()
+            };
+            let settings_effects = settings_update::<E>(
+                settings,
+                settings_errors,
+                remote_url,
+                selected,
+                compatibility,
+                &next_settings,
+                ctx,
+            );
+            remote_access.step = if settings_errors.is_empty() {
+                RemoteAccessStep::Saving {
+                    remote_url: url.to_owned(),
+                    previous_remote_https,
+                }
+            } else {
+                RemoteAccessStep::Failed(RemoteAccessError::Save("Settings are invalid".to_owned()))
+            };
+            Effects::none().join(settings_effects)
+        }
+        (Ok(_), _) => {
+            remote_access.step = RemoteAccessStep::Failed(RemoteAccessError::Save(
+                "Settings are not loaded".to_owned(),
+            ));
+            Effects::none()
+        }
+        (Err(error), _) => {
+            remote_access.step =
+                RemoteAccessStep::Failed(RemoteAccessError::Unreachable(error.message()));
+            Effects::none()
+        }
+    }
+}
+
+/// Selects the statistics request, the previous statistics are dropped if the request is different.
+fn statistics_request_update(
+    selected: &mut Option<StatisticsRequest>,
+    statistics: &mut Option<Loadable<Statistics, EnvError>>,
+    statistics_history: &mut Option<StatisticsHistory>,
+    request: &StatisticsRequest,
+) -> Effects {
+    if selected.as_ref() == Some(request) && statistics.is_some() {
+        return Effects::none().unchanged();
+    };
+    // set the Loading state only on the first fetch of the statistics
+    *selected = Some(request.to_owned());
+    *statistics = Some(Loadable::Loading);
+    *statistics_history = Some(StatisticsHistory::default());
+    Effects::none()
+}
+
+/// Fetches the statistics, unless the server is known to not support them.
+fn statistics_fetch_update<E: Env + 'static>(
+    statistics: &mut Option<Loadable<Statistics, EnvError>>,
+    selected: &Selected,
+    compatibility: &Option<ServerCompatibility>,
+    request: &StatisticsRequest,
+) -> Effects {
+    match supports(compatibility, ServerFeature::Statistics) {
+        true => Effects::one(get_torrent_statistics::<E>(
+            &selected.transport_url,
+            request,
+        ))
+        .unchanged(),
+        false => eq_update(
+            statistics,
+            Some(Loadable::Err(unsupported_feature_error(
+                ServerFeature::Statistics,
+            ))),
+        ),
     }
 }
 
+/// The features are assumed to be supported until the version of the server is known.
+fn supports(compatibility: &Option<ServerCompatibility>, feature: ServerFeature) -> bool {
+    compatibility
+        .as_ref()
+        .map(|compatibility| compatibility.supports(feature))
+        .unwrap_or(true)
+}
+
+fn unsupported_feature_error(feature: ServerFeature) -> EnvError {
+    EnvError::Other(format!(
+        "{feature:?} is not supported by this version of the streaming server"
+    ))
+}
+
+/// The [`ProfileSettings::streaming_server_url`] followed by the other known servers.
+fn server_urls(settings: &ProfileSettings) -> impl Iterator<Item = &Url> {
+    iter::once(&settings.streaming_server_url)
+        .chain(settings.streaming_servers.iter())
+        .unique()
+}
+
+fn servers(settings: &ProfileSettings) -> Vec<ServerHealth> {
+    server_urls(settings)
+        .map(|url| ServerHealth {
+            url: url.to_owned(),
+            settings: Loadable::Loading,
+        })
+        .collect()
+}
+
 fn get_settings<E: Env + 'static>(url: &Url) -> Effect {
     let endpoint = url.join("settings").expect("url builder failed");
     let request = Request::get(endpoint.as_str())
@@ -473,7 +983,13 @@ fn set_settings<E: Env + 'static>(url: &Url, settings: &Settings) -> Effect {
     .into()
 }
 
-fn create_magnet<E: Env + 'static>(url: &Url, info_hash: &str, announce: &[String]) -> Effect {
+/// The request creating the torrent of a magnet on the server,
+/// the peers are searched on the DHT and on the trackers of the magnet.
+fn create_magnet_request(
+    url: &Url,
+    info_hash: &str,
+    announce: &[String],
+) -> Request<impl Serialize + ConditionalSend + 'static> {
     #[derive(Serialize)]
     #[serde(rename_all = "camelCase")]
     struct PeerSearch {
@@ -492,7 +1008,6 @@ fn create_magnet<E: Env + 'static>(url: &Url, info_hash: &str, announce: &[Strin
         torrent: Torrent,
         peer_search: Option<PeerSearch>,
     }
-    let info_hash = info_hash.to_owned();
     let endpoint = url
         .join(&format!("{info_hash}/"))
         .expect("url builder failed")
@@ -515,10 +1030,15 @@ fn create_magnet<E: Env + 'static>(url: &Url, info_hash: &str, announce: &[Strin
             None
         },
     };
-    let request = Request::post(endpoint.as_str())
+    Request::post(endpoint.as_str())
         .header(http::header::CONTENT_TYPE, "application/json")
         .body(body)
-        .expect("request builder failed");
+        .expect("request builder failed")
+}
+
+fn create_magnet<E: Env + 'static>(url: &Url, info_hash: &str, announce: &[String]) -> Effect {
+    let info_hash = info_hash.to_owned();
+    let request = create_magnet_request(url, &info_hash, announce);
     EffectFuture::Concurrent(
         E::fetch::<_, serde_json::Value>(request)
             .map_ok(|_| ())
@@ -532,6 +1052,24 @@ fn create_magnet<E: Env + 'static>(url: &Url, info_hash: &str, announce: &[Strin
     .into()
 }
 
+/// Creates the torrent of a magnet on the server for its files,
+/// which are responded with the statistics of the created torrent.
+pub(crate) fn get_torrent_files<E: Env + 'static>(
+    url: &Url,
+    info_hash: &str,
+    announce: &[String],
+) -> Effect {
+    let info_hash = info_hash.to_owned();
+    let request = create_magnet_request(url, &info_hash, announce);
+    EffectFuture::Concurrent(
+        E::fetch::<_, Statistics>(request)
+            .map_ok(|statistics| statistics.files)
+            .map(move |result| Msg::Internal(Internal::TorrentFilesResult(info_hash, result)))
+            .boxed_env(),
+    )
+    .into()
+}
+
 fn create_torrent<E: Env + 'static>(url: &Url, info_hash: &str, torrent: &[u8]) -> Effect {
     #[derive(Serialize)]
     struct Body {
@@ -558,41 +1096,6 @@ fn create_torrent<E: Env + 'static>(url: &Url, info_hash: &str, torrent: &[u8])
     .into()
 }
 
-fn parse_magnet(magnet: &Url) -> Result<(String, Vec<String>), MagnetError> {
-    let magnet = Magnet::new(magnet.as_str())?;
-    let info_hash = magnet.xt.ok_or(MagnetError::NotAMagnetURL)?;
-    let announce = magnet.tr;
-    Ok((info_hash, announce))
-}
-
-fn parse_torrent(torrent: &[u8]) -> Result<(String, Vec<String>), serde_bencode::Error> {
-    #[derive(Deserialize)]
-    struct TorrentFile {
-        info: serde_bencode::value::Value,
-        #[serde(default)]
-        announce: Option<String>,
-        #[serde(default)]
-        #[serde(rename = "announce-list")]
-        announce_list: Option<Vec<Vec<String>>>,
-    }
-    let torrent_file = serde_bencode::from_bytes::<TorrentFile>(torrent)?;
-    let info_bytes = serde_bencode::to_bytes(&torrent_file.info)?;
-    let mut hasher = Sha1::new();
-    hasher.update(info_bytes);
-    let info_hash = hex::encode(hasher.finalize());
-    let mut announce = vec![];
-    if let Some(announce_entry) = torrent_file.announce {
-        announce.push(announce_entry);
-    };
-    if let Some(announce_lists) = torrent_file.announce_list {
-        for announce_list in announce_lists {
-            announce.extend(announce_list.into_iter());
-        }
-    };
-    announce.dedup();
-    Ok((info_hash, announce))
-}
-
 fn get_torrent_statistics<E: Env + 'static>(url: &Url, request: &StatisticsRequest) -> Effect {
     let statistics_request = request.clone();
     let endpoint = url
@@ -678,14 +1181,21 @@ fn get_https_endpoint<E: Env + 'static>(
 fn update_remote_url<E: Env + 'static>(
     remote_url: &mut Option<Url>,
     selected: &Selected,
+    compatibility: &Option<ServerCompatibility>,
     settings: &Settings,
     ctx: &Ctx,
 ) -> Effects {
     match (settings.remote_https.as_ref(), ctx.profile.auth_key()) {
-        (Some(ip_address), Some(auth_key)) if !ip_address.is_empty() => Effects::one(
-            get_https_endpoint::<E>(&selected.transport_url, auth_key, ip_address),
-        )
-        .unchanged(),
+        (Some(ip_address), Some(auth_key))
+            if !ip_address.is_empty() && supports(compatibility, ServerFeature::RemoteHttps) =>
+        {
+            Effects::one(get_https_endpoint::<E>(
+                &selected.transport_url,
+                auth_key,
+                ip_address,
+            ))
+            .unchanged()
+        }
         _ => eq_update(remote_url, None),
     }
 }
@@ -0,0 +1,132 @@
+use enclose::enclose;
+use futures::FutureExt;
+use http::Request;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::{
+    models::{
+        common::{eq_update, Loadable},
+        ctx::Ctx,
+    },
+    runtime::{
+        msg::{Action, ActionLoad, Internal, Msg},
+        Effect, EffectFuture, Effects, Env, EnvFutureExt, UpdateWithCtx,
+    },
+    types::{
+        streams::{StreamsBucket, StreamsItemKey},
+        subtitles::{SubtitlesError, SubtitlesFile, SubtitlesFormat},
+    },
+};
+
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Selected {
+    pub url: Url,
+    /// The format supported by the player.
+    pub format: SubtitlesFormat,
+    /// The stream for which the subtitles are shown.
+    ///
+    /// Its [`StreamItemState::subtitle_delay`] is applied to the converted subtitles.
+    ///
+    /// [`StreamItemState::subtitle_delay`]: crate::types::streams::StreamItemState::subtitle_delay
+    pub stream_key: Option<StreamsItemKey>,
+}
+
+/// Fetches and parses a subtitles file (SRT, WebVTT or ASS/SSA)
+/// and converts it into the format supported by the player.
+#[derive(Default, Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SubtitlesConverter {
+    pub selected: Option<Selected>,
+    /// The parsed subtitles file, without the delay applied.
+    pub file: Option<Loadable<SubtitlesFile, SubtitlesError>>,
+    /// The subtitles in the selected format, with the delay applied.
+    pub content: Option<String>,
+}
+
+impl<E: Env + 'static> UpdateWithCtx<E> for SubtitlesConverter {
+    fn update(&mut self, msg: &Msg, ctx: &Ctx) -> Effects {
+        match msg {
+            Msg::Action(Action::Load(ActionLoad::SubtitlesConverter(selected))) => {
+                let file_effects = if self.selected.as_ref().map(|selected| &selected.url)
+                    != Some(&selected.url)
+                {
+                    eq_update(&mut self.file, Some(Loadable::Loading))
+                        .join(Effects::one(fetch_subtitles::<E>(&selected.url)))
+                } else {
+                    Effects::none().unchanged()
+                };
+                let selected_effects = eq_update(&mut self.selected, Some(selected.to_owned()));
+                let content_effects =
+                    content_update(&mut self.content, &self.selected, &self.file, &ctx.streams);
+                selected_effects.join(file_effects).join(content_effects)
+            }
+            Msg::Action(Action::Unload) => {
+                let selected_effects = eq_update(&mut self.selected, None);
+                let file_effects = eq_update(&mut self.file, None);
+                let content_effects = eq_update(&mut self.content, None);
+                selected_effects.join(file_effects).join(content_effects)
+            }
+            Msg::Internal(Internal::SubtitlesFileResult(url, result))
+                if self.selected.as_ref().map(|selected| &selected.url) == Some(url)
+                    && self.file == Some(Loadable::Loading) =>
+            {
+                let next_file = match result {
+                    Ok(file) => Loadable::Ready(file.to_owned()),
+                    Err(error) => Loadable::Err(error.to_owned()),
+                };
+                let file_effects = eq_update(&mut self.file, Some(next_file));
+                let content_effects =
+                    content_update(&mut self.content, &self.selected, &self.file, &ctx.streams);
+                file_effects.join(content_effects)
+            }
+            Msg::Internal(Internal::StreamsChanged(_)) => {
+                content_update(&mut self.content, &self.selected, &self.file, &ctx.streams)
+            }
+            _ => Effects::none().unchanged(),
+        }
+    }
+}
+
+/// Fetches and parses the subtitles file from the given [`Url`].
+pub(crate) fn fetch_subtitles<E: Env + 'static>(url: &Url) -> Effect {
+    let request = Request::get(url.as_str())
+        .body(())
+        .expect("request builder failed");
+
+    EffectFuture::Concurrent(
+        E::fetch_bytes(request)
+            .map(enclose!((url) move |result| {
+                let result = result
+                    .map_err(|error| SubtitlesError::Fetch(error.message()))
+                    .and_then(|bytes| SubtitlesFile::parse(&bytes));
+
+                Msg::Internal(Internal::SubtitlesFileResult(url, result))
+            }))
+            .boxed_env(),
+    )
+    .into()
+}
+
+fn content_update(
+    content: &mut Option<String>,
+    selected: &Option<Selected>,
+    file: &Option<Loadable<SubtitlesFile, SubtitlesError>>,
+    streams: &StreamsBucket,
+) -> Effects {
+    let next_content = match (selected, file) {
+        (Some(selected), Some(Loadable::Ready(file))) => {
+            let delay = selected
+                .stream_key
+                .as_ref()
+                .and_then(|stream_key| streams.items.get(stream_key))
+                .and_then(|streams_item| streams_item.state.as_ref())
+                .and_then(|state| state.subtitle_delay)
+                .unwrap_or_default();
+            Some(file.to_owned().delayed(delay).to_format(selected.format))
+        }
+        _ => None,
+    };
+    eq_update(content, next_content)
+}
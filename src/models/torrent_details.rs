@@ -0,0 +1,167 @@
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::{
+    models::{
+        common::{eq_update, Loadable},
+        ctx::Ctx,
+        streaming_server::get_torrent_files,
+    },
+    runtime::{
+        msg::{Action, ActionLoad, ActionTorrentDetails, Internal, Msg},
+        Effects, Env, UpdateWithCtx,
+    },
+    types::{
+        resource::{MetaItemPreview, Stream},
+        streaming_server::find_video_file,
+        torrent::{files_size, TorrentInfo, TorrentInfoError},
+    },
+};
+
+#[derive(Clone, Deserialize, Debug)]
+#[serde(untagged)]
+pub enum Selected {
+    /// The bytes of a .torrent file.
+    File(Vec<u8>),
+    Magnet(Url),
+}
+
+/// The metadata of a .torrent file or a magnet, inspected before playing it.
+///
+/// The files of a magnet are fetched by creating the torrent on the streaming server.
+/// The largest video is selected by default, the [`TorrentDetails::stream`]
+/// of the selected file can be played and the [`TorrentDetails::meta_item`]
+/// added to the library.
+#[derive(Default, Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TorrentDetails {
+    pub info: Option<Loadable<TorrentInfo, TorrentInfoError>>,
+    /// The index of the selected file in the [`TorrentInfo::files`].
+    pub selected_file: Option<u16>,
+    pub stream: Option<Stream>,
+    pub meta_item: Option<MetaItemPreview>,
+}
+
+impl<E: Env + 'static> UpdateWithCtx<E> for TorrentDetails {
+    fn update(&mut self, msg: &Msg, ctx: &Ctx) -> Effects {
+        match msg {
+            Msg::Action(Action::Load(ActionLoad::TorrentDetails(selected))) => {
+                let info = match selected {
+                    Selected::File(torrent) => TorrentInfo::from_torrent(torrent),
+                    Selected::Magnet(magnet) => TorrentInfo::from_magnet(magnet),
+                };
+                let files_effects = match &info {
+                    Ok(info) if info.files.is_empty() => Effects::one(get_torrent_files::<E>(
+                        &ctx.streaming_server.url,
+                        &hex::encode(info.info_hash),
+                        &info.announce,
+                    ))
+                    .unchanged(),
+                    _ => Effects::none().unchanged(),
+                };
+                let selected_file = info
+                    .as_ref()
+                    .ok()
+                    .and_then(|info| find_video_file(&info.files, None))
+                    .and_then(|file_idx| u16::try_from(file_idx).ok());
+                let info_effects = eq_update(
+                    &mut self.info,
+                    Some(match info {
+                        Ok(info) => Loadable::Ready(info),
+                        Err(error) => Loadable::Err(error),
+                    }),
+                );
+                let selected_file_effects = selected_file_update(
+                    &mut self.selected_file,
+                    &mut self.stream,
+                    &mut self.meta_item,
+                    &self.info,
+                    selected_file,
+                );
+                info_effects.join(selected_file_effects).join(files_effects)
+            }
+            Msg::Action(Action::Unload) => {
+                let info_effects = eq_update(&mut self.info, None);
+                let selected_file_effects = selected_file_update(
+                    &mut self.selected_file,
+                    &mut self.stream,
+                    &mut self.meta_item,
+                    &self.info,
+                    None,
+                );
+                info_effects.join(selected_file_effects)
+            }
+            Msg::Action(Action::TorrentDetails(ActionTorrentDetails::SelectFile(file_idx))) => {
+                match &self.info {
+                    Some(Loadable::Ready(info)) if (*file_idx as usize) < info.files.len() => {
+                        selected_file_update(
+                            &mut self.selected_file,
+                            &mut self.stream,
+                            &mut self.meta_item,
+                            &self.info,
+                            Some(*file_idx),
+                        )
+                    }
+                    _ => Effects::none().unchanged(),
+                }
+            }
+            Msg::Internal(Internal::TorrentFilesResult(info_hash, Err(error))) => {
+                match &self.info {
+                    Some(Loadable::Ready(info))
+                        if hex::encode(info.info_hash) == *info_hash && info.files.is_empty() =>
+                    {
+                        self.info = Some(Loadable::Err(TorrentInfoError::Files(error.message())));
+                        Effects::none().join(selected_file_update(
+                            &mut self.selected_file,
+                            &mut self.stream,
+                            &mut self.meta_item,
+                            &self.info,
+                            None,
+                        ))
+                    }
+                    _ => Effects::none().unchanged(),
+                }
+            }
+            Msg::Internal(Internal::TorrentFilesResult(info_hash, Ok(files))) => {
+                match &mut self.info {
+                    Some(Loadable::Ready(info))
+                        if hex::encode(info.info_hash) == *info_hash && info.files.is_empty() =>
+                    {
+                        info.files = files.to_owned();
+                        info.size = files_size(files);
+                        let selected_file = find_video_file(&info.files, None)
+                            .and_then(|file_idx| u16::try_from(file_idx).ok());
+                        Effects::none().join(selected_file_update(
+                            &mut self.selected_file,
+                            &mut self.stream,
+                            &mut self.meta_item,
+                            &self.info,
+                            selected_file,
+                        ))
+                    }
+                    _ => Effects::none().unchanged(),
+                }
+            }
+            _ => Effects::none().unchanged(),
+        }
+    }
+}
+
+fn selected_file_update(
+    selected_file: &mut Option<u16>,
+    stream: &mut Option<Stream>,
+    meta_item: &mut Option<MetaItemPreview>,
+    info: &Option<Loadable<TorrentInfo, TorrentInfoError>>,
+    next_selected_file: Option<u16>,
+) -> Effects {
+    let (next_stream, next_meta_item) = match info {
+        Some(Loadable::Ready(info)) => (
+            Some(info.stream(next_selected_file)),
+            Some(info.meta_item()),
+        ),
+        _ => (None, None),
+    };
+    eq_update(selected_file, next_selected_file)
+        .join(eq_update(stream, next_stream))
+        .join(eq_update(meta_item, next_meta_item))
+}
@@ -6,6 +6,7 @@ use crate::constants::{
 };
 use crate::models::ctx::Ctx;
 use crate::models::streaming_server::StreamingServer;
+use crate::types::resource::PlaybackCapabilities;
 use chrono::{DateTime, Utc};
 use futures::{future, Future, TryFutureExt};
 use http::Request;
@@ -157,6 +158,30 @@ pub trait Env {
     ) -> serde_json::Value;
     #[cfg(debug_assertions)]
     fn log(message: String);
+    /// Fetches the raw response body, e.g. for subtitles files which are not JSON.
+    ///
+    /// Fails with [`EnvError::Fetch`] unless implemented by the environment.
+    fn fetch_bytes(request: Request<()>) -> TryEnvFuture<Vec<u8>>
+    where
+        Self: Sized,
+    {
+        future::err(EnvError::Fetch(format!(
+            "Fetching raw bytes is not supported: {}",
+            request.uri()
+        )))
+        .boxed_env()
+    }
+    /// What the client is able to play natively, for choosing the [`StreamPlayback`].
+    ///
+    /// `None` unless implemented by the environment, in which case no choice is made.
+    ///
+    /// [`StreamPlayback`]: crate::types::resource::StreamPlayback
+    fn playback_capabilities() -> Option<PlaybackCapabilities>
+    where
+        Self: Sized,
+    {
+        None
+    }
     fn addon_transport(transport_url: &Url) -> Box<dyn AddonTransport>
     where
         Self: Sized + 'static,
@@ -263,6 +288,30 @@ pub trait Env {
                         .await?;
                     schema_version = 14;
                 }
+                if schema_version == 14 {
+                    migrate_storage_schema_to_v15::<Self>()
+                        .map_err(|error| EnvError::StorageSchemaVersionUpgrade(Box::new(error)))
+                        .await?;
+                    schema_version = 15;
+                }
+                if schema_version == 15 {
+                    migrate_storage_schema_to_v16::<Self>()
+                        .map_err(|error| EnvError::StorageSchemaVersionUpgrade(Box::new(error)))
+                        .await?;
+                    schema_version = 16;
+                }
+                if schema_version == 16 {
+                    migrate_storage_schema_to_v17::<Self>()
+                        .map_err(|error| EnvError::StorageSchemaVersionUpgrade(Box::new(error)))
+                        .await?;
+                    schema_version = 17;
+                }
+                if schema_version == 17 {
+                    migrate_storage_schema_to_v18::<Self>()
+                        .map_err(|error| EnvError::StorageSchemaVersionUpgrade(Box::new(error)))
+                        .await?;
+                    schema_version = 18;
+                }
                 if schema_version != SCHEMA_VERSION {
                     panic!(
                         "Storage schema version must be upgraded from {} to {}",
@@ -592,6 +641,107 @@ fn migrate_storage_schema_to_v14<E: Env>() -> TryEnvFuture<()> {
         .boxed_env()
 }
 
+fn migrate_storage_schema_to_v15<E: Env>() -> TryEnvFuture<()> {
+    E::get_storage::<serde_json::Value>(PROFILE_STORAGE_KEY)
+        .and_then(|mut profile| {
+            match profile
+                .as_mut()
+                .and_then(|profile| profile.as_object_mut())
+                .and_then(|profile| profile.get_mut("settings"))
+                .and_then(|settings| settings.as_object_mut())
+            {
+                Some(settings) => {
+                    settings.insert(
+                        "notificationsQuietHours".to_owned(),
+                        serde_json::Value::Null,
+                    );
+                    E::set_storage(PROFILE_STORAGE_KEY, Some(&profile))
+                }
+                _ => E::set_storage::<()>(PROFILE_STORAGE_KEY, None),
+            }
+        })
+        .and_then(|_| E::set_storage(SCHEMA_VERSION_STORAGE_KEY, Some(&15)))
+        .boxed_env()
+}
+
+fn migrate_storage_schema_to_v16<E: Env>() -> TryEnvFuture<()> {
+    E::get_storage::<serde_json::Value>(PROFILE_STORAGE_KEY)
+        .and_then(|mut profile| {
+            match profile
+                .as_mut()
+                .and_then(|profile| profile.as_object_mut())
+                .and_then(|profile| profile.get_mut("settings"))
+                .and_then(|settings| settings.as_object_mut())
+            {
+                Some(settings) => {
+                    settings.insert(
+                        "bingeRules".to_owned(),
+                        serde_json::json!({
+                            "skipWatched": false,
+                            "stopAtSeasonEnd": false,
+                            "specialsInAiredOrder": false,
+                            "playNextCount": null
+                        }),
+                    );
+                    E::set_storage(PROFILE_STORAGE_KEY, Some(&profile))
+                }
+                _ => E::set_storage::<()>(PROFILE_STORAGE_KEY, None),
+            }
+        })
+        .and_then(|_| E::set_storage(SCHEMA_VERSION_STORAGE_KEY, Some(&16)))
+        .boxed_env()
+}
+
+fn migrate_storage_schema_to_v17<E: Env>() -> TryEnvFuture<()> {
+    E::get_storage::<serde_json::Value>(PROFILE_STORAGE_KEY)
+        .and_then(|mut profile| {
+            match profile
+                .as_mut()
+                .and_then(|profile| profile.as_object_mut())
+                .and_then(|profile| profile.get_mut("settings"))
+                .and_then(|settings| settings.as_object_mut())
+            {
+                Some(settings) => {
+                    settings.insert(
+                        "streamPreferences".to_owned(),
+                        serde_json::json!({
+                            "preferredResolutions": [],
+                            "maxSize": null,
+                            "excludeCam": false,
+                            "preferredLanguages": [],
+                            "hideP2p": false,
+                            "preferDirectUrls": false
+                        }),
+                    );
+                    E::set_storage(PROFILE_STORAGE_KEY, Some(&profile))
+                }
+                _ => E::set_storage::<()>(PROFILE_STORAGE_KEY, None),
+            }
+        })
+        .and_then(|_| E::set_storage(SCHEMA_VERSION_STORAGE_KEY, Some(&17)))
+        .boxed_env()
+}
+
+fn migrate_storage_schema_to_v18<E: Env>() -> TryEnvFuture<()> {
+    E::get_storage::<serde_json::Value>(PROFILE_STORAGE_KEY)
+        .and_then(|mut profile| {
+            match profile
+                .as_mut()
+                .and_then(|profile| profile.as_object_mut())
+                .and_then(|profile| profile.get_mut("settings"))
+                .and_then(|settings| settings.as_object_mut())
+            {
+                Some(settings) => {
+                    settings.insert("streamingServers".to_owned(), serde_json::json!([]));
+                    E::set_storage(PROFILE_STORAGE_KEY, Some(&profile))
+                }
+                _ => E::set_storage::<()>(PROFILE_STORAGE_KEY, None),
+            }
+        })
+        .and_then(|_| E::set_storage(SCHEMA_VERSION_STORAGE_KEY, Some(&18)))
+        .boxed_env()
+}
+
 #[cfg(test)]
 mod test {
     use serde_json::{json, Value};
@@ -604,7 +754,9 @@ mod test {
             env::{
                 migrate_storage_schema_to_v10, migrate_storage_schema_to_v11,
                 migrate_storage_schema_to_v12, migrate_storage_schema_to_v13,
-                migrate_storage_schema_to_v14, migrate_storage_schema_to_v6,
+                migrate_storage_schema_to_v14, migrate_storage_schema_to_v15,
+                migrate_storage_schema_to_v16, migrate_storage_schema_to_v17,
+                migrate_storage_schema_to_v18, migrate_storage_schema_to_v6,
                 migrate_storage_schema_to_v7, migrate_storage_schema_to_v8,
                 migrate_storage_schema_to_v9,
             },
@@ -1123,4 +1275,168 @@ mod test {
             "Profile should match"
         );
     }
+
+    #[tokio::test]
+    async fn test_migration_from_14_to_15() {
+        let _test_env_guard = TestEnv::reset().expect("Should lock TestEnv");
+
+        let init_profile = json!({
+            "settings": {}
+        });
+
+        let migrated_profile = json!({
+            "settings": {
+                "notificationsQuietHours": null
+            }
+        });
+
+        set_profile_and_schema_version(&init_profile, 14);
+
+        migrate_storage_schema_to_v15::<TestEnv>()
+            .await
+            .expect("Should migrate");
+
+        let storage = STORAGE.read().expect("Should lock");
+
+        assert_eq!(
+            &15.to_string(),
+            storage
+                .get(SCHEMA_VERSION_STORAGE_KEY)
+                .expect("Should have the schema set"),
+            "Scheme version should now be updated"
+        );
+        assert_eq!(
+            &migrated_profile.to_string(),
+            storage
+                .get(PROFILE_STORAGE_KEY)
+                .expect("Should have the profile set"),
+            "Profile should match"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_migration_from_15_to_16() {
+        let _test_env_guard = TestEnv::reset().expect("Should lock TestEnv");
+
+        let init_profile = json!({
+            "settings": {}
+        });
+
+        let migrated_profile = json!({
+            "settings": {
+                "bingeRules": {
+                    "skipWatched": false,
+                    "stopAtSeasonEnd": false,
+                    "specialsInAiredOrder": false,
+                    "playNextCount": null
+                }
+            }
+        });
+
+        set_profile_and_schema_version(&init_profile, 15);
+
+        migrate_storage_schema_to_v16::<TestEnv>()
+            .await
+            .expect("Should migrate");
+
+        let storage = STORAGE.read().expect("Should lock");
+
+        assert_eq!(
+            &16.to_string(),
+            storage
+                .get(SCHEMA_VERSION_STORAGE_KEY)
+                .expect("Should have the schema set"),
+            "Scheme version should now be updated"
+        );
+        assert_eq!(
+            &migrated_profile.to_string(),
+            storage
+                .get(PROFILE_STORAGE_KEY)
+                .expect("Should have the profile set"),
+            "Profile should match"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_migration_from_16_to_17() {
+        let _test_env_guard = TestEnv::reset().expect("Should lock TestEnv");
+
+        let init_profile = json!({
+            "settings": {}
+        });
+
+        let migrated_profile = json!({
+            "settings": {
+                "streamPreferences": {
+                    "preferredResolutions": [],
+                    "maxSize": null,
+                    "excludeCam": false,
+                    "preferredLanguages": [],
+                    "hideP2p": false,
+                    "preferDirectUrls": false
+                }
+            }
+        });
+
+        set_profile_and_schema_version(&init_profile, 16);
+
+        migrate_storage_schema_to_v17::<TestEnv>()
+            .await
+            .expect("Should migrate");
+
+        let storage = STORAGE.read().expect("Should lock");
+
+        assert_eq!(
+            &17.to_string(),
+            storage
+                .get(SCHEMA_VERSION_STORAGE_KEY)
+                .expect("Should have the schema set"),
+            "Scheme version should now be updated"
+        );
+        assert_eq!(
+            &migrated_profile.to_string(),
+            storage
+                .get(PROFILE_STORAGE_KEY)
+                .expect("Should have the profile set"),
+            "Profile should match"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_migration_from_17_to_18() {
+        let _test_env_guard = TestEnv::reset().expect("Should lock TestEnv");
+
+        let init_profile = json!({
+            "settings": {}
+        });
+
+        let migrated_profile = json!({
+            "settings": {
+                "streamingServers": []
+            }
+        });
+
+        set_profile_and_schema_version(&init_profile, 17);
+
+        migrate_storage_schema_to_v18::<TestEnv>()
+            .await
+            .expect("Should migrate");
+
+        let storage = STORAGE.read().expect("Should lock");
+
+        assert_eq!(
+            &18.to_string(),
+            storage
+                .get(SCHEMA_VERSION_STORAGE_KEY)
+                .expect("Should have the schema set"),
+            "Scheme version should now be updated"
+        );
+        assert_eq!(
+            &migrated_profile.to_string(),
+            storage
+                .get(PROFILE_STORAGE_KEY)
+                .expect("Should have the profile set"),
+            "Profile should match"
+        );
+    }
 }
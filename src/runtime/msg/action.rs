@@ -7,6 +7,8 @@ use crate::types::streams::StreamItemState;
 use crate::{
     models::{
         addon_details::Selected as AddonDetailsSelected,
+        calendar::Selected as CalendarSelected,
+        cast_session::Selected as CastSessionSelected,
         catalog_with_filters::Selected as CatalogWithFiltersSelected,
         catalogs_with_extra::Selected as CatalogsWithExtraSelected,
         installed_addons_with_filters::Selected as InstalledAddonsWithFiltersSelected,
@@ -15,14 +17,20 @@ use crate::{
         meta_details::Selected as MetaDetailsSelected,
         player::{Selected as PlayerSelected, VideoParams},
         streaming_server::StatisticsRequest as StreamingServerStatisticsRequest,
+        subtitles_converter::Selected as SubtitlesConverterSelected,
+        torrent_details::Selected as TorrentDetailsSelected,
     },
     types::{
         addon::Descriptor,
         api::AuthRequest,
-        library::LibraryItemId,
+        data_archive::DataArchive,
+        library::{LibraryImportSource, LibraryItemId, NotificationPreferences},
         profile::Settings as ProfileSettings,
-        resource::{MetaItemId, MetaItemPreview, Video},
-        streaming_server::Settings as StreamingServerSettings,
+        resource::{MetaItemId, MetaItemPreview, Stream, Video},
+        streaming_server::{
+            Settings as StreamingServerSettings, SettingsPreset as StreamingServerSettingsPreset,
+        },
+        subtitles::SubtitlesReference,
     },
 };
 
@@ -42,6 +50,8 @@ pub enum ActionCtx {
     RewindLibraryItem(String),
     /// If boolean is set to `true` it will disable notifications for the LibraryItem.
     ToggleLibraryItemNotifications(LibraryItemId, bool),
+    /// Narrow down which notifications to receive for the LibraryItem.
+    UpdateLibraryItemNotificationPreferences(LibraryItemId, NotificationPreferences),
     /// Dismiss all Notification for a given [`MetaItemId`].
     DismissNotificationItem(MetaItemId),
     ClearSearchHistory,
@@ -62,6 +72,10 @@ pub enum ActionCtx {
     GetEvents,
     /// Dismiss an event by id, either a Modal or Notification
     DismissEvent(String),
+    /// Replace all the user data with the content of a local [`DataArchive`].
+    ///
+    /// Archives created with an older schema version are migrated before being loaded.
+    ImportDataArchive(DataArchive),
 }
 
 #[derive(Clone, Deserialize, Debug)]
@@ -100,6 +114,26 @@ pub enum ActionMetaDetails {
     MarkVideoAsWatched(Video, bool),
 }
 
+#[derive(Clone, Deserialize, Debug)]
+#[serde(tag = "action", content = "args")]
+pub enum ActionDataExport {
+    /// Creates a local [`DataArchive`] of the user data, no API request is made.
+    CreateArchive,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+#[serde(tag = "action", content = "args")]
+pub enum ActionLibraryImport {
+    /// Parses the content of an export file and resolves its entries
+    /// through the installed addons without modifying the library.
+    Preview {
+        source: LibraryImportSource,
+        content: String,
+    },
+    /// Saves the resolved items of the current preview to the library.
+    Commit,
+}
+
 #[derive(Clone, Deserialize, Debug)]
 #[serde(untagged)]
 pub enum CreateTorrentArgs {
@@ -119,10 +153,57 @@ pub struct PlayOnDeviceArgs {
 #[serde(tag = "action", content = "args")]
 pub enum ActionStreamingServer {
     Reload,
+    /// Settings with values outside of the allowed ranges are not sent to the server.
     UpdateSettings(StreamingServerSettings),
+    /// Updates the torrent settings with the values of the preset.
+    ApplySettingsPreset(StreamingServerSettingsPreset),
     CreateTorrent(CreateTorrentArgs),
     GetStatistics(StreamingServerStatisticsRequest),
     PlayOnDevice(PlayOnDeviceArgs),
+    /// Sets up the HTTPS access from other devices on one of the
+    /// [`NetworkInfo::available_interfaces`] of the streaming server.
+    ///
+    /// [`NetworkInfo::available_interfaces`]: crate::types::streaming_server::NetworkInfo::available_interfaces
+    SetupRemoteAccess(String),
+}
+
+#[derive(Clone, Deserialize, Debug)]
+#[serde(tag = "action", content = "args")]
+pub enum ActionCastSession {
+    SetPaused(bool),
+    /// In milliseconds
+    Seek(u64),
+    Stop,
+    /// Polls the playback status of the device.
+    ///
+    /// Meant to be dispatched periodically while casting.
+    Refresh,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+#[serde(tag = "action", content = "args")]
+pub enum ActionDownloads {
+    /// Queues the stream for a full download on the streaming server.
+    #[serde(rename_all = "camelCase")]
+    Add {
+        stream: Stream,
+        library_item_id: Option<LibraryItemId>,
+        video_id: Option<String>,
+    },
+    /// Polls the progress of the active download and starts the next queued one.
+    ///
+    /// Meant to be dispatched periodically while there are unfinished downloads.
+    Refresh,
+    /// Queues a failed download again.
+    Retry(String),
+    Remove(String),
+}
+
+#[derive(Clone, Deserialize, Debug)]
+#[serde(tag = "action", content = "args")]
+pub enum ActionTorrentDetails {
+    /// Selects the file of the torrent by its index.
+    SelectFile(u16),
 }
 
 #[derive(Clone, Deserialize, Debug)]
@@ -141,6 +222,14 @@ pub enum ActionPlayer {
     StreamStateChanged {
         state: StreamItemState,
     },
+    /// Sets the subtitles against which the timing of the selected subtitles is synced.
+    SubtitlesReferenceChanged {
+        reference: Option<SubtitlesReference>,
+    },
+    /// Applies the suggested [`Player::subtitles_sync`] delay to the stream state.
+    ///
+    /// [`Player::subtitles_sync`]: crate::models::player::Player::subtitles_sync
+    ApplySubtitlesSync,
     TimeChanged {
         time: u64,
         duration: u64,
@@ -149,6 +238,21 @@ pub enum ActionPlayer {
     PausedChanged {
         paused: bool,
     },
+    /// The player has started or stopped buffering.
+    BufferingChanged {
+        buffering: bool,
+    },
+    /// The player has switched to another rendition of the stream.
+    QualityChanged {
+        width: u64,
+        height: u64,
+        /// In bits per second
+        bitrate: Option<u64>,
+    },
+    /// The playback has failed with the given player error code.
+    PlaybackError {
+        code: i64,
+    },
     /// User has clicked on the next video button.
     NextVideo,
     /// Video player has ended.
@@ -163,6 +267,9 @@ pub enum ActionPlayer {
 #[serde(tag = "model", content = "args")]
 pub enum ActionLoad {
     AddonDetails(AddonDetailsSelected),
+    Calendar(CalendarSelected),
+    /// Starts playing the stream on the device.
+    CastSession(CastSessionSelected),
     CatalogWithFilters(Option<CatalogWithFiltersSelected>),
     CatalogsWithExtra(CatalogsWithExtraSelected),
     DataExport,
@@ -174,6 +281,9 @@ pub enum ActionLoad {
     MetaDetails(MetaDetailsSelected),
     Player(Box<PlayerSelected>),
     Link,
+    SubtitlesConverter(SubtitlesConverterSelected),
+    /// Decodes the .torrent file or the magnet.
+    TorrentDetails(TorrentDetailsSelected),
 }
 
 #[derive(Clone, Deserialize, Debug)]
@@ -200,7 +310,12 @@ pub enum Action {
     CatalogsWithExtra(ActionCatalogsWithExtra),
     LibraryByType(ActionLibraryByType),
     MetaDetails(ActionMetaDetails),
+    DataExport(ActionDataExport),
+    LibraryImport(ActionLibraryImport),
     StreamingServer(ActionStreamingServer),
+    Downloads(ActionDownloads),
+    CastSession(ActionCastSession),
+    TorrentDetails(ActionTorrentDetails),
     Player(ActionPlayer),
     Load(ActionLoad),
     Search(ActionSearch),
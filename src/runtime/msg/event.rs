@@ -2,6 +2,8 @@ use crate::models::ctx::CtxError;
 use crate::models::player::AnalyticsContext as PlayerAnalyticsContext;
 use crate::types::api::AuthRequest;
 use crate::types::library::LibraryItemId;
+use crate::types::notifications::NotificationDigest;
+use crate::types::player::QoeSummary;
 use crate::types::profile::{AuthKey, Settings, UID};
 use serde::Serialize;
 use url::Url;
@@ -30,6 +32,12 @@ pub enum Event {
         is_binge_enabled: bool,
         is_playing_next_video: bool,
     },
+    /// The quality of experience summary of a playback session,
+    /// emitted when another stream is loaded or the player is unloaded.
+    PlayerQoe {
+        context: PlayerAnalyticsContext,
+        qoe: QoeSummary,
+    },
     TraktPlaying {
         context: PlayerAnalyticsContext,
     },
@@ -54,6 +62,15 @@ pub enum Event {
     DismissedEventsPushedToStorage {
         uid: UID,
     },
+    IntroOutroPushedToStorage {
+        uid: UID,
+    },
+    DownloadsPushedToStorage {
+        ids: Vec<String>,
+    },
+    DataArchiveImported {
+        uid: UID,
+    },
     UserPulledFromAPI {
         uid: UID,
     },
@@ -121,13 +138,26 @@ pub enum Event {
     LibraryItemRewinded {
         id: LibraryItemId,
     },
+    LibraryItemsImported {
+        ids: Vec<LibraryItemId>,
+    },
     LibraryItemNotificationsToggled {
         id: LibraryItemId,
     },
+    LibraryItemNotificationPreferencesUpdated {
+        id: LibraryItemId,
+    },
     /// The notifications for the given LibraryItemId have been dismissed
     NotificationsDismissed {
         id: LibraryItemId,
     },
+    /// New videos are due to be shown as push or OS notifications.
+    ///
+    /// Not emitted during the quiet hours in the settings,
+    /// the digests are delayed until the next pull of notifications after that.
+    NotificationDigestsDue {
+        digests: Vec<NotificationDigest>,
+    },
     MagnetParsed {
         magnet: Url,
     },
@@ -137,6 +167,16 @@ pub enum Event {
     PlayingOnDevice {
         device: String,
     },
+    CastSessionStopped {
+        device: String,
+    },
+    DownloadQueued {
+        id: String,
+    },
+    DownloadCompleted {
+        id: String,
+        library_item_id: Option<LibraryItemId>,
+    },
     Error {
         error: CtxError,
         source: Box<Event>,
@@ -1,6 +1,7 @@
 use crate::models::common::ResourceLoadable;
 use url::Url;
 
+use crate::models::cast_session::CastStatus;
 use crate::models::ctx::CtxError;
 use crate::models::link::LinkError;
 use crate::models::local_search::Searchable;
@@ -9,14 +10,20 @@ use crate::runtime::EnvError;
 use crate::types::addon::{Descriptor, Manifest, ResourceRequest, ResourceResponse};
 use crate::types::api::{
     APIRequest, AuthRequest, DataExportResponse, DatastoreRequest, GetModalResponse,
-    GetNotificationResponse, LinkCodeResponse, LinkDataResponse, SeekLogRequest, SkipGapsRequest,
-    SkipGapsResponse, SuccessResponse,
+    GetNotificationResponse, LinkCodeResponse, LinkDataResponse, SeekLog, SeekLogRequest,
+    SkipGapsRequest, SkipGapsResponse, SuccessResponse,
 };
+use crate::types::events::DismissedEventsBucket;
 use crate::types::library::{LibraryBucket, LibraryItem, LibraryItemId};
+use crate::types::notifications::NotificationsBucket;
 use crate::types::profile::{Auth, AuthKey, Profile, User};
 use crate::types::resource::{MetaItem, Stream};
-use crate::types::streaming_server::{GetHTTPSResponse, NetworkInfo, SettingsResponse, Statistics};
-use crate::types::streams::StreamItemState;
+use crate::types::search_history::SearchHistoryBucket;
+use crate::types::streaming_server::{
+    ActiveServer, File, GetHTTPSResponse, NetworkInfo, SettingsResponse, Statistics,
+};
+use crate::types::streams::{StreamItemState, StreamsBucket};
+use crate::types::subtitles::{SubtitlesError, SubtitlesFile};
 
 pub type CtxStorageResponse = (
     Option<Profile>,
@@ -33,6 +40,19 @@ pub struct CtxAuthResponse {
 
 pub type LibraryPlanResponse = (Vec<String>, Vec<String>);
 
+/// The buckets read back from the storage after importing and migrating a [`DataArchive`].
+///
+/// [`DataArchive`]: crate::types::data_archive::DataArchive
+#[derive(Debug)]
+pub struct DataArchiveImportResponse {
+    pub profile: Profile,
+    pub library: LibraryBucket,
+    pub streams: StreamsBucket,
+    pub search_history: SearchHistoryBucket,
+    pub notifications: NotificationsBucket,
+    pub dismissed_events: DismissedEventsBucket,
+}
+
 //
 // Those messages are meant to be dispatched and handled only inside stremio-core crate
 //
@@ -76,6 +96,9 @@ pub enum Internal {
     },
     /// Dispatched when library item needs to be updated in the memory, storage and API.
     UpdateLibraryItem(LibraryItem),
+    /// Dispatched when multiple library items need to be updated at once,
+    /// e.g. when importing a library from an export file.
+    UpdateLibraryItems(Vec<LibraryItem>),
     /// Dispatched when some of auth, addons or settings changed.
     ProfileChanged,
     /// Dispatched when library changes with a flag if its already persisted.
@@ -97,6 +120,8 @@ pub enum Internal {
     LinkCodeResult(Result<LinkCodeResponse, LinkError>),
     /// Result for loading link data.
     LinkDataResult(String, Result<LinkDataResponse, LinkError>),
+    /// The streaming server in use was switched or it responded with its settings.
+    ActiveServerChanged(ActiveServer),
     /// Result for loading streaming server settings.
     StreamingServerSettingsResult(Url, Result<SettingsResponse, EnvError>),
     /// Result for loading streaming server base url.
@@ -111,6 +136,12 @@ pub enum Internal {
     StreamingServerCreateTorrentResult(String, Result<(), EnvError>),
     /// Result for playing on device.
     StreamingServerPlayOnDeviceResult(String, Result<(), EnvError>),
+    /// Result for sending a command to the device of the cast session.
+    CastSessionCommandResult(String, Result<(), EnvError>),
+    /// Result for the playback status of the device of the cast session.
+    ///
+    /// The device will return None (or `null`) when it's not playing.
+    CastSessionStatusResult(String, Result<Option<CastStatus>, EnvError>),
     // Result for get https endpoint request
     StreamingServerGetHTTPSResult(Url, Result<GetHTTPSResponse, EnvError>),
     /// Result for streaming server statistics.
@@ -121,6 +152,16 @@ pub enum Internal {
         (Url, StatisticsRequest),
         Result<Option<Statistics>, EnvError>,
     ),
+    /// Dispatched periodically by the player while a torrent stream is playing,
+    /// with the playback time and duration in milliseconds.
+    PollStreamingServerStatistics {
+        request: StatisticsRequest,
+        time: u64,
+        duration: u64,
+    },
+    /// Result for creating the torrent of the selected stream on the streaming server,
+    /// when the file to play has to be picked from the files of the torrent.
+    TorrentFilesResult(String, Result<Vec<File>, EnvError>),
     /// Result for fetching resource from addons.
     ResourceRequestResult(ResourceRequest, Box<Result<ResourceResponse, EnvError>>),
     /// Result for fetching manifest from addon.
@@ -146,4 +187,26 @@ pub enum Internal {
     ),
     /// When dismissed events changed
     DismissedEventsChanged,
+    /// Dispatched when the player has been unloaded or switched to the next video
+    /// of a series, with the seeks and the outro time of the played episode.
+    IntroOutroRecorded {
+        meta_id: String,
+        video_id: String,
+        season: u32,
+        duration: u64,
+        seek_history: Vec<SeekLog>,
+        outro: Option<u64>,
+    },
+    /// The locally detected intros and outros have changed.
+    IntroOutroChanged,
+    /// Result for importing a local data archive into the storage.
+    DataArchiveImportResult(Result<Box<DataArchiveImportResponse>, CtxError>),
+    /// Result for fetching and parsing a subtitles file.
+    SubtitlesFileResult(Url, Result<SubtitlesFile, SubtitlesError>),
+    /// Result for starting a download on the streaming server.
+    DownloadStartResult(String, Result<(), EnvError>),
+    /// Result for the statistics of a download.
+    ///
+    /// Server will return None (or `null`) when the file has been fully downloaded.
+    DownloadStatisticsResult(String, Result<Option<Statistics>, EnvError>),
 }
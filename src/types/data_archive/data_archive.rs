@@ -0,0 +1,62 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    constants::SCHEMA_VERSION,
+    runtime::Env,
+    types::{
+        events::DismissedEventsBucket, library::LibraryBucket, notifications::NotificationsBucket,
+        profile::Profile, search_history::SearchHistoryBucket, streams::StreamsBucket,
+    },
+};
+
+/// A local backup of all the user data which can be restored without the API.
+///
+/// The [`Profile::auth`] is never exported as the archive can be shared or stored unencrypted,
+/// only the settings and the addons of the profile are restored.
+///
+/// The buckets are kept as raw JSON values because archives created with an older
+/// [`SCHEMA_VERSION`] have to be migrated before they can be deserialized.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DataArchive {
+    /// The storage schema version of the buckets in the archive.
+    pub schema_version: u32,
+    /// The time of the export.
+    pub created: DateTime<Utc>,
+    pub profile: serde_json::Value,
+    pub library: serde_json::Value,
+    pub streams: serde_json::Value,
+    pub search_history: serde_json::Value,
+    pub notifications: serde_json::Value,
+    pub dismissed_events: serde_json::Value,
+}
+
+impl DataArchive {
+    pub fn new<E: Env + 'static>(
+        profile: &Profile,
+        library: &LibraryBucket,
+        streams: &StreamsBucket,
+        search_history: &SearchHistoryBucket,
+        notifications: &NotificationsBucket,
+        dismissed_events: &DismissedEventsBucket,
+    ) -> Self {
+        DataArchive {
+            schema_version: SCHEMA_VERSION,
+            created: E::now(),
+            profile: serde_json::to_value(Profile {
+                auth: None,
+                ..profile.to_owned()
+            })
+            .expect("Failed to serialize profile"),
+            library: serde_json::to_value(library).expect("Failed to serialize library"),
+            streams: serde_json::to_value(streams).expect("Failed to serialize streams"),
+            search_history: serde_json::to_value(search_history)
+                .expect("Failed to serialize search history"),
+            notifications: serde_json::to_value(notifications)
+                .expect("Failed to serialize notifications"),
+            dismissed_events: serde_json::to_value(dismissed_events)
+                .expect("Failed to serialize dismissed events"),
+        }
+    }
+}
@@ -0,0 +1,2 @@
+mod data_archive;
+pub use data_archive::*;
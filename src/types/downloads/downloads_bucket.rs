@@ -0,0 +1,97 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use url::Url;
+
+use crate::types::library::LibraryItemId;
+use crate::types::resource::{Stream, StreamSource};
+
+/// The streams queued for a full download on the streaming server.
+///
+/// Unlike the other buckets it's not bound to the user account,
+/// as the downloaded files are stored on the device.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DownloadsBucket {
+    /// Downloads in the order they were queued.
+    pub items: Vec<DownloadItem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadItem {
+    /// `{info_hash}/{file_idx}` for torrents and the hex encoded sha1 of the url for plain urls.
+    pub id: String,
+    pub stream: Stream,
+    /// The [`LibraryItem`] the stream was downloaded for.
+    ///
+    /// [`LibraryItem`]: crate::types::library::LibraryItem
+    pub library_item_id: Option<LibraryItemId>,
+    pub video_id: Option<String>,
+    pub state: DownloadState,
+    /// The streaming server the stream is downloaded on, `None` until the download is started.
+    #[serde(default)]
+    pub streaming_server_url: Option<Url>,
+    pub ctime: DateTime<Utc>,
+}
+
+impl DownloadItem {
+    /// Returns `None` for sources which can't be downloaded by the streaming server.
+    pub fn new(
+        stream: Stream,
+        library_item_id: Option<LibraryItemId>,
+        video_id: Option<String>,
+        now: DateTime<Utc>,
+    ) -> Option<Self> {
+        let id = match &stream.source {
+            StreamSource::Torrent {
+                info_hash,
+                file_idx,
+                ..
+            } => format!(
+                "{}/{}",
+                hex::encode(info_hash),
+                file_idx.map_or_else(|| "-1".to_owned(), |file_idx| file_idx.to_string())
+            ),
+            StreamSource::Url { url } => {
+                let mut hasher = Sha1::new();
+                hasher.update(url.as_str());
+                hex::encode(hasher.finalize())
+            }
+            _ => return None,
+        };
+        Some(DownloadItem {
+            id,
+            stream,
+            library_item_id,
+            video_id,
+            state: DownloadState::Queued,
+            streaming_server_url: None,
+            ctime: now,
+        })
+    }
+    #[inline]
+    pub fn is_active(&self) -> bool {
+        matches!(self.state, DownloadState::Downloading { .. })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum DownloadState {
+    Queued,
+    #[serde(rename_all = "camelCase")]
+    Downloading {
+        /// From `0.0` to `1.0`
+        progress: f64,
+        downloaded: u64,
+        download_speed: f64,
+    },
+    /// The download can be played offline with the local stream
+    /// served by the streaming server, see [`Downloads::local_stream`].
+    ///
+    /// [`Downloads::local_stream`]: crate::models::downloads::Downloads::local_stream
+    Completed,
+    Failed {
+        error: String,
+    },
+}
@@ -0,0 +1,2 @@
+mod downloads_bucket;
+pub use downloads_bucket::*;
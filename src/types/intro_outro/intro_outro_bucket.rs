@@ -0,0 +1,279 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::types::api::SeekLog;
+use crate::types::player::IntroData;
+use crate::types::profile::UID;
+
+/// Seeks starting later than this are not considered as skipping the intro, in milliseconds.
+const INTRO_MAX_START: u64 = 5 * 60 * 1000;
+/// The length range of a seek which skips the intro, in milliseconds.
+const INTRO_LENGTH_RANGE: (u64, u64) = (15 * 1000, 3 * 60 * 1000);
+/// The maximum difference between the timings of two episodes
+/// for them to be considered the same intro or outro, in milliseconds.
+const MATCH_TOLERANCE: u64 = 10 * 1000;
+/// The minimum number of episodes with matching timings before an intro or an outro is suggested.
+const MIN_EPISODES: usize = 2;
+/// The number of the latest episodes kept per series.
+const RECORDS_COUNT: usize = 30;
+
+/// The intros and outros of series episodes skipped by the user, used to suggest
+/// the [`IntroOutro`] when the skip gaps API has no data for the stream.
+///
+/// [`IntroOutro`]: crate::types::player::IntroOutro
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntroOutroBucket {
+    pub uid: UID,
+    /// The key is the series (meta item) id.
+    pub items: HashMap<String, IntroOutroItem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntroOutroItem {
+    pub intros: Vec<IntroRecord>,
+    pub outros: Vec<OutroRecord>,
+    pub mtime: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntroRecord {
+    pub video_id: String,
+    /// In milliseconds
+    pub from: u64,
+    /// In milliseconds
+    pub to: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutroRecord {
+    pub video_id: String,
+    pub season: u32,
+    /// The time left until the end of the video when the outro started, in milliseconds.
+    ///
+    /// Outros have a consistent length within a season,
+    /// while the length of the episodes differs.
+    pub remaining: u64,
+}
+
+impl IntroOutroBucket {
+    pub fn new(uid: UID) -> Self {
+        Self {
+            uid,
+            items: HashMap::new(),
+        }
+    }
+    /// Records the intro skipped with the seeks and the outro of a series episode.
+    ///
+    /// Returns `false` when there is neither an intro nor an outro to record.
+    #[allow(clippy::too_many_arguments)]
+    pub fn record(
+        &mut self,
+        meta_id: &str,
+        video_id: &str,
+        season: u32,
+        duration: u64,
+        seek_history: &[SeekLog],
+        outro: Option<u64>,
+        now: DateTime<Utc>,
+    ) -> bool {
+        let intro = seek_history
+            .iter()
+            .find(|seek| {
+                seek.from <= INTRO_MAX_START
+                    && seek.to > seek.from
+                    && (INTRO_LENGTH_RANGE.0..=INTRO_LENGTH_RANGE.1)
+                        .contains(&(seek.to - seek.from))
+            })
+            .map(|seek| IntroRecord {
+                video_id: video_id.to_owned(),
+                from: seek.from,
+                to: seek.to,
+            });
+        // the user may switch to the next video at any time, only the second half is an outro
+        let outro = outro
+            .filter(|outro| *outro < duration && *outro >= duration / 2)
+            .map(|outro| OutroRecord {
+                video_id: video_id.to_owned(),
+                season,
+                remaining: duration - outro,
+            });
+        if intro.is_none() && outro.is_none() {
+            return false;
+        }
+
+        let item = self
+            .items
+            .entry(meta_id.to_owned())
+            .or_insert_with(|| IntroOutroItem {
+                intros: vec![],
+                outros: vec![],
+                mtime: now,
+            });
+        if let Some(intro) = intro {
+            item.intros.retain(|record| record.video_id != video_id);
+            item.intros.push(intro);
+            let overflow = item.intros.len().saturating_sub(RECORDS_COUNT);
+            item.intros.drain(..overflow);
+        }
+        if let Some(outro) = outro {
+            item.outros.retain(|record| record.video_id != video_id);
+            item.outros.push(outro);
+            let overflow = item.outros.len().saturating_sub(RECORDS_COUNT);
+            item.outros.drain(..overflow);
+        }
+        item.mtime = now;
+        true
+    }
+}
+
+impl IntroOutroItem {
+    /// The intro skipped at about the same time in at least a few episodes of the series.
+    pub fn intro(&self) -> Option<IntroData> {
+        let timings = self
+            .intros
+            .iter()
+            .map(|record| (record.from, record.to))
+            .collect::<Vec<_>>();
+        consensus(&timings).map(|(from, to)| IntroData {
+            from,
+            to,
+            duration: None,
+        })
+    }
+    /// The outro time of a video with the given duration, if the outros of the
+    /// other episodes in the season started at about the same time before their end.
+    pub fn outro(&self, season: u32, duration: u64) -> Option<u64> {
+        let timings = self
+            .outros
+            .iter()
+            .filter(|record| record.season == season)
+            .map(|record| (record.remaining, 0))
+            .collect::<Vec<_>>();
+        consensus(&timings)
+            .map(|(remaining, _)| remaining)
+            .filter(|remaining| *remaining < duration)
+            .map(|remaining| duration - remaining)
+    }
+}
+
+/// Returns the median timings of the largest group of episodes with matching timings,
+/// if the group is large enough.
+fn consensus(timings: &[(u64, u64)]) -> Option<(u64, u64)> {
+    let matches = |a: &(u64, u64), b: &(u64, u64)| {
+        a.0.abs_diff(b.0) <= MATCH_TOLERANCE && a.1.abs_diff(b.1) <= MATCH_TOLERANCE
+    };
+    let mut group = timings
+        .iter()
+        .map(|timing| {
+            timings
+                .iter()
+                .filter(|other| matches(timing, other))
+                .copied()
+                .collect::<Vec<_>>()
+        })
+        // the first group is kept on equal sizes
+        .fold(Vec::new(), |largest, group| {
+            if group.len() > largest.len() {
+                group
+            } else {
+                largest
+            }
+        });
+    if group.len() < MIN_EPISODES {
+        return None;
+    }
+    let middle = group.len() / 2;
+    group.sort_unstable_by_key(|timing| timing.0);
+    let first = group[middle].0;
+    group.sort_unstable_by_key(|timing| timing.1);
+    let second = group[middle].1;
+    Some((first, second))
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{TimeZone, Utc};
+
+    use super::IntroOutroBucket;
+    use crate::types::api::SeekLog;
+    use crate::types::player::IntroData;
+
+    #[test]
+    fn intro_outro_consensus() {
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let mut bucket = IntroOutroBucket::default();
+        let seek = |from, to| vec![SeekLog { from, to }];
+
+        assert!(bucket.record(
+            "tt1",
+            "tt1:1:1",
+            1,
+            2_400_000,
+            &seek(30_000, 110_000),
+            None,
+            now
+        ));
+        assert_eq!(
+            bucket.items["tt1"].intro(),
+            None,
+            "A single episode is not enough"
+        );
+
+        assert!(bucket.record(
+            "tt1",
+            "tt1:1:2",
+            1,
+            2_500_000,
+            &seek(32_000, 112_000),
+            Some(2_410_000),
+            now
+        ));
+        assert!(bucket.record(
+            "tt1",
+            "tt1:1:3",
+            1,
+            2_300_000,
+            &seek(31_000, 108_000),
+            Some(2_212_000),
+            now
+        ));
+        assert!(bucket.record(
+            "tt1",
+            "tt1:1:4",
+            2,
+            2_300_000,
+            &seek(400_000, 500_000),
+            Some(2_000_000),
+            now
+        ));
+        assert!(
+            !bucket.record(
+                "tt1",
+                "tt1:1:5",
+                1,
+                2_300_000,
+                &seek(0, 2_000),
+                Some(100_000),
+                now
+            ),
+            "Neither the short seek nor the early next video are recorded"
+        );
+
+        let item = &bucket.items["tt1"];
+        assert_eq!(
+            item.intro(),
+            Some(IntroData {
+                from: 31_000,
+                to: 110_000,
+                duration: None,
+            })
+        );
+        assert_eq!(item.outro(1, 2_000_000), Some(1_910_000));
+        assert_eq!(item.outro(2, 2_000_000), None);
+    }
+}
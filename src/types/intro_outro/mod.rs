@@ -0,0 +1,2 @@
+mod intro_outro_bucket;
+pub use intro_outro_bucket::*;
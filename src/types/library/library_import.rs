@@ -0,0 +1,318 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::types::resource::SeriesInfo;
+
+/// The export file formats which can be imported into the [`LibraryBucket`].
+///
+/// [`LibraryBucket`]: crate::types::library::LibraryBucket
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub enum LibraryImportSource {
+    /// Trakt `history` JSON export (movies and episodes).
+    TraktHistory,
+    /// Trakt `watchlist` JSON export (movies and shows).
+    TraktWatchlist,
+    /// IMDb `ratings.csv` export, every rated title is considered watched.
+    IMDbRatings,
+    /// IMDb `watchlist.csv` export.
+    IMDbWatchlist,
+    /// Letterboxd `watched.csv` or `diary.csv` export.
+    LetterboxdWatched,
+    /// Letterboxd `watchlist.csv` export.
+    LetterboxdWatchlist,
+}
+
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
+#[serde(tag = "type", content = "content")]
+pub enum LibraryImportError {
+    /// The export file is not a valid JSON of the expected format.
+    InvalidJSON(String),
+    /// The export file is not a valid CSV.
+    InvalidCSV(String),
+    /// A column required by the export format is missing from the CSV header.
+    MissingColumn(String),
+    /// The export file does not contain any importable entries.
+    Empty,
+}
+
+/// A single title parsed from an export file, before resolving it through the addons.
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryImportEntry {
+    /// The title as found in the export file.
+    pub name: String,
+    /// Either `movie` or `series`
+    pub r#type: String,
+    pub year: Option<i32>,
+    /// The IMDb id (`tt...`) if the export file provides one.
+    pub imdb_id: Option<String>,
+    /// Whether the title has been watched (movies) or
+    /// whether any of its episodes have been watched (series).
+    pub watched: bool,
+    /// The last time the title was watched or rated.
+    pub last_watched: Option<DateTime<Utc>>,
+    /// The watched episodes of a series.
+    pub episodes: Vec<SeriesInfo>,
+}
+
+impl LibraryImportEntry {
+    fn key(&self) -> (String, Option<i32>, String) {
+        match &self.imdb_id {
+            Some(imdb_id) => (imdb_id.to_owned(), None, self.r#type.to_owned()),
+            None => (self.name.to_lowercase(), self.year, self.r#type.to_owned()),
+        }
+    }
+    fn merge(&mut self, other: LibraryImportEntry) {
+        self.watched |= other.watched;
+        self.last_watched = self.last_watched.max(other.last_watched);
+        for series_info in other.episodes {
+            if !self.episodes.contains(&series_info) {
+                self.episodes.push(series_info);
+            }
+        }
+    }
+}
+
+/// Parses the content of an export file into [`LibraryImportEntry`]s.
+///
+/// Entries referring to the same title (e.g. multiple episodes of a show in the Trakt history)
+/// are merged into a single entry, keeping the order of first appearance.
+pub fn parse_library_import(
+    source: LibraryImportSource,
+    content: &str,
+) -> Result<Vec<LibraryImportEntry>, LibraryImportError> {
+    let entries = match source {
+        LibraryImportSource::TraktHistory | LibraryImportSource::TraktWatchlist => {
+            parse_trakt(content)?
+        }
+        LibraryImportSource::IMDbRatings => parse_imdb(content, true)?,
+        LibraryImportSource::IMDbWatchlist => parse_imdb(content, false)?,
+        LibraryImportSource::LetterboxdWatched => parse_letterboxd(content, true)?,
+        LibraryImportSource::LetterboxdWatchlist => parse_letterboxd(content, false)?,
+    };
+    let mut positions = HashMap::new();
+    let mut merged_entries: Vec<LibraryImportEntry> = vec![];
+    for entry in entries {
+        match positions.get(&entry.key()) {
+            Some(position) => merged_entries[*position].merge(entry),
+            None => {
+                positions.insert(entry.key(), merged_entries.len());
+                merged_entries.push(entry);
+            }
+        }
+    }
+    if merged_entries.is_empty() {
+        return Err(LibraryImportError::Empty);
+    }
+    Ok(merged_entries)
+}
+
+#[derive(Deserialize)]
+struct TraktIds {
+    imdb: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TraktTitle {
+    title: Option<String>,
+    year: Option<i32>,
+    ids: TraktIds,
+}
+
+#[derive(Deserialize)]
+struct TraktEpisode {
+    season: u32,
+    number: u32,
+}
+
+#[derive(Deserialize)]
+struct TraktItem {
+    r#type: String,
+    watched_at: Option<DateTime<Utc>>,
+    movie: Option<TraktTitle>,
+    show: Option<TraktTitle>,
+    episode: Option<TraktEpisode>,
+}
+
+fn parse_trakt(content: &str) -> Result<Vec<LibraryImportEntry>, LibraryImportError> {
+    let items = serde_json::from_str::<Vec<TraktItem>>(content)
+        .map_err(|error| LibraryImportError::InvalidJSON(error.to_string()))?;
+    Ok(items
+        .into_iter()
+        .filter_map(|item| {
+            let watched = item.watched_at.is_some();
+            let (title, r#type, episodes) = match item.r#type.as_str() {
+                "movie" => (item.movie?, "movie", vec![]),
+                "show" => (item.show?, "series", vec![]),
+                "episode" => {
+                    let episode = item.episode?;
+                    (
+                        item.show?,
+                        "series",
+                        vec![SeriesInfo {
+                            season: episode.season,
+                            episode: episode.number,
+                        }],
+                    )
+                }
+                _ => return None,
+            };
+            Some(LibraryImportEntry {
+                name: title.title.unwrap_or_default(),
+                r#type: r#type.to_owned(),
+                year: title.year,
+                imdb_id: title.ids.imdb.filter(|imdb_id| imdb_id.starts_with("tt")),
+                watched,
+                last_watched: item.watched_at,
+                episodes,
+            })
+        })
+        .collect())
+}
+
+fn parse_imdb(content: &str, watched: bool) -> Result<Vec<LibraryImportEntry>, LibraryImportError> {
+    let table = CsvTable::parse(content)?;
+    let id_column = table.column("Const")?;
+    let title_column = table.column("Title")?;
+    let title_type_column = table.column("Title Type")?;
+    let year_column = table.column("Year")?;
+    let date_column = match watched {
+        true => Some(table.column("Date Rated")?),
+        false => None,
+    };
+    Ok(table
+        .rows
+        .iter()
+        .filter_map(|row| {
+            let title_type = row
+                .get(title_type_column)?
+                .to_lowercase()
+                .replace([' ', '-'], "");
+            let r#type = match title_type.as_str() {
+                "movie" | "tvmovie" | "video" | "short" | "tvshort" | "tvspecial" => "movie",
+                "tvseries" | "tvminiseries" => "series",
+                _ => return None,
+            };
+            let last_watched = date_column
+                .and_then(|column| row.get(column))
+                .and_then(|date| parse_date(date));
+            Some(LibraryImportEntry {
+                name: row.get(title_column)?.to_owned(),
+                r#type: r#type.to_owned(),
+                year: row.get(year_column).and_then(|year| year.parse().ok()),
+                imdb_id: row
+                    .get(id_column)
+                    .filter(|imdb_id| imdb_id.starts_with("tt"))
+                    .cloned(),
+                watched,
+                last_watched,
+                episodes: vec![],
+            })
+        })
+        .collect())
+}
+
+fn parse_letterboxd(
+    content: &str,
+    watched: bool,
+) -> Result<Vec<LibraryImportEntry>, LibraryImportError> {
+    let table = CsvTable::parse(content)?;
+    let name_column = table.column("Name")?;
+    let year_column = table.column("Year")?;
+    let date_column = table.column("Date")?;
+    // `diary.csv` has the actual watched date, `watched.csv` only the date it was logged
+    let watched_date_column = table.column("Watched Date").ok();
+    Ok(table
+        .rows
+        .iter()
+        .filter_map(|row| {
+            let last_watched = watched_date_column
+                .and_then(|column| row.get(column))
+                .or_else(|| row.get(date_column))
+                .and_then(|date| parse_date(date));
+            Some(LibraryImportEntry {
+                name: row.get(name_column)?.to_owned(),
+                r#type: "movie".to_owned(),
+                year: row.get(year_column).and_then(|year| year.parse().ok()),
+                imdb_id: None,
+                watched,
+                last_watched: last_watched.filter(|_| watched),
+                episodes: vec![],
+            })
+        })
+        .collect())
+}
+
+fn parse_date(date: &str) -> Option<DateTime<Utc>> {
+    NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|date| Utc.from_utc_datetime(&date))
+}
+
+/// A minimal RFC 4180 CSV reader, enough for the export files of the supported services.
+struct CsvTable {
+    header: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl CsvTable {
+    fn parse(content: &str) -> Result<Self, LibraryImportError> {
+        let content = content.trim_start_matches('\u{feff}');
+        let mut records = vec![];
+        let mut record = vec![];
+        let mut field = String::new();
+        let mut quoted = false;
+        let mut chars = content.chars().peekable();
+        while let Some(char) = chars.next() {
+            match char {
+                '"' if quoted => {
+                    if chars.peek() == Some(&'"') {
+                        chars.next();
+                        field.push('"');
+                    } else {
+                        quoted = false;
+                    }
+                }
+                '"' if field.is_empty() => quoted = true,
+                ',' if !quoted => record.push(std::mem::take(&mut field)),
+                '\r' if !quoted => {}
+                '\n' if !quoted => {
+                    record.push(std::mem::take(&mut field));
+                    records.push(std::mem::take(&mut record));
+                }
+                _ => field.push(char),
+            }
+        }
+        if quoted {
+            return Err(LibraryImportError::InvalidCSV(
+                "Unterminated quoted field".to_owned(),
+            ));
+        }
+        if !field.is_empty() || !record.is_empty() {
+            record.push(field);
+            records.push(record);
+        }
+        let mut records = records
+            .into_iter()
+            .filter(|record| record.iter().any(|field| !field.is_empty()));
+        let header = records
+            .next()
+            .ok_or_else(|| LibraryImportError::InvalidCSV("Missing header".to_owned()))?
+            .into_iter()
+            .map(|column| column.trim().to_owned())
+            .collect();
+        Ok(CsvTable {
+            header,
+            rows: records.collect(),
+        })
+    }
+    fn column(&self, name: &str) -> Result<usize, LibraryImportError> {
+        self.header
+            .iter()
+            .position(|column| column.eq_ignore_ascii_case(name))
+            .ok_or_else(|| LibraryImportError::MissingColumn(name.to_owned()))
+    }
+}
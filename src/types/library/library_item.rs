@@ -9,7 +9,10 @@ use url::Url;
 
 use crate::{
     runtime::Env,
-    types::{resource::{MetaItemBehaviorHints, MetaItemPreview, PosterShape, Video}},
+    types::{
+        library::NotificationPreferences,
+        resource::{MetaItemBehaviorHints, MetaItemPreview, PosterShape, Video},
+    },
 };
 
 pub type LibraryItemId = String;
@@ -78,8 +81,25 @@ impl LibraryItem {
     /// - `LibraryItem.behavior_hints.default_video_id` should be `None`
     /// - The LibraryItem should not have been removed from the Library
     /// - The LibraryItem should not be temporary but in your LibraryItem
-    pub fn should_pull_notifications(&self) -> bool {
+    /// - The LibraryItem should have been watched recently
+    /// if [`NotificationPreferences::watched_within_days`] is set
+    pub fn should_pull_notifications<E: Env + 'static>(&self) -> bool {
+        let watched_recently = match self.state.notif_prefs.watched_within_days {
+            Some(days) => self
+                .state
+                .last_watched
+                .map(|last_watched| {
+                    // the item is always recent when the deadline is past the maximum date
+                    last_watched
+                        .checked_add_signed(Duration::days(days.into()))
+                        .map(|deadline| deadline >= E::now())
+                        .unwrap_or(true)
+                })
+                .unwrap_or_default(),
+            None => true,
+        };
         !self.state.no_notif
+            && watched_recently
             && self.r#type != "other"
             && self.r#type != "movie"
             && self.behavior_hints.default_video_id.is_none()
@@ -186,6 +206,10 @@ pub struct LibraryItemState {
     /// Default: receive notifications
     #[serde(default)]
     pub no_notif: bool,
+    /// Which notifications to receive for the given [`LibraryItem`]
+    /// when notifications are not turned off with `no_notif`.
+    #[serde(default, skip_serializing_if = "NotificationPreferences::is_default")]
+    pub notif_prefs: NotificationPreferences,
 }
 
 impl LibraryItemState {
@@ -1,5 +1,11 @@
 mod library_bucket;
 pub use library_bucket::*;
 
+mod library_import;
+pub use library_import::*;
+
 mod library_item;
 pub use library_item::*;
+
+mod notification_preferences;
+pub use notification_preferences::*;
@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::resource::{SeriesInfo, Video};
+
+/// Narrows down the notifications received for a [`LibraryItem`].
+///
+/// All the preferences are combined, i.e. a video has to match all of the enabled ones.
+/// Turning off notifications altogether is still done with [`LibraryItemState::no_notif`].
+///
+/// [`LibraryItem`]: crate::types::library::LibraryItem
+/// [`LibraryItemState::no_notif`]: crate::types::library::LibraryItemState::no_notif
+#[derive(Default, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationPreferences {
+    /// Notify only for the first episode of a season after the one currently watched.
+    #[serde(default)]
+    pub new_seasons_only: bool,
+    /// Notify only for episodes after the one currently watched.
+    #[serde(default)]
+    pub after_current_episode: bool,
+    /// Notify only if the item has been watched in the given number of days.
+    #[serde(default)]
+    pub watched_within_days: Option<u32>,
+}
+
+impl NotificationPreferences {
+    pub fn is_default(&self) -> bool {
+        *self == NotificationPreferences::default()
+    }
+    /// Whether a notification should be created for the given video.
+    ///
+    /// `current` is the [`SeriesInfo`] of the last watched video and `videos`
+    /// are all the videos of the meta item.
+    pub fn should_notify(
+        &self,
+        video: &Video,
+        current: Option<&SeriesInfo>,
+        videos: &[Video],
+    ) -> bool {
+        let series_info = match &video.series_info {
+            Some(series_info) => series_info,
+            // without a season and an episode only the default preferences can be applied
+            None => return !self.new_seasons_only && !self.after_current_episode,
+        };
+        let is_after_current = current
+            .map(|current| {
+                (series_info.season, series_info.episode) > (current.season, current.episode)
+            })
+            .unwrap_or(true);
+        if self.after_current_episode && !is_after_current {
+            return false;
+        }
+
+        if self.new_seasons_only {
+            let is_new_season = current
+                .map(|current| series_info.season > current.season)
+                .unwrap_or(true);
+            // seasons don't always start from episode 1 so we check for the first one we know of
+            let is_season_premiere = !videos.iter().any(|other_video| {
+                other_video
+                    .series_info
+                    .as_ref()
+                    .map(|other| {
+                        other.season == series_info.season && other.episode < series_info.episode
+                    })
+                    .unwrap_or_default()
+            });
+            return is_new_season && is_season_premiere;
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::types::resource::{SeriesInfo, Video};
+
+    use super::NotificationPreferences;
+
+    fn video(season: u32, episode: u32) -> Video {
+        Video {
+            id: format!("tt1:{season}:{episode}"),
+            series_info: Some(SeriesInfo { season, episode }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_notify_with_preferences() {
+        let videos = vec![
+            video(1, 1),
+            video(1, 2),
+            video(1, 3),
+            video(2, 1),
+            video(2, 2),
+            video(3, 0),
+            video(3, 1),
+        ];
+        let current = SeriesInfo {
+            season: 1,
+            episode: 2,
+        };
+        let notified = |preferences: NotificationPreferences| {
+            videos
+                .iter()
+                .filter(|video| preferences.should_notify(video, Some(&current), &videos))
+                .map(|video| video.id.as_str())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(notified(Default::default()).len(), videos.len());
+        assert_eq!(
+            notified(NotificationPreferences {
+                after_current_episode: true,
+                ..Default::default()
+            }),
+            vec!["tt1:1:3", "tt1:2:1", "tt1:2:2", "tt1:3:0", "tt1:3:1"]
+        );
+        assert_eq!(
+            notified(NotificationPreferences {
+                new_seasons_only: true,
+                ..Default::default()
+            }),
+            vec!["tt1:2:1", "tt1:3:0"],
+            "Only the first known episode of a later season should be notified"
+        );
+    }
+}
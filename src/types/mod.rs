@@ -1,6 +1,9 @@
 pub mod addon;
 pub mod api;
+pub mod data_archive;
+pub mod downloads;
 pub mod events;
+pub mod intro_outro;
 pub mod library;
 pub mod notifications;
 pub mod player;
@@ -9,6 +12,8 @@ pub mod resource;
 pub mod search_history;
 pub mod streaming_server;
 pub mod streams;
+pub mod subtitles;
+pub mod torrent;
 
 mod query_params_encode;
 pub use query_params_encode::*;
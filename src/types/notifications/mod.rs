@@ -3,3 +3,6 @@ pub use notifications_bucket::*;
 
 mod notification_item;
 pub use notification_item::*;
+
+mod notification_digest;
+pub use notification_digest::*;
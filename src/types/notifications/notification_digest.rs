@@ -0,0 +1,74 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use url::Url;
+
+use crate::types::{
+    library::LibraryBucket,
+    notifications::{NotificationItem, NotificationsBucket},
+    resource::MetaItemId,
+};
+
+/// The new videos of a single meta item, delivered as one notification.
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationDigest {
+    pub meta_id: MetaItemId,
+    pub name: String,
+    pub poster: Option<Url>,
+    /// The new videos ordered by their release date.
+    pub items: Vec<NotificationItem>,
+}
+
+impl NotificationDigest {
+    /// Groups the notifications for videos which were not delivered yet per meta item.
+    ///
+    /// Videos are tracked by id rather than by their release date as they can be pulled
+    /// long after being released, e.g. when an addon lags behind or a series is added to the library.
+    /// Only videos released after the bucket was created are included,
+    /// this way we don't alert for the whole backlog on first run.
+    /// Digests are ordered by their latest video, the newest first.
+    pub fn pending(notifications: &NotificationsBucket, library: &LibraryBucket) -> Vec<Self> {
+        let mut digests = notifications
+            .items
+            .iter()
+            .filter_map(|(meta_id, meta_notifications)| {
+                let library_item = library.items.get(meta_id)?;
+                let delivered = notifications.delivered.get(meta_id);
+                let mut items = meta_notifications
+                    .values()
+                    .filter(|item| item.video_released > notifications.created)
+                    .filter(|item| {
+                        delivered
+                            .map(|delivered| !delivered.contains(&item.video_id))
+                            .unwrap_or(true)
+                    })
+                    .cloned()
+                    .collect::<Vec<_>>();
+                if items.is_empty() {
+                    return None;
+                }
+
+                items.sort_by(|a, b| {
+                    a.video_released
+                        .cmp(&b.video_released)
+                        .then_with(|| a.video_id.cmp(&b.video_id))
+                });
+                Some(NotificationDigest {
+                    meta_id: meta_id.to_owned(),
+                    name: library_item.name.to_owned(),
+                    poster: library_item.poster.to_owned(),
+                    items,
+                })
+            })
+            .collect::<Vec<_>>();
+        digests.sort_by(|a, b| {
+            b.latest_released()
+                .cmp(&a.latest_released())
+                .then_with(|| a.meta_id.cmp(&b.meta_id))
+        });
+        digests
+    }
+    fn latest_released(&self) -> Option<&DateTime<Utc>> {
+        self.items.last().map(|item| &item.video_released)
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::{hash_map::Entry, HashMap};
+use std::collections::{hash_map::Entry, HashMap, HashSet};
 
 #[cfg(test)]
 use chrono::offset::TimeZone;
@@ -29,6 +29,16 @@ pub struct NotificationsBucket {
     /// The last time notifications were pulled.
     #[serde(default)]
     pub last_updated: Option<DateTime<Utc>>,
+    /// The last time a [`NotificationDigest`] was delivered.
+    ///
+    /// [`NotificationDigest`]: crate::types::notifications::NotificationDigest
+    #[serde(default)]
+    pub last_delivered: Option<DateTime<Utc>>,
+    /// The videos already delivered in a [`NotificationDigest`] per meta item.
+    ///
+    /// [`NotificationDigest`]: crate::types::notifications::NotificationDigest
+    #[serde(default)]
+    pub delivered: HashMap<MetaItemId, HashSet<VideoId>>,
     /// The moment that the notification bucket was initialized.
     #[cfg_attr(test, derivative(Default(value = "Utc.timestamp_opt(0, 0).unwrap()")))]
     pub created: DateTime<Utc>,
@@ -52,6 +62,8 @@ impl NotificationsBucket {
                 acc
             }),
             last_updated: None,
+            last_delivered: None,
+            delivered: HashMap::new(),
             created: E::now(),
         }
     }
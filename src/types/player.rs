@@ -16,3 +16,47 @@ pub struct IntroData {
     /// and stream duration ([`LibraryItem.state.duration`]) > 0!
     pub duration: Option<u64>,
 }
+
+/// Where the playback of the selected video should start, see [`Player::resume`].
+///
+/// [`Player::resume`]: crate::models::player::Player::resume
+#[derive(Clone, Serialize, Debug, PartialEq, Eq)]
+#[serde(tag = "type", content = "content")]
+pub enum ResumeDecision {
+    /// Play from the beginning.
+    Start,
+    /// Seek to the stored offset, rewound a bit after a long break.
+    Resume {
+        /// In milliseconds
+        time: u64,
+        /// How much the stored offset was rewound, in milliseconds.
+        rewound: u64,
+    },
+    /// The stored offset is already in the outro (credits), so the video is marked as watched
+    /// and the [`Player::next_video`] should be played instead, if there is one.
+    ///
+    /// [`Player::next_video`]: crate::models::player::Player::next_video
+    Finished,
+}
+
+/// The quality of experience of a playback session, see [`Player::qoe`].
+///
+/// [`Player::qoe`]: crate::models::player::Player::qoe
+#[derive(Clone, Default, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct QoeSummary {
+    /// The time from loading the player until the playback started, in milliseconds.
+    pub startup_time: Option<i64>,
+    /// The number of times the playback stalled for buffering after it had started.
+    pub stalls: u32,
+    /// The time spent stalled after the playback had started, in milliseconds.
+    pub stall_time: i64,
+    /// The share of the time since the playback started spent stalled, in permille
+    /// between `0` and `1000`.
+    pub stall_permille: u32,
+    pub quality_changes: u32,
+    /// The latest reported bitrate, in bits per second.
+    pub bitrate: Option<u64>,
+    /// The codes of the playback errors in the order they occurred.
+    pub error_codes: Vec<i64>,
+}
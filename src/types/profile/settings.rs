@@ -1,5 +1,6 @@
 use crate::constants::STREAMING_SERVER_URL;
-use chrono::{DateTime, Utc};
+use crate::types::resource::Video;
+use chrono::{DateTime, Duration, Timelike, Utc};
 use serde::{Deserialize, Serialize};
 use url::Url;
 
@@ -8,8 +9,13 @@ use url::Url;
 pub struct Settings {
     pub interface_language: String,
     pub streaming_server_url: Url,
+    /// Other known streaming servers, in the order in which they are tried
+    /// when the [`Settings::streaming_server_url`] is unreachable.
+    pub streaming_servers: Vec<Url>,
     pub player_type: Option<String>,
     pub binge_watching: bool,
+    /// Which video is played next when [`Settings::binge_watching`] is enabled.
+    pub binge_rules: BingeRules,
     pub play_in_background: bool,
     pub hardware_decoding: bool,
     pub frame_rate_matching_strategy: FrameRateMatchingStrategy,
@@ -37,7 +43,11 @@ pub struct Settings {
     /// Whether we should pause the playback when the application get's minimized
     pub pause_on_minimize: bool,
     pub surround_sound: bool,
+    /// How the streams of all addons are sorted and filtered.
+    pub stream_preferences: StreamPreferences,
     pub streaming_server_warning_dismissed: Option<DateTime<Utc>>,
+    /// The daily period in which notifications should not be delivered.
+    pub notifications_quiet_hours: Option<QuietHours>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -47,17 +57,133 @@ pub enum FrameRateMatchingStrategy {
     FrameRateAndResolution,
 }
 
+#[derive(Clone, Default, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BingeRules {
+    /// Skip the episodes which are already marked as watched.
+    pub skip_watched: bool,
+    /// Don't continue with the next season after its last episode.
+    pub stop_at_season_end: bool,
+    /// Play the specials (season 0) in between the episodes, in the order they were released.
+    pub specials_in_aired_order: bool,
+    /// Stop after playing the given number of videos in a row, i.e. a sleep timer.
+    pub play_next_count: Option<u32>,
+}
+
+impl BingeRules {
+    /// The order in which the videos are played one after another.
+    ///
+    /// The videos are kept in the order of the meta item unless
+    /// [`BingeRules::specials_in_aired_order`] is enabled, in which case every special
+    /// follows the last episode released before it.
+    pub fn binge_order<'a>(&self, videos: &'a [Video]) -> Vec<&'a Video> {
+        if !self.specials_in_aired_order {
+            return videos.iter().collect();
+        }
+
+        let is_special = |video: &Video| {
+            video
+                .series_info
+                .as_ref()
+                .map(|series_info| series_info.season == 0)
+                .unwrap_or_default()
+        };
+        let (mut specials, episodes): (Vec<_>, Vec<_>) =
+            videos.iter().partition(|video| is_special(*video));
+        // specials without a release date are played after all the episodes
+        specials.sort_by_key(|special| (special.released.is_none(), special.released));
+        let mut specials = specials.into_iter().peekable();
+        let mut order = Vec::with_capacity(videos.len());
+        for episode in episodes {
+            if let Some(released) = episode.released {
+                while let Some(special) = specials.next_if(|special| {
+                    special
+                        .released
+                        .map(|special_released| special_released < released)
+                        .unwrap_or_default()
+                }) {
+                    order.push(special);
+                }
+            }
+            order.push(episode);
+        }
+        order.extend(specials);
+        order
+    }
+}
+
+#[derive(Clone, Default, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamPreferences {
+    /// The vertical resolutions, from the most preferred, e.g. `[1080, 2160]`
+    pub preferred_resolutions: Vec<u32>,
+    /// In bytes
+    pub max_size: Option<u64>,
+    /// Hide the CAM and telesync (TS) releases.
+    pub exclude_cam: bool,
+    /// ISO 639-2/B codes of the audio languages, from the most preferred.
+    pub preferred_languages: Vec<String>,
+    /// Hide the streams of the addons which use P2P, see [`ManifestBehaviorHints::p2p`].
+    ///
+    /// [`ManifestBehaviorHints::p2p`]: crate::types::addon::ManifestBehaviorHints::p2p
+    pub hide_p2p: bool,
+    /// List the streams from URLs before the torrents.
+    pub prefer_direct_urls: bool,
+}
+
+/// A daily period, in the user's local time, in which notifications are held back.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuietHours {
+    /// Start of the period in minutes since midnight.
+    pub start: u16,
+    /// End of the period in minutes since midnight.
+    ///
+    /// Lower than `start` when the period spans midnight, e.g. 22:00 - 08:00.
+    pub end: u16,
+    /// Offset of the user's local time from UTC in minutes.
+    pub utc_offset: i32,
+}
+
+impl QuietHours {
+    /// Whether `start` and `end` are within a day and `utc_offset` is within the real-world offsets.
+    pub fn is_valid(&self) -> bool {
+        const MINUTES_PER_DAY: u16 = 24 * 60;
+        const MAX_UTC_OFFSET: i32 = 14 * 60;
+
+        self.start < MINUTES_PER_DAY
+            && self.end < MINUTES_PER_DAY
+            && (-MAX_UTC_OFFSET..=MAX_UTC_OFFSET).contains(&self.utc_offset)
+    }
+    /// Whether the given time falls into the quiet hours.
+    pub fn contains(&self, time: &DateTime<Utc>) -> bool {
+        let minutes = self.local_minutes(time);
+        let (start, end) = (i64::from(self.start), i64::from(self.end));
+        if start <= end {
+            start <= minutes && minutes < end
+        } else {
+            minutes >= start || minutes < end
+        }
+    }
+    fn local_minutes(&self, time: &DateTime<Utc>) -> i64 {
+        let local_time = *time + Duration::minutes(self.utc_offset.into());
+        i64::from(local_time.hour() * 60 + local_time.minute())
+    }
+}
+
 impl Default for Settings {
     fn default() -> Self {
         Settings {
             player_type: None,
             binge_watching: true,
+            binge_rules: BingeRules::default(),
             play_in_background: true,
             hardware_decoding: true,
             frame_rate_matching_strategy: FrameRateMatchingStrategy::FrameRateOnly,
             next_video_notification_duration: 35000,
             audio_passthrough: false,
             streaming_server_url: STREAMING_SERVER_URL.to_owned(),
+            streaming_servers: vec![],
             interface_language: "eng".to_owned(),
             audio_language: Some("eng".to_owned()),
             secondary_audio_language: None,
@@ -76,7 +202,9 @@ impl Default for Settings {
             seek_short_time_duration: 3000,
             pause_on_minimize: false,
             surround_sound: false,
+            stream_preferences: StreamPreferences::default(),
             streaming_server_warning_dismissed: None,
+            notifications_quiet_hours: None,
         }
     }
 }
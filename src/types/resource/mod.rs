@@ -4,5 +4,11 @@ pub use meta_item::*;
 mod stream;
 pub use stream::*;
 
+mod stream_metadata;
+pub use stream_metadata::*;
+
+mod stream_playback;
+pub use stream_playback::*;
+
 mod subtitles;
 pub use subtitles::*;
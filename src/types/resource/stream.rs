@@ -1,5 +1,7 @@
-use crate::constants::{BASE64, URI_COMPONENT_ENCODE_SET, YOUTUBE_ADDON_ID_PREFIX};
-use crate::types::resource::Subtitles;
+use crate::constants::{
+    BASE64, BINGE_MATCH_MIN_SCORE, URI_COMPONENT_ENCODE_SET, YOUTUBE_ADDON_ID_PREFIX,
+};
+use crate::types::resource::{PlaybackCapabilities, StreamMetadata, StreamPlayback, Subtitles};
 use base64::Engine;
 use boolinator::Boolinator;
 #[cfg(test)]
@@ -11,7 +13,8 @@ use percent_encoding::utf8_percent_encode;
 use serde::de::Error;
 use serde::{Deserialize, Deserializer, Serialize};
 use serde_with::{serde_as, DefaultOnNull};
-use std::collections::HashMap;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
 use std::io::Write;
 use stremio_serde_hex::{SerHex, Strict};
 use url::{form_urlencoded, Url};
@@ -237,6 +240,100 @@ impl Stream {
             _ => false,
         }
     }
+
+    /// Parses the quality info from the name, the description and the behavior hints.
+    pub fn metadata(&self) -> StreamMetadata {
+        StreamMetadata::parse(self)
+    }
+
+    /// Chooses how the client plays the stream, directly or through the streaming server.
+    pub fn playback(
+        &self,
+        streaming_server_url: Option<&Url>,
+        capabilities: &PlaybackCapabilities,
+    ) -> Option<StreamPlayback> {
+        StreamPlayback::new(self, streaming_server_url, capabilities)
+    }
+
+    /// Scores how likely the stream is the same release as `other_stream`,
+    /// e.g. when looking for the stream of the next video.
+    ///
+    /// Addons change the format of the [`StreamBehaviorHints::binge_group`] from time to time,
+    /// so the parsed [`StreamMetadata`] of the streams is compared as well.
+    pub fn binge_score(&self, other_stream: &Stream) -> u32 {
+        let binge_group_score = match (
+            &self.behavior_hints.binge_group,
+            &other_stream.behavior_hints.binge_group,
+        ) {
+            (Some(a), Some(b)) if a == b => 100,
+            (Some(a), Some(b)) => {
+                let (a, b) = (tokens(a), tokens(b));
+                if a == b {
+                    80
+                } else {
+                    let common = a.intersection(&b).count() as u32;
+                    let all = a.union(&b).count().max(1) as u32;
+                    40 * common / all
+                }
+            }
+            _ => 0,
+        };
+        let (metadata, other_metadata) = (self.metadata(), other_stream.metadata());
+        let resolution_score = match (metadata.resolution, other_metadata.resolution) {
+            (Some(a), Some(b)) if a == b => 30,
+            _ => 0,
+        };
+        let size_score = match (metadata.size, other_metadata.size) {
+            (Some(a), Some(b)) if a.max(b) as f64 <= a.min(b) as f64 * 1.5 => 10,
+            _ => 0,
+        };
+        let release_group_score = match (metadata.release_group, other_metadata.release_group) {
+            (Some(a), Some(b)) if a.eq_ignore_ascii_case(&b) => 20,
+            _ => 0,
+        };
+        let encoding_score = [
+            metadata.source.is_some() && metadata.source == other_metadata.source,
+            metadata.video_codec.is_some() && metadata.video_codec == other_metadata.video_codec,
+            !metadata.hdr.is_empty() && metadata.hdr == other_metadata.hdr,
+        ]
+        .into_iter()
+        .filter(|is_match| *is_match)
+        .count() as u32
+            * 5;
+        let cached_score = match (metadata.cached, other_metadata.cached) {
+            (Some(a), Some(b)) if a == b => 10,
+            _ => 0,
+        };
+        binge_group_score
+            + resolution_score
+            + size_score
+            + release_group_score
+            + encoding_score
+            + cached_score
+    }
+
+    /// The stream with the highest [`Stream::binge_score`], if it's at least [`BINGE_MATCH_MIN_SCORE`].
+    ///
+    /// The first one is picked on equal scores, i.e. the streams are expected to be in the addon order.
+    pub fn best_binge_match<'a>(
+        &self,
+        streams: impl IntoIterator<Item = &'a Stream>,
+    ) -> Option<&'a Stream> {
+        streams
+            .into_iter()
+            .map(|stream| (stream, stream.binge_score(self)))
+            .filter(|(_, score)| *score >= BINGE_MATCH_MIN_SCORE)
+            .min_by_key(|(_, score)| Reverse(*score))
+            .map(|(stream, _)| stream)
+    }
+}
+
+/// The distinct lowercase alphanumeric tokens of the given text.
+fn tokens(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(str::to_lowercase)
+        .collect()
 }
 
 #[serde_as]
@@ -0,0 +1,461 @@
+use itertools::Itertools;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::types::resource::{Stream, StreamSource};
+
+static RESOLUTION_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(?:(4320|2160|1440|1080|720|576|480|360)[pi]|(8k)|(4k|uhd)|(fhd))\b")
+        .expect("RESOLUTION_REGEX parse failed")
+});
+static SOURCE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(concat!(
+        r"(?i)\b(?:(cam|camrip|hdcam)|(ts|telesync|hdts|tc|telecine)|(scr|screener|dvdscr)",
+        r"|(dvd|dvdrip|dvd5|dvd9)|(hdtv|pdtv)|(web[ .-]?dl|webrip|web)",
+        r"|(bluray|blu-ray|bdrip|brrip)|(remux))\b"
+    ))
+    .expect("SOURCE_REGEX parse failed")
+});
+static VIDEO_CODEC_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(?:(x264|h\.?264|avc)|(x265|h\.?265|hevc)|(av1)|(vp9)|(xvid|divx))\b")
+        .expect("VIDEO_CODEC_REGEX parse failed")
+});
+static HDR_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)\b(?:(dv|dovi|dolby[ .]?vision)\b|(hdr10\+|hdr10plus\b)|(hdr10|hdr)\b|(hlg)\b)",
+    )
+    .expect("HDR_REGEX parse failed")
+});
+static AUDIO_CHANNELS_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(?:^|\D)([1-9])\.([01])(?:ch)?\b(\s*[kmgt]i?b\b)?")
+        .expect("AUDIO_CHANNELS_REGEX parse failed")
+});
+static SIZE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(\d+(?:[.,]\d+)?) ?(tb|tib|gb|gib|mb|mib)\b").expect("SIZE_REGEX parse failed")
+});
+const VIDEO_EXTENSIONS: [&str; 6] = [".mkv", ".mp4", ".avi", ".m4v", ".webm", ".ts"];
+/// Tokens which follow a dash in the filenames, but are not a release group.
+const NOT_RELEASE_GROUPS: [&str; 8] = ["dl", "rip", "ray", "x264", "x265", "hdr", "dv", "web"];
+/// The language of the country flags which addons put in the description.
+const FLAG_LANGUAGES: [(&str, &str); 28] = [
+    ("GB", "eng"),
+    ("US", "eng"),
+    ("IT", "ita"),
+    ("ES", "spa"),
+    ("MX", "spa"),
+    ("FR", "fre"),
+    ("DE", "ger"),
+    ("RU", "rus"),
+    ("PT", "por"),
+    ("BR", "por"),
+    ("JP", "jpn"),
+    ("KR", "kor"),
+    ("CN", "chi"),
+    ("TW", "chi"),
+    ("IN", "hin"),
+    ("PL", "pol"),
+    ("NL", "dut"),
+    ("TR", "tur"),
+    ("SA", "ara"),
+    ("UA", "ukr"),
+    ("SE", "swe"),
+    ("GR", "gre"),
+    ("CZ", "cze"),
+    ("HU", "hun"),
+    ("RO", "rum"),
+    ("IL", "heb"),
+    ("TH", "tha"),
+    ("VN", "vie"),
+];
+/// The words used for the languages in the release names.
+const WORD_LANGUAGES: [(&str, &str); 30] = [
+    ("eng", "eng"),
+    ("english", "eng"),
+    ("ita", "ita"),
+    ("italian", "ita"),
+    ("spa", "spa"),
+    ("esp", "spa"),
+    ("spanish", "spa"),
+    ("castellano", "spa"),
+    ("latino", "spa"),
+    ("fre", "fre"),
+    ("fra", "fre"),
+    ("french", "fre"),
+    ("vff", "fre"),
+    ("truefrench", "fre"),
+    ("ger", "ger"),
+    ("deu", "ger"),
+    ("german", "ger"),
+    ("rus", "rus"),
+    ("russian", "rus"),
+    ("por", "por"),
+    ("portuguese", "por"),
+    ("jpn", "jpn"),
+    ("japanese", "jpn"),
+    ("kor", "kor"),
+    ("korean", "kor"),
+    ("chinese", "chi"),
+    ("hindi", "hin"),
+    ("polish", "pol"),
+    ("dutch", "dut"),
+    ("turkish", "tur"),
+];
+
+/// The source of the release, from the worst to the best quality.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Debug)]
+pub enum ReleaseSource {
+    Cam,
+    Telesync,
+    Screener,
+    DVD,
+    HDTV,
+    Web,
+    BluRay,
+    Remux,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Debug)]
+pub enum VideoCodec {
+    XviD,
+    H264,
+    VP9,
+    H265,
+    AV1,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Debug)]
+pub enum HdrFormat {
+    HLG,
+    HDR10,
+    HDR10Plus,
+    DolbyVision,
+}
+
+/// The quality info of a [`Stream`], parsed from its name, description
+/// and the known [`StreamBehaviorHints::other`] keys (`filename` and `videoSize`).
+///
+/// [`StreamBehaviorHints::other`]: crate::types::resource::StreamBehaviorHints::other
+#[derive(Default, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamMetadata {
+    /// The vertical resolution, e.g. `1080`
+    pub resolution: Option<u32>,
+    pub source: Option<ReleaseSource>,
+    pub video_codec: Option<VideoCodec>,
+    pub hdr: Vec<HdrFormat>,
+    /// e.g. `5.1`
+    pub audio_channels: Option<String>,
+    /// In bytes
+    pub size: Option<u64>,
+    /// ISO 639-2/B codes of the audio languages, e.g. `eng`
+    pub languages: Vec<String>,
+    /// e.g. `NTb` in `Show.S01E01.1080p.WEB.h264-NTb.mkv`
+    pub release_group: Option<String>,
+    /// Whether the torrent is cached by a debrid service and can be played right away.
+    ///
+    /// `None` for the streams which are not torrents.
+    pub cached: Option<bool>,
+}
+
+impl StreamMetadata {
+    pub fn parse(stream: &Stream) -> Self {
+        let filename = stream
+            .behavior_hints
+            .other
+            .get("filename")
+            .and_then(|filename| filename.as_str());
+        let text = [
+            filename,
+            stream.name.as_deref(),
+            stream.description.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        .join("\n");
+        let video_size = stream
+            .behavior_hints
+            .other
+            .get("videoSize")
+            .and_then(|video_size| video_size.as_u64());
+        StreamMetadata {
+            resolution: parse_resolution(&text),
+            source: parse_source(&text),
+            video_codec: parse_video_codec(&text),
+            hdr: parse_hdr(&text),
+            audio_channels: parse_audio_channels(&text),
+            size: video_size.or_else(|| parse_size(&text)),
+            languages: parse_languages(&text),
+            release_group: parse_release_group(&text),
+            cached: parse_cached(stream),
+        }
+    }
+}
+
+/// Returns the index of the first matching group of the first match.
+fn first_group(regex: &Regex, text: &str) -> Option<usize> {
+    regex
+        .captures(text)
+        .and_then(|captures| (1..captures.len()).find(|index| captures.get(*index).is_some()))
+}
+
+fn parse_resolution(text: &str) -> Option<u32> {
+    RESOLUTION_REGEX.captures(text).and_then(|captures| {
+        match (1..=4).find(|index| captures.get(*index).is_some())? {
+            1 => captures[1].parse().ok(),
+            2 => Some(4320),
+            3 => Some(2160),
+            _ => Some(1080),
+        }
+    })
+}
+
+/// The best of the mentioned sources, as e.g. a `.ts` extension is not a telesync.
+fn parse_source(text: &str) -> Option<ReleaseSource> {
+    SOURCE_REGEX
+        .captures_iter(text)
+        .filter_map(|captures| {
+            match (1..captures.len()).find(|index| captures.get(*index).is_some())? {
+                1 => Some(ReleaseSource::Cam),
+                2 => Some(ReleaseSource::Telesync),
+                3 => Some(ReleaseSource::Screener),
+                4 => Some(ReleaseSource::DVD),
+                5 => Some(ReleaseSource::HDTV),
+                6 => Some(ReleaseSource::Web),
+                7 => Some(ReleaseSource::BluRay),
+                _ => Some(ReleaseSource::Remux),
+            }
+        })
+        .max()
+}
+
+fn parse_video_codec(text: &str) -> Option<VideoCodec> {
+    match first_group(&VIDEO_CODEC_REGEX, text)? {
+        1 => Some(VideoCodec::H264),
+        2 => Some(VideoCodec::H265),
+        3 => Some(VideoCodec::AV1),
+        4 => Some(VideoCodec::VP9),
+        _ => Some(VideoCodec::XviD),
+    }
+}
+
+fn parse_hdr(text: &str) -> Vec<HdrFormat> {
+    HDR_REGEX
+        .captures_iter(text)
+        .filter_map(|captures| {
+            match (1..captures.len()).find(|index| captures.get(*index).is_some())? {
+                1 => Some(HdrFormat::DolbyVision),
+                2 => Some(HdrFormat::HDR10Plus),
+                3 => Some(HdrFormat::HDR10),
+                _ => Some(HdrFormat::HLG),
+            }
+        })
+        .unique()
+        .collect()
+}
+
+fn parse_audio_channels(text: &str) -> Option<String> {
+    AUDIO_CHANNELS_REGEX
+        .captures_iter(text)
+        // e.g. `2.0 GB` is a size
+        .find(|captures| captures.get(3).is_none())
+        .map(|captures| format!("{}.{}", &captures[1], &captures[2]))
+}
+
+fn parse_size(text: &str) -> Option<u64> {
+    SIZE_REGEX.captures(text).and_then(|captures| {
+        let size = captures[1].replace(',', ".").parse::<f64>().ok()?;
+        let unit = match captures[2].to_lowercase().as_str() {
+            "tb" => 1e12,
+            "tib" => 1024_f64.powi(4),
+            "gb" => 1e9,
+            "gib" => 1024_f64.powi(3),
+            "mb" => 1e6,
+            _ => 1024_f64.powi(2),
+        };
+        Some((size * unit).round() as u64)
+    })
+}
+
+fn parse_languages(text: &str) -> Vec<String> {
+    let mut flags = vec![];
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if is_regional_indicator(c) {
+            let country = [Some(c), chars.next()]
+                .into_iter()
+                .flatten()
+                .filter(|c| is_regional_indicator(*c))
+                .filter_map(|c| char::from_u32(c as u32 - 0x1F1E6 + 'A' as u32))
+                .collect::<String>();
+            flags.extend(
+                FLAG_LANGUAGES
+                    .iter()
+                    .find(|(flag_country, _)| *flag_country == country)
+                    .map(|(_, language)| *language),
+            );
+        }
+    }
+    let words = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter_map(|word| {
+            let word = word.to_lowercase();
+            WORD_LANGUAGES
+                .iter()
+                .find(|(language_word, _)| *language_word == word)
+                .map(|(_, language)| *language)
+        });
+    flags
+        .into_iter()
+        .chain(words)
+        .unique()
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    ('\u{1F1E6}'..='\u{1F1FF}').contains(&c)
+}
+
+fn parse_release_group(text: &str) -> Option<String> {
+    text.split_whitespace()
+        .filter(|word| word.matches('.').count() >= 2)
+        .find_map(|word| {
+            let word = VIDEO_EXTENSIONS
+                .iter()
+                .find_map(|extension| {
+                    word.len()
+                        .checked_sub(extension.len())
+                        // the index may not be a char boundary in titles which are not in latin
+                        .and_then(|index| Some((word.get(..index)?, word.get(index..)?)))
+                        .filter(|(_, tail)| tail.eq_ignore_ascii_case(extension))
+                        .map(|(word, _)| word)
+                })
+                .unwrap_or(word);
+            word.rsplit_once('-')
+                .map(|(_, release_group)| release_group)
+                .filter(|release_group| {
+                    (2..=12).contains(&release_group.len())
+                        && release_group.chars().all(|c| c.is_ascii_alphanumeric())
+                        && !NOT_RELEASE_GROUPS.contains(&release_group.to_lowercase().as_str())
+                })
+                .map(ToOwned::to_owned)
+        })
+}
+
+fn parse_cached(stream: &Stream) -> Option<bool> {
+    let name = stream.name.as_deref().unwrap_or_default();
+    match &stream.source {
+        StreamSource::Torrent { .. } => Some(false),
+        StreamSource::Url { url } if url.scheme() == "magnet" => Some(false),
+        // debrid addons mark the cached torrents with `[RD+]`, `[AD+]`, etc.
+        StreamSource::Url { .. } if name.contains("+]") || name.contains('⚡') => Some(true),
+        StreamSource::Url { .. } if name.to_lowercase().contains("download]") => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{HdrFormat, ReleaseSource, StreamMetadata, VideoCodec};
+    use crate::types::resource::{Stream, StreamBehaviorHints, StreamSource};
+
+    #[test]
+    fn parse_stream_metadata() {
+        let stream = Stream {
+            source: StreamSource::Url {
+                url: "https://debrid/file.mkv".parse().unwrap(),
+            },
+            name: Some("[RD+] Torrentio\n4k DV | HDR".to_owned()),
+            description: Some(
+                "Show.S01E01.2160p.WEB-DL.DDP5.1.DV.HDR.H.265-NTb.mkv\n👤 20 💾 5.2 GB ⚙️ ThePirateBay\nMulti Audio / 🇬🇧 / 🇮🇹"
+                    .to_owned(),
+            ),
+            thumbnail: None,
+            subtitles: vec![],
+            behavior_hints: StreamBehaviorHints::default(),
+        };
+        assert_eq!(
+            stream.metadata(),
+            StreamMetadata {
+                resolution: Some(2160),
+                source: Some(ReleaseSource::Web),
+                video_codec: Some(VideoCodec::H265),
+                hdr: vec![HdrFormat::DolbyVision, HdrFormat::HDR10],
+                audio_channels: Some("5.1".to_owned()),
+                size: Some(5_200_000_000),
+                languages: vec!["eng".to_owned(), "ita".to_owned()],
+                release_group: Some("NTb".to_owned()),
+                cached: Some(true),
+            }
+        );
+
+        let stream = Stream {
+            source: StreamSource::Torrent {
+                info_hash: [0; 20],
+                file_idx: None,
+                announce: vec![],
+            },
+            name: Some("Movie 720p".to_owned()),
+            description: Some("Movie.2024.HDCAM.x264.ITA.2.0 GB".to_owned()),
+            thumbnail: None,
+            subtitles: vec![],
+            behavior_hints: StreamBehaviorHints {
+                other: [
+                    ("videoSize".to_owned(), serde_json::json!(2_147_483_648_u64)),
+                    (
+                        "filename".to_owned(),
+                        serde_json::json!("Movie.2024.HDCAM.x264-GRP.avi"),
+                    ),
+                ]
+                .into_iter()
+                .collect(),
+                ..Default::default()
+            },
+        };
+        assert_eq!(
+            stream.metadata(),
+            StreamMetadata {
+                resolution: Some(720),
+                source: Some(ReleaseSource::Cam),
+                video_codec: Some(VideoCodec::H264),
+                hdr: vec![],
+                audio_channels: None,
+                size: Some(2_147_483_648),
+                languages: vec!["ita".to_owned()],
+                release_group: Some("GRP".to_owned()),
+                cached: Some(false),
+            }
+        );
+
+        let stream = |description: &str| Stream {
+            source: StreamSource::Url {
+                url: "https://host/file.mkv".parse().unwrap(),
+            },
+            name: None,
+            description: Some(description.to_owned()),
+            thumbnail: None,
+            subtitles: vec![],
+            behavior_hints: StreamBehaviorHints::default(),
+        };
+        assert_eq!(
+            stream("Сериал.S01.Серия").metadata().release_group,
+            None,
+            "Non latin title without a release group"
+        );
+        assert_eq!(
+            stream("Сериал.S01E01.1080p.WEB-DL-Группа.mkv")
+                .metadata()
+                .release_group,
+            None,
+            "Non latin release group is ignored"
+        );
+        assert_eq!(
+            stream("電視劇.S01E01.1080p.WEB-DL-GRP.mkv")
+                .metadata()
+                .release_group,
+            Some("GRP".to_owned()),
+            "Release group of a non latin title"
+        );
+    }
+}
@@ -0,0 +1,245 @@
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use url::Url;
+
+use crate::types::resource::{Stream, StreamSource, VideoCodec};
+
+/// The file extensions of the containers, in lower case.
+const CONTAINER_EXTENSIONS: [(&str, VideoContainer); 7] = [
+    ("mp4", VideoContainer::Mp4),
+    ("m4v", VideoContainer::Mp4),
+    ("mov", VideoContainer::Mp4),
+    ("mkv", VideoContainer::Matroska),
+    ("webm", VideoContainer::WebM),
+    ("avi", VideoContainer::Avi),
+    ("ts", VideoContainer::MpegTs),
+];
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub enum VideoContainer {
+    Mp4,
+    Matroska,
+    WebM,
+    Avi,
+    MpegTs,
+}
+
+impl VideoContainer {
+    /// The container of a file by its extension, e.g. `Movie.2024.1080p.mkv`
+    pub fn from_filename(filename: &str) -> Option<Self> {
+        let (_, extension) = filename.rsplit_once('.')?;
+        CONTAINER_EXTENSIONS
+            .iter()
+            .find(|(container_extension, _)| extension.eq_ignore_ascii_case(container_extension))
+            .map(|(_, container)| *container)
+    }
+}
+
+/// What the client is able to play natively, supplied by the [`Env`].
+///
+/// [`Env`]: crate::runtime::Env
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaybackCapabilities {
+    pub containers: Vec<VideoContainer>,
+    pub video_codecs: Vec<VideoCodec>,
+    /// The highest vertical resolution, e.g. `1080`
+    pub max_resolution: Option<u32>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub enum PlaybackMethod {
+    /// The stream is played as it is.
+    DirectPlay,
+    /// The video is copied into a HLS stream by the streaming server.
+    Remux,
+    /// The video is encoded again into a HLS stream by the streaming server.
+    Transcode,
+}
+
+/// How the client should play a [`Stream`] and from which url.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamPlayback {
+    pub method: PlaybackMethod,
+    pub url: String,
+}
+
+impl StreamPlayback {
+    /// Chooses the [`PlaybackMethod`] of the stream from its container, codec and resolution.
+    ///
+    /// The container and the codec which can't be recognized are assumed to be playable.
+    /// Without a streaming server the stream can only be played directly.
+    pub fn new(
+        stream: &Stream,
+        streaming_server_url: Option<&Url>,
+        capabilities: &PlaybackCapabilities,
+    ) -> Option<Self> {
+        let streaming_url = stream.streaming_url(streaming_server_url)?;
+        let metadata = stream.metadata();
+        let container_supported = container(stream)
+            .map(|container| capabilities.containers.contains(&container))
+            .unwrap_or(true);
+        let video_codec_supported = metadata
+            .video_codec
+            .map(|video_codec| capabilities.video_codecs.contains(&video_codec))
+            .unwrap_or(true);
+        let resolution_supported = match (metadata.resolution, capabilities.max_resolution) {
+            (Some(resolution), Some(max_resolution)) => resolution <= max_resolution,
+            _ => true,
+        };
+        let method = match (
+            streaming_server_url,
+            container_supported,
+            video_codec_supported && resolution_supported,
+        ) {
+            (None, _, _) | (_, true, true) => PlaybackMethod::DirectPlay,
+            (_, false, true) => PlaybackMethod::Remux,
+            _ => PlaybackMethod::Transcode,
+        };
+        let url = match (method, streaming_server_url) {
+            (PlaybackMethod::DirectPlay, _) | (_, None) => streaming_url,
+            (method, Some(streaming_server_url)) => {
+                hls_url(streaming_server_url, &streaming_url, method, capabilities)?
+            }
+        };
+        Some(StreamPlayback { method, url })
+    }
+}
+
+/// The container from the `filename` behavior hint or the path of the url.
+fn container(stream: &Stream) -> Option<VideoContainer> {
+    let filename = stream
+        .behavior_hints
+        .other
+        .get("filename")
+        .and_then(|filename| filename.as_str());
+    match (filename, &stream.source) {
+        (Some(filename), _) => VideoContainer::from_filename(filename),
+        (None, StreamSource::Url { url }) => url
+            .path_segments()
+            .and_then(|mut path| path.next_back())
+            .and_then(VideoContainer::from_filename),
+        _ => None,
+    }
+}
+
+/// The name of the codec used by the streaming server.
+fn video_codec_name(video_codec: &VideoCodec) -> &'static str {
+    match video_codec {
+        VideoCodec::XviD => "mpeg4",
+        VideoCodec::H264 => "h264",
+        VideoCodec::VP9 => "vp9",
+        VideoCodec::H265 => "hevc",
+        VideoCodec::AV1 => "av1",
+    }
+}
+
+/// The HLS playlist of the streaming url, the streaming server copies the video
+/// when its codec is one of the `videoCodecs` unless the transcoding is forced.
+fn hls_url(
+    streaming_server_url: &Url,
+    streaming_url: &str,
+    method: PlaybackMethod,
+    capabilities: &PlaybackCapabilities,
+) -> Option<String> {
+    let mut hasher = Sha1::new();
+    hasher.update(streaming_url.as_bytes());
+    let id = hex::encode(hasher.finalize());
+    let mut url = streaming_server_url.to_owned();
+    url.path_segments_mut()
+        .ok()?
+        .pop_if_empty()
+        .extend(["hlsv2", &id, "master.m3u8"]);
+    {
+        let mut query = url.query_pairs_mut();
+        query.append_pair("mediaURL", streaming_url);
+        query.extend_pairs(
+            capabilities
+                .video_codecs
+                .iter()
+                .map(|video_codec| ("videoCodecs", video_codec_name(video_codec))),
+        );
+        if let Some(max_resolution) = capabilities.max_resolution {
+            query.append_pair("maxHeight", &max_resolution.to_string());
+        };
+        if method == PlaybackMethod::Transcode {
+            query.append_pair("forceTranscoding", "1");
+        };
+    }
+    Some(url.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{PlaybackCapabilities, PlaybackMethod, StreamPlayback, VideoContainer};
+    use crate::types::resource::{Stream, StreamBehaviorHints, StreamSource, VideoCodec};
+    use url::Url;
+
+    #[test]
+    fn stream_playback_method() {
+        let streaming_server_url = Url::parse("http://127.0.0.1:11470/").unwrap();
+        let capabilities = PlaybackCapabilities {
+            containers: vec![VideoContainer::Mp4, VideoContainer::WebM],
+            video_codecs: vec![VideoCodec::H264, VideoCodec::VP9],
+            max_resolution: Some(1080),
+        };
+        let stream = |url: &str, description: &str| Stream {
+            source: StreamSource::Url {
+                url: url.parse().unwrap(),
+            },
+            name: None,
+            description: Some(description.to_owned()),
+            thumbnail: None,
+            subtitles: vec![],
+            behavior_hints: StreamBehaviorHints::default(),
+        };
+
+        assert_eq!(
+            StreamPlayback::new(
+                &stream("https://host/movie.mp4", "Movie 1080p x264"),
+                Some(&streaming_server_url),
+                &capabilities
+            ),
+            Some(StreamPlayback {
+                method: PlaybackMethod::DirectPlay,
+                url: "https://host/movie.mp4".to_owned(),
+            }),
+            "Supported container and codec are played directly"
+        );
+        assert_eq!(
+            StreamPlayback::new(
+                &stream("https://host/movie.mkv", "Movie 1080p x264"),
+                Some(&streaming_server_url),
+                &capabilities
+            ),
+            Some(StreamPlayback {
+                method: PlaybackMethod::Remux,
+                url: "http://127.0.0.1:11470/hlsv2/d5885bed34d599135b7f9ebc35182cc785702c37/master.m3u8?mediaURL=https%3A%2F%2Fhost%2Fmovie.mkv&videoCodecs=h264&videoCodecs=vp9&maxHeight=1080".to_owned(),
+            }),
+            "Unsupported container is remuxed"
+        );
+        assert_eq!(
+            StreamPlayback::new(
+                &stream("https://host/movie.mp4", "Movie 2160p HEVC"),
+                Some(&streaming_server_url),
+                &capabilities
+            )
+            .map(|playback| playback.method),
+            Some(PlaybackMethod::Transcode),
+            "Unsupported codec is transcoded"
+        );
+        assert_eq!(
+            StreamPlayback::new(
+                &stream("https://host/movie.mkv", "Movie 2160p HEVC"),
+                None,
+                &capabilities
+            ),
+            Some(StreamPlayback {
+                method: PlaybackMethod::DirectPlay,
+                url: "https://host/movie.mkv".to_owned(),
+            }),
+            "Without a streaming server the stream is played directly"
+        );
+    }
+}
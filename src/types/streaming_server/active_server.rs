@@ -0,0 +1,39 @@
+use serde::Serialize;
+use url::Url;
+
+use crate::types::streaming_server::{ServerCompatibility, ServerFeature};
+
+/// The streaming server in use, shared with all the models through the [`Ctx`].
+///
+/// [`Ctx`]: crate::models::ctx::Ctx
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveServer {
+    /// The [`Settings::streaming_server_url`] unless it was unreachable
+    /// and another known server is used instead.
+    ///
+    /// [`Settings::streaming_server_url`]: crate::types::profile::Settings::streaming_server_url
+    pub url: Url,
+    /// `None` until the server responds with its settings, or when it's unreachable.
+    pub compatibility: Option<ServerCompatibility>,
+}
+
+impl ActiveServer {
+    pub fn new(url: Url) -> Self {
+        ActiveServer {
+            url,
+            compatibility: None,
+        }
+    }
+    /// The url of the server once it responded, `None` while it's loading or when it's unreachable.
+    pub fn ready_url(&self) -> Option<&Url> {
+        self.compatibility.as_ref().map(|_| &self.url)
+    }
+    /// The features are assumed to be supported until the version of the server is known.
+    pub fn supports(&self, feature: ServerFeature) -> bool {
+        self.compatibility
+            .as_ref()
+            .map(|compatibility| compatibility.supports(feature))
+            .unwrap_or(true)
+    }
+}
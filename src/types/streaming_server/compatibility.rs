@@ -0,0 +1,98 @@
+use semver::Version;
+use serde::{Deserialize, Serialize};
+
+/// The oldest streaming server version supporting each of the features.
+///
+/// There's no public changelog of the server endpoints to take the versions from,
+/// so only the servers older than 4.0.0 are asked to update. A feature should get
+/// a later version only together with a reference to the server release which added it,
+/// otherwise the servers supporting it would be gated.
+const FEATURE_VERSIONS: [(ServerFeature, Version); 4] = [
+    (ServerFeature::Statistics, Version::new(4, 0, 0)),
+    (ServerFeature::Casting, Version::new(4, 0, 0)),
+    (ServerFeature::RemoteHttps, Version::new(4, 0, 0)),
+    (ServerFeature::Transcoding, Version::new(4, 0, 0)),
+];
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub enum ServerFeature {
+    /// The statistics of the torrent streams.
+    Statistics,
+    /// Playing on the devices found by the server.
+    Casting,
+    /// The HTTPS endpoint for the remote access.
+    RemoteHttps,
+    /// The HLS remuxing and transcoding of the streams.
+    Transcoding,
+}
+
+/// The features the streaming server is too old for, by its version.
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerCompatibility {
+    /// `None` when the version can't be parsed, all the features are assumed to be supported then.
+    pub version: Option<Version>,
+    /// The features which require an update of the server, empty when it's up to date.
+    pub unsupported_features: Vec<ServerFeature>,
+}
+
+impl ServerCompatibility {
+    /// The compatibility of the [`Settings::server_version`], e.g. `4.20.8`
+    ///
+    /// [`Settings::server_version`]: crate::types::streaming_server::Settings::server_version
+    pub fn new(server_version: &str) -> Self {
+        let version = Version::parse(server_version.trim_start_matches('v')).ok();
+        let unsupported_features = match &version {
+            Some(version) => FEATURE_VERSIONS
+                .iter()
+                .filter(|(_, min_version)| version < min_version)
+                .map(|(feature, _)| *feature)
+                .collect(),
+            None => vec![],
+        };
+        ServerCompatibility {
+            version,
+            unsupported_features,
+        }
+    }
+    pub fn supports(&self, feature: ServerFeature) -> bool {
+        !self.unsupported_features.contains(&feature)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ServerCompatibility, ServerFeature};
+
+    #[test]
+    fn server_compatibility() {
+        assert_eq!(
+            ServerCompatibility::new("4.20.8").unsupported_features,
+            vec![],
+            "Up to date server supports all the features"
+        );
+        assert_eq!(
+            ServerCompatibility::new("3.13.0").unsupported_features,
+            vec![
+                ServerFeature::Statistics,
+                ServerFeature::Casting,
+                ServerFeature::RemoteHttps,
+                ServerFeature::Transcoding,
+            ],
+            "Old server does not support the features"
+        );
+        assert_eq!(
+            ServerCompatibility::new("v4.4.0").unsupported_features,
+            vec![],
+            "Version prefix is ignored"
+        );
+        assert_eq!(
+            ServerCompatibility::new(""),
+            ServerCompatibility {
+                version: None,
+                unsupported_features: vec![],
+            },
+            "Unknown version is assumed to support all the features"
+        );
+    }
+}
@@ -1,6 +1,15 @@
+mod active_server;
+pub use active_server::*;
+
+mod compatibility;
+pub use compatibility::*;
+
 mod network_info;
 pub use network_info::*;
 
+mod remote_access;
+pub use remote_access::*;
+
 mod response;
 pub use response::*;
 
@@ -0,0 +1,56 @@
+use serde::Serialize;
+use url::Url;
+
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
+#[serde(tag = "type", content = "content")]
+pub enum RemoteAccessError {
+    /// The certificate can be requested only with the auth key of the user.
+    NotLoggedIn,
+    /// The streaming server is too old for the remote access.
+    UnsupportedServer,
+    /// The interface is not one of the [`NetworkInfo::available_interfaces`].
+    ///
+    /// [`NetworkInfo::available_interfaces`]: crate::types::streaming_server::NetworkInfo::available_interfaces
+    UnavailableInterface(String),
+    /// The streaming server failed to request the certificate for the interface.
+    Certificate(String),
+    /// The remote url did not respond to the round-trip probe.
+    Unreachable(String),
+    /// The interface could not be saved in the settings of the streaming server.
+    Save(String),
+}
+
+/// The steps of the remote access setup, in the order they are made.
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
+#[serde(tag = "type", content = "content")]
+pub enum RemoteAccessStep {
+    RequestingCertificate,
+    /// Fetching the settings of the streaming server through the remote url.
+    Probing(Url),
+    /// Saving the interface as the `remote_https` setting of the streaming server,
+    /// the previous `remote_https` is restored if the server fails to save it.
+    #[serde(rename_all = "camelCase")]
+    Saving {
+        remote_url: Url,
+        previous_remote_https: Option<String>,
+    },
+    Ready(Url),
+    Failed(RemoteAccessError),
+}
+
+/// The HTTPS access to the streaming server from other devices, set up on an interface of the server.
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteAccess {
+    pub interface: String,
+    pub step: RemoteAccessStep,
+}
+
+impl RemoteAccess {
+    pub fn new(interface: String) -> Self {
+        RemoteAccess {
+            interface,
+            step: RemoteAccessStep::RequestingCertificate,
+        }
+    }
+}
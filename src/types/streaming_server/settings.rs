@@ -1,5 +1,14 @@
 use serde::{Deserialize, Serialize};
 
+/// In bytes, `None` means an unlimited cache.
+const CACHE_SIZE_RANGE: (f64, f64) = (0.0, 1e13);
+const BT_MAX_CONNECTIONS_RANGE: (f64, f64) = (1.0, 1000.0);
+/// In milliseconds
+const BT_TIMEOUT_RANGE: (f64, f64) = (1000.0, 120_000.0);
+/// In bytes per second
+const BT_DOWNLOAD_SPEED_LIMIT_RANGE: (f64, f64) = (65_536.0, 1e9);
+const BT_MIN_PEERS_FOR_STABLE_RANGE: (f64, f64) = (1.0, 100.0);
+
 #[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Settings {
@@ -15,3 +24,132 @@ pub struct Settings {
     pub bt_download_speed_hard_limit: f64,
     pub bt_min_peers_for_stable: u64,
 }
+
+impl Settings {
+    /// Checks the values which differ from the `current` settings before they are sent
+    /// to the streaming server, as it doesn't validate them itself.
+    ///
+    /// The unchanged values are not checked, so that an out-of-range value
+    /// reported by the server doesn't block the updates of the other settings.
+    pub fn validate(&self, current: Option<&Settings>) -> Result<(), Vec<SettingsError>> {
+        let current_values = current.map(Settings::ranged_values);
+        let limits_changed = current
+            .map(|current| {
+                current.bt_download_speed_soft_limit != self.bt_download_speed_soft_limit
+                    || current.bt_download_speed_hard_limit != self.bt_download_speed_hard_limit
+            })
+            .unwrap_or(true);
+        let errors = self
+            .ranged_values()
+            .into_iter()
+            .enumerate()
+            .filter(|(index, (_, value, _))| {
+                current_values
+                    .map(|current_values| current_values[*index].1 != *value)
+                    .unwrap_or(true)
+            })
+            .filter_map(|(_, (setting, value, (min, max)))| match value {
+                Some(value) if !(min..=max).contains(&value) => Some(SettingsError::OutOfRange {
+                    setting: setting.to_owned(),
+                    min,
+                    max,
+                }),
+                _ => None,
+            })
+            .chain(
+                (limits_changed
+                    && self.bt_download_speed_soft_limit > self.bt_download_speed_hard_limit)
+                    .then_some(SettingsError::SoftLimitAboveHardLimit),
+            )
+            .collect::<Vec<_>>();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+    /// The settings with an allowed range of values and their range.
+    fn ranged_values(&self) -> [(&'static str, Option<f64>, (f64, f64)); 7] {
+        [
+            ("cacheSize", self.cache_size, CACHE_SIZE_RANGE),
+            (
+                "btMaxConnections",
+                Some(self.bt_max_connections as f64),
+                BT_MAX_CONNECTIONS_RANGE,
+            ),
+            (
+                "btHandshakeTimeout",
+                Some(self.bt_handshake_timeout as f64),
+                BT_TIMEOUT_RANGE,
+            ),
+            (
+                "btRequestTimeout",
+                Some(self.bt_request_timeout as f64),
+                BT_TIMEOUT_RANGE,
+            ),
+            (
+                "btDownloadSpeedSoftLimit",
+                Some(self.bt_download_speed_soft_limit),
+                BT_DOWNLOAD_SPEED_LIMIT_RANGE,
+            ),
+            (
+                "btDownloadSpeedHardLimit",
+                Some(self.bt_download_speed_hard_limit),
+                BT_DOWNLOAD_SPEED_LIMIT_RANGE,
+            ),
+            (
+                "btMinPeersForStable",
+                Some(self.bt_min_peers_for_stable as f64),
+                BT_MIN_PEERS_FOR_STABLE_RANGE,
+            ),
+        ]
+    }
+}
+
+#[derive(Clone, PartialEq, Serialize, Debug)]
+#[serde(tag = "type", content = "content")]
+pub enum SettingsError {
+    /// The value of the setting is not within the allowed `min..=max` range.
+    OutOfRange { setting: String, min: f64, max: f64 },
+    /// The soft download speed limit is greater than the hard one.
+    SoftLimitAboveHardLimit,
+}
+
+/// Named sets of values for the torrent settings.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub enum SettingsPreset {
+    /// Fewer connections and lower speed limits, for slow or metered connections.
+    LowBandwidth,
+    /// The values the streaming server starts with.
+    Default,
+    /// Many connections and high speed limits, for servers with a fast uplink.
+    Seedbox,
+}
+
+impl SettingsPreset {
+    /// Returns the `settings` with the torrent values of the preset,
+    /// the other settings (like the cache) are kept.
+    pub fn apply(self, settings: &Settings) -> Settings {
+        let (
+            bt_max_connections,
+            bt_handshake_timeout,
+            bt_request_timeout,
+            bt_download_speed_soft_limit,
+            bt_download_speed_hard_limit,
+            bt_min_peers_for_stable,
+        ) = match self {
+            SettingsPreset::LowBandwidth => (35, 20_000, 4_000, 1_677_721.6, 2_621_440.0, 5),
+            SettingsPreset::Default => (55, 20_000, 4_000, 2_621_440.0, 3_670_016.0, 5),
+            SettingsPreset::Seedbox => (200, 25_000, 6_000, 4_194_304.0, 39_321_600.0, 10),
+        };
+        Settings {
+            bt_max_connections,
+            bt_handshake_timeout,
+            bt_request_timeout,
+            bt_download_speed_soft_limit,
+            bt_download_speed_hard_limit,
+            bt_min_peers_for_stable,
+            ..settings.to_owned()
+        }
+    }
+}
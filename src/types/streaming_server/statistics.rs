@@ -1,6 +1,24 @@
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use url::Url;
 
+use crate::types::resource::{SeriesInfo, VideoContainer};
+
+/// The number of the latest samples kept in the [`StatisticsHistory`].
+const HISTORY_SIZE: usize = 12;
+/// The minimum number of samples before the throughput is estimated.
+const HISTORY_MIN_SAMPLES: usize = 3;
+/// The throughput to playback rate ratio below which the playback is at risk of buffering.
+const AT_RISK_RATIO: f64 = 1.5;
+static EPISODE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\bs(\d{1,2})[ ._-]?e(\d{1,3})|\b(\d{1,2})x(\d{1,3})\b")
+        .expect("EPISODE_REGEX parse failed")
+});
+static SAMPLE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)\b(sample|trailer)\b").expect("SAMPLE_REGEX parse failed"));
+
 #[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct File {
@@ -10,6 +28,43 @@ pub struct File {
     pub offset: u64,
 }
 
+impl File {
+    /// Whether the file is a video, the samples and trailers excluded.
+    pub fn is_video(&self) -> bool {
+        VideoContainer::from_filename(&self.name).is_some() && !SAMPLE_REGEX.is_match(&self.name)
+    }
+    /// The season and the episode in the filename, e.g. `S01E02` or `1x02`.
+    pub fn series_info(&self) -> Option<SeriesInfo> {
+        let captures = EPISODE_REGEX.captures(&self.name)?;
+        let (season, episode) = match (captures.get(1), captures.get(2)) {
+            (Some(season), Some(episode)) => (season, episode),
+            _ => (captures.get(3)?, captures.get(4)?),
+        };
+        Some(SeriesInfo {
+            season: season.as_str().parse().ok()?,
+            episode: episode.as_str().parse().ok()?,
+        })
+    }
+}
+
+/// The index of the file to play from the files of a torrent.
+///
+/// With a `series_info` only the video of the episode is matched,
+/// otherwise the largest video is played.
+pub fn find_video_file(files: &[File], series_info: Option<&SeriesInfo>) -> Option<usize> {
+    files
+        .iter()
+        .enumerate()
+        .filter(|(_, file)| file.is_video())
+        .filter(|(_, file)| {
+            series_info.map_or(true, |series_info| {
+                file.series_info().as_ref() == Some(series_info)
+            })
+        })
+        .max_by_key(|(_, file)| file.length)
+        .map(|(file_idx, _)| file_idx)
+}
+
 #[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Growler {
@@ -82,3 +137,111 @@ pub struct Statistics {
     pub swarm_paused: bool,
     pub swarm_size: u64,
 }
+
+/// A sample of the [`Statistics`] of a torrent stream.
+#[derive(Clone, PartialEq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StatisticsSample {
+    pub time: DateTime<Utc>,
+    /// In bytes per second
+    pub download_speed: f64,
+    pub peers: u64,
+    pub stream_progress: f64,
+    pub stream_len: u64,
+}
+
+/// Whether the download of a torrent stream keeps up with its playback.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Debug)]
+pub enum BufferingVerdict {
+    /// The rest of the stream will be downloaded before the playback reaches it.
+    Smooth,
+    /// The download is only slightly faster than the playback.
+    AtRisk,
+    /// The playback will catch up with the download.
+    WillBuffer,
+}
+
+/// Rolling window of the [`Statistics`] of the playing torrent stream.
+#[derive(Default, Clone, PartialEq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StatisticsHistory {
+    pub samples: Vec<StatisticsSample>,
+    /// `None` until there are enough samples and the playback time is known.
+    pub buffering: Option<BufferingVerdict>,
+    /// The playback time and duration of the stream, in milliseconds.
+    #[serde(skip_serializing)]
+    pub playback: Option<(u64, u64)>,
+}
+
+impl StatisticsHistory {
+    /// Adds a sample, `None` statistics mean that the stream has been fully downloaded.
+    ///
+    /// Returns `true` if the history has changed.
+    pub fn push(&mut self, time: DateTime<Utc>, statistics: Option<&Statistics>) -> bool {
+        let sample = match (statistics, self.samples.last()) {
+            (Some(statistics), _) => StatisticsSample {
+                time,
+                download_speed: statistics.download_speed,
+                peers: statistics.peers,
+                stream_progress: statistics.stream_progress,
+                stream_len: statistics.stream_len,
+            },
+            (None, Some(last_sample)) if last_sample.stream_progress >= 1.0 => return false,
+            (None, last_sample) => StatisticsSample {
+                time,
+                download_speed: 0.0,
+                peers: 0,
+                stream_progress: 1.0,
+                stream_len: last_sample.map_or(0, |sample| sample.stream_len),
+            },
+        };
+        self.samples.push(sample);
+        if self.samples.len() > HISTORY_SIZE {
+            self.samples.remove(0);
+        };
+        self.buffering = self.buffering_verdict();
+        true
+    }
+    /// Returns `true` if the buffering verdict has changed.
+    pub fn set_playback(&mut self, time: u64, duration: u64) -> bool {
+        self.playback = Some((time, duration));
+        let buffering = self.buffering_verdict();
+        if self.buffering != buffering {
+            self.buffering = buffering;
+            true
+        } else {
+            false
+        }
+    }
+    /// Compares the time needed for downloading the rest of the stream at the average throughput
+    /// of the window to the remaining playback time.
+    fn buffering_verdict(&self) -> Option<BufferingVerdict> {
+        let last_sample = self.samples.last()?;
+        if last_sample.stream_progress >= 1.0 {
+            return Some(BufferingVerdict::Smooth);
+        };
+        let (time, duration) = self.playback.filter(|(_, duration)| *duration > 0)?;
+        if self.samples.len() < HISTORY_MIN_SAMPLES {
+            return None;
+        };
+        let throughput = self
+            .samples
+            .iter()
+            .map(|sample| sample.download_speed)
+            .sum::<f64>()
+            / self.samples.len() as f64;
+        let remaining_bytes = last_sample.stream_len as f64 * (1.0 - last_sample.stream_progress);
+        let remaining_playback_time = duration.saturating_sub(time) as f64 / 1000.0;
+        if throughput <= 0.0 {
+            return Some(BufferingVerdict::WillBuffer);
+        };
+        let ratio = remaining_playback_time / (remaining_bytes / throughput);
+        if ratio >= AT_RISK_RATIO {
+            Some(BufferingVerdict::Smooth)
+        } else if ratio >= 1.0 {
+            Some(BufferingVerdict::AtRisk)
+        } else {
+            Some(BufferingVerdict::WillBuffer)
+        }
+    }
+}
@@ -0,0 +1,8 @@
+mod subtitles_encoding;
+pub use subtitles_encoding::*;
+
+mod subtitles_file;
+pub use subtitles_file::*;
+
+mod subtitles_sync;
+pub use subtitles_sync::*;
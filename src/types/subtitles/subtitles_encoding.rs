@@ -0,0 +1,105 @@
+use serde::{Deserialize, Serialize};
+
+/// The text encodings detected when decoding a subtitles file.
+///
+/// Files without a BOM which are not a valid UTF-8 are decoded with one of the
+/// single-byte Windows code pages which are still common for SRT files.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub enum SubtitlesEncoding {
+    UTF8,
+    UTF16LE,
+    UTF16BE,
+    /// Cyrillic
+    Windows1251,
+    /// Western European
+    Windows1252,
+}
+
+/// Windows-1251 characters in the `0x80..=0xBF` range,
+/// the range `0xC0..=0xFF` maps to `U+0410..=U+044F`.
+const WINDOWS_1251: [char; 64] = [
+    'Ђ', 'Ѓ', '‚', 'ѓ', '„', '…', '†', '‡', '€', '‰', 'Љ', '‹', 'Њ', 'Ќ', 'Ћ', 'Џ', //
+    'ђ', '‘', '’', '“', '”', '•', '–', '—', '\u{98}', '™', 'љ', '›', 'њ', 'ќ', 'ћ', 'џ', //
+    '\u{a0}', 'Ў', 'ў', 'Ј', '¤', 'Ґ', '¦', '§', 'Ё', '©', 'Є', '«', '¬', '\u{ad}', '®',
+    'Ї', //
+    '°', '±', 'І', 'і', 'ґ', 'µ', '¶', '·', 'ё', '№', 'є', '»', 'ј', 'Ѕ', 'ѕ', 'ї',
+];
+
+/// Windows-1252 characters in the `0x80..=0x9F` range,
+/// the rest of the code page matches ISO-8859-1.
+const WINDOWS_1252: [char; 32] = [
+    '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8d}', 'Ž',
+    '\u{8f}', //
+    '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9d}', 'ž', 'Ÿ',
+];
+
+impl SubtitlesEncoding {
+    /// Detects the encoding of the given bytes by the BOM or by the content.
+    pub fn detect(bytes: &[u8]) -> Self {
+        match bytes {
+            [0xEF, 0xBB, 0xBF, ..] => SubtitlesEncoding::UTF8,
+            [0xFF, 0xFE, ..] => SubtitlesEncoding::UTF16LE,
+            [0xFE, 0xFF, ..] => SubtitlesEncoding::UTF16BE,
+            _ if std::str::from_utf8(bytes).is_ok() => SubtitlesEncoding::UTF8,
+            _ => {
+                // Cyrillic words are written entirely with non-ASCII characters,
+                // while the accented latin letters are usually surrounded by ASCII ones
+                let non_ascii = bytes.iter().filter(|byte| !byte.is_ascii()).count();
+                let adjacent_non_ascii = bytes
+                    .windows(2)
+                    .filter(|pair| !pair[0].is_ascii() && !pair[1].is_ascii())
+                    .count();
+                if adjacent_non_ascii * 2 > non_ascii {
+                    SubtitlesEncoding::Windows1251
+                } else {
+                    SubtitlesEncoding::Windows1252
+                }
+            }
+        }
+    }
+    /// Decodes the given bytes, skipping the BOM if any.
+    ///
+    /// Invalid sequences are replaced with [`char::REPLACEMENT_CHARACTER`].
+    pub fn decode(&self, bytes: &[u8]) -> String {
+        match self {
+            SubtitlesEncoding::UTF8 => {
+                String::from_utf8_lossy(bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(bytes))
+                    .into_owned()
+            }
+            SubtitlesEncoding::UTF16LE => decode_utf16(
+                bytes.strip_prefix(&[0xFF, 0xFE]).unwrap_or(bytes),
+                u16::from_le_bytes,
+            ),
+            SubtitlesEncoding::UTF16BE => decode_utf16(
+                bytes.strip_prefix(&[0xFE, 0xFF]).unwrap_or(bytes),
+                u16::from_be_bytes,
+            ),
+            SubtitlesEncoding::Windows1251 => bytes
+                .iter()
+                .map(|byte| match byte {
+                    0x00..=0x7F => char::from(*byte),
+                    0x80..=0xBF => WINDOWS_1251[usize::from(byte - 0x80)],
+                    0xC0..=0xFF => char::from_u32(0x0410 + u32::from(byte - 0xC0))
+                        .unwrap_or(char::REPLACEMENT_CHARACTER),
+                })
+                .collect(),
+            SubtitlesEncoding::Windows1252 => bytes
+                .iter()
+                .map(|byte| match byte {
+                    0x80..=0x9F => WINDOWS_1252[usize::from(byte - 0x80)],
+                    _ => char::from(*byte),
+                })
+                .collect(),
+        }
+    }
+}
+
+fn decode_utf16(bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> String {
+    char::decode_utf16(
+        bytes
+            .chunks_exact(2)
+            .map(|pair| from_bytes([pair[0], pair[1]])),
+    )
+    .map(|result| result.unwrap_or(char::REPLACEMENT_CHARACTER))
+    .collect()
+}
@@ -0,0 +1,421 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::subtitles::SubtitlesEncoding;
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub enum SubtitlesFormat {
+    SRT,
+    WebVTT,
+    /// Advanced SubStation Alpha, SubStation Alpha files are parsed the same way.
+    ASS,
+}
+
+impl SubtitlesFormat {
+    /// Detects the format of a decoded subtitles file by its content.
+    ///
+    /// Anything which is neither WebVTT nor ASS/SSA is considered SRT.
+    pub fn detect(content: &str) -> Self {
+        let content = content.trim_start_matches('\u{feff}').trim_start();
+        if content.starts_with("WEBVTT") {
+            SubtitlesFormat::WebVTT
+        } else if content.starts_with("[Script Info]") || content.contains("[Events]") {
+            SubtitlesFormat::ASS
+        } else {
+            SubtitlesFormat::SRT
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
+#[serde(tag = "type", content = "content")]
+pub enum SubtitlesError {
+    /// The subtitles file could not be fetched.
+    Fetch(String),
+    /// No cues could be parsed from the subtitles file.
+    Empty,
+}
+
+/// A single subtitle shown between `start` and `end`.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SubtitlesCue {
+    /// In milliseconds
+    pub start: u64,
+    /// In milliseconds
+    pub end: u64,
+    /// The lines of the cue separated by `\n`.
+    ///
+    /// Only the `<i>`, `<b>` and `<u>` tags are kept as they are supported by all the formats.
+    pub text: String,
+}
+
+/// A parsed subtitles file, independent of the format it was parsed from.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SubtitlesFile {
+    pub format: SubtitlesFormat,
+    pub encoding: SubtitlesEncoding,
+    /// Ordered by their start time
+    pub cues: Vec<SubtitlesCue>,
+}
+
+impl SubtitlesFile {
+    /// Decodes and parses a subtitles file in any of the supported formats.
+    ///
+    /// Malformed cues are skipped, the file is rejected only if none of them could be parsed.
+    pub fn parse(bytes: &[u8]) -> Result<Self, SubtitlesError> {
+        let encoding = SubtitlesEncoding::detect(bytes);
+        let content = encoding
+            .decode(bytes)
+            .replace("\r\n", "\n")
+            .replace('\r', "\n");
+        let format = SubtitlesFormat::detect(&content);
+        let mut cues = match format {
+            SubtitlesFormat::SRT | SubtitlesFormat::WebVTT => parse_timed_blocks(&content),
+            SubtitlesFormat::ASS => parse_ass(&content),
+        };
+        if cues.is_empty() {
+            return Err(SubtitlesError::Empty);
+        }
+        cues.sort_by_key(|cue| cue.start);
+        Ok(SubtitlesFile {
+            format,
+            encoding,
+            cues,
+        })
+    }
+    /// Shifts all the cues by the given delay in milliseconds,
+    /// i.e. the [`StreamItemState::subtitle_delay`].
+    ///
+    /// Cues which would end before the start of the video are removed.
+    ///
+    /// [`StreamItemState::subtitle_delay`]: crate::types::streams::StreamItemState::subtitle_delay
+    pub fn delayed(self, delay: i64) -> Self {
+        let shift = |time: u64| (time as i64).saturating_add(delay).max(0) as u64;
+        SubtitlesFile {
+            cues: self
+                .cues
+                .into_iter()
+                .map(|cue| SubtitlesCue {
+                    start: shift(cue.start),
+                    end: shift(cue.end),
+                    ..cue
+                })
+                .filter(|cue| cue.end > 0)
+                .collect(),
+            ..self
+        }
+    }
+    /// Serializes the cues in the given format.
+    pub fn to_format(&self, format: SubtitlesFormat) -> String {
+        match format {
+            SubtitlesFormat::SRT => self
+                .cues
+                .iter()
+                .enumerate()
+                .map(|(index, cue)| {
+                    format!(
+                        "{}\n{} --> {}\n{}\n\n",
+                        index + 1,
+                        format_timestamp(cue.start, ','),
+                        format_timestamp(cue.end, ','),
+                        cue.text
+                    )
+                })
+                .collect(),
+            SubtitlesFormat::WebVTT => {
+                self.cues.iter().fold("WEBVTT\n\n".to_owned(), |vtt, cue| {
+                    // an empty line would end the cue
+                    let text = cue
+                        .text
+                        .lines()
+                        .filter(|line| !line.trim().is_empty())
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    vtt + &format!(
+                        "{} --> {}\n{}\n\n",
+                        format_timestamp(cue.start, '.'),
+                        format_timestamp(cue.end, '.'),
+                        text
+                    )
+                })
+            }
+            SubtitlesFormat::ASS => self.cues.iter().fold(ASS_HEADER.to_owned(), |ass, cue| {
+                let text = cue
+                    .text
+                    .replace('\n', "\\N")
+                    .replace("<i>", "{\\i1}")
+                    .replace("</i>", "{\\i0}")
+                    .replace("<b>", "{\\b1}")
+                    .replace("</b>", "{\\b0}")
+                    .replace("<u>", "{\\u1}")
+                    .replace("</u>", "{\\u0}");
+                ass + &format!(
+                    "Dialogue: 0,{},{},Default,,0,0,0,,{}\n",
+                    format_ass_timestamp(cue.start),
+                    format_ass_timestamp(cue.end),
+                    text
+                )
+            }),
+        }
+    }
+}
+
+const ASS_HEADER: &str = "[Script Info]
+ScriptType: v4.00+
+
+[V4+ Styles]
+Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding
+Style: Default,Arial,20,&H00FFFFFF,&H000000FF,&H00000000,&H00000000,0,0,0,0,100,100,0,0,1,2,2,2,10,10,10,1
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+";
+
+/// Parses the SRT and WebVTT cues, which are both blocks separated by empty lines
+/// with a `start --> end` timing line followed by the text.
+///
+/// Blocks without a timing line (the WebVTT header, `NOTE`, `STYLE` and `REGION` blocks) are skipped.
+fn parse_timed_blocks(content: &str) -> Vec<SubtitlesCue> {
+    let mut cues = vec![];
+    let mut lines = content.lines().peekable();
+    while lines.peek().is_some() {
+        let block = lines
+            .by_ref()
+            .skip_while(|line| line.trim().is_empty())
+            .take_while(|line| !line.trim().is_empty())
+            .collect::<Vec<_>>();
+        let timing_position = match block.iter().position(|line| line.contains("-->")) {
+            Some(position) => position,
+            None => continue,
+        };
+        let (start, end) = match block[timing_position].split_once("-->") {
+            Some((start, end)) => (
+                parse_timestamp(start),
+                // WebVTT cue settings follow the end timestamp
+                end.split_whitespace().next().and_then(parse_timestamp),
+            ),
+            None => continue,
+        };
+        let text = block[timing_position + 1..]
+            .iter()
+            .map(|line| line.trim_end())
+            .collect::<Vec<_>>()
+            .join("\n");
+        if let (Some(start), Some(end), false) = (start, end, text.is_empty()) {
+            cues.push(SubtitlesCue {
+                start,
+                end: end.max(start),
+                text: strip_tags(&text),
+            });
+        }
+    }
+    cues
+}
+
+/// Parses the `Dialogue` lines of the `[Events]` section using the order of its `Format` line.
+fn parse_ass(content: &str) -> Vec<SubtitlesCue> {
+    let mut format = vec![];
+    let mut cues = vec![];
+    let mut in_events = false;
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_events = line.eq_ignore_ascii_case("[Events]");
+        } else if !in_events {
+            continue;
+        } else if let Some(fields) = line.strip_prefix("Format:") {
+            format = fields
+                .split(',')
+                .map(|field| field.trim().to_lowercase())
+                .collect();
+        } else if let Some(fields) = line.strip_prefix("Dialogue:") {
+            if format.is_empty() {
+                continue;
+            }
+            // the text is always the last field and may contain commas
+            let fields = fields.splitn(format.len(), ',').collect::<Vec<_>>();
+            let field = |name: &str| {
+                format
+                    .iter()
+                    .position(|field| field == name)
+                    .and_then(|position| fields.get(position))
+            };
+            let start = field("start").and_then(|start| parse_timestamp(start));
+            let end = field("end").and_then(|end| parse_timestamp(end));
+            let text = field("text")
+                .map(|text| {
+                    strip_ass_overrides(text)
+                        .replace("\\N", "\n")
+                        .replace("\\n", "\n")
+                        .replace("\\h", " ")
+                })
+                .unwrap_or_default();
+            if let (Some(start), Some(end), false) = (start, end, text.trim().is_empty()) {
+                cues.push(SubtitlesCue {
+                    start,
+                    end: end.max(start),
+                    text: text.trim().to_owned(),
+                });
+            }
+        }
+    }
+    cues
+}
+
+/// Parses `hh:mm:ss,mmm`, `hh:mm:ss.mmm`, `mm:ss.mmm` and the ASS `h:mm:ss.cc` timestamps.
+fn parse_timestamp(timestamp: &str) -> Option<u64> {
+    let timestamp = timestamp.trim();
+    let (time, fraction) = match timestamp.rsplit_once([',', '.']) {
+        Some((time, fraction)) => (time, fraction),
+        None => (timestamp, "0"),
+    };
+    if fraction.is_empty() || !fraction.chars().all(|char| char.is_ascii_digit()) {
+        return None;
+    }
+    // the fraction is in hundredths for ASS and in thousandths for the rest
+    let millis = format!("{fraction:0<3}")[..3].parse::<u64>().ok()?;
+    let parts = time
+        .split(':')
+        .map(|part| part.trim().parse::<u64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    let (hours, minutes, seconds) = match parts.as_slice() {
+        [hours, minutes, seconds] => (*hours, *minutes, *seconds),
+        [minutes, seconds] => (0, *minutes, *seconds),
+        _ => return None,
+    };
+    // malformed files may have timestamps too large for the milliseconds
+    hours
+        .checked_mul(3600)?
+        .checked_add(minutes.checked_mul(60)?)?
+        .checked_add(seconds)?
+        .checked_mul(1000)?
+        .checked_add(millis)
+}
+
+fn format_timestamp(time: u64, separator: char) -> String {
+    format!(
+        "{:02}:{:02}:{:02}{separator}{:03}",
+        time / 3_600_000,
+        time / 60_000 % 60,
+        time / 1000 % 60,
+        time % 1000
+    )
+}
+
+fn format_ass_timestamp(time: u64) -> String {
+    format!(
+        "{}:{:02}:{:02}.{:02}",
+        time / 3_600_000,
+        time / 60_000 % 60,
+        time / 1000 % 60,
+        time % 1000 / 10
+    )
+}
+
+/// Removes all the tags except `<i>`, `<b>` and `<u>`, e.g. `<font>` in SRT
+/// or the WebVTT voice and class spans.
+fn strip_tags(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(tag_start) = rest.find('<') {
+        let (before, tag) = rest.split_at(tag_start);
+        result.push_str(before);
+        match tag.find('>') {
+            Some(tag_end) => {
+                let name = tag[1..tag_end].trim_start_matches('/').to_lowercase();
+                if ["i", "b", "u"].contains(&name.as_str()) {
+                    result.push_str(&tag[..=tag_end].to_lowercase());
+                }
+                rest = &tag[tag_end + 1..];
+            }
+            None => {
+                result.push_str(tag);
+                rest = "";
+            }
+        }
+    }
+    result.push_str(rest);
+    // ASS style overrides are commonly found in SRT files as well, e.g. `{\an8}`
+    strip_ass_overrides(&result)
+}
+
+/// Converts the italic, bold and underline ASS overrides and removes the rest.
+fn strip_ass_overrides(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(block_start) = rest.find("{\\") {
+        let (before, block) = rest.split_at(block_start);
+        result.push_str(before);
+        match block.find('}') {
+            Some(block_end) => {
+                for tag in block[2..block_end].split('\\') {
+                    let tag = match tag {
+                        "i1" => "<i>",
+                        "i0" => "</i>",
+                        "b1" => "<b>",
+                        "b0" => "</b>",
+                        "u1" => "<u>",
+                        "u0" => "</u>",
+                        _ => "",
+                    };
+                    result.push_str(tag);
+                }
+                rest = &block[block_end + 1..];
+            }
+            None => {
+                result.push_str(block);
+                rest = "";
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_timestamp, SubtitlesCue, SubtitlesFile, SubtitlesFormat};
+
+    #[test]
+    fn parse_ass_and_convert_to_srt() {
+        let ass = "[Script Info]\nScriptType: v4.00+\n\n[Events]\n\
+            Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\n\
+            Dialogue: 0,0:00:05.50,0:00:07.00,Default,,0,0,0,,{\\i1}Hello{\\i0}, world\\NSecond line\n\
+            Comment: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,Ignored\n\
+            Dialogue: 0,0:00:01.00,0:00:02.25,Default,,0,0,0,,{\\pos(10,10)}First\n";
+        let file = SubtitlesFile::parse(ass.as_bytes()).expect("Should parse the ASS file");
+        assert_eq!(file.format, SubtitlesFormat::ASS);
+        assert_eq!(
+            file.cues,
+            vec![
+                SubtitlesCue {
+                    start: 1000,
+                    end: 2250,
+                    text: "First".to_owned(),
+                },
+                SubtitlesCue {
+                    start: 5500,
+                    end: 7000,
+                    text: "<i>Hello</i>, world\nSecond line".to_owned(),
+                },
+            ]
+        );
+        assert_eq!(
+            file.to_format(SubtitlesFormat::SRT),
+            "1\n00:00:01,000 --> 00:00:02,250\nFirst\n\n\
+            2\n00:00:05,500 --> 00:00:07,000\n<i>Hello</i>, world\nSecond line\n\n"
+        );
+    }
+
+    #[test]
+    fn parse_timestamps() {
+        assert_eq!(parse_timestamp("01:02:03,456"), Some(3_723_456));
+        assert_eq!(parse_timestamp("02:03.5"), Some(123_500));
+        assert_eq!(parse_timestamp("0:00:05.50"), Some(5500));
+        assert_eq!(
+            parse_timestamp("99999999999999999:00:00,000"),
+            None,
+            "Too large timestamp should not be parsed"
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::types::subtitles::SubtitlesCue;
+
+/// Ratios of the common framerate mismatches, e.g. subtitles timed for
+/// a 23.976 fps release played along a 25 fps one.
+const FRAMERATE_RATIOS: [f64; 7] = [
+    1.0,
+    25.0 / 23.976,
+    23.976 / 25.0,
+    25.0 / 24.0,
+    24.0 / 25.0,
+    24.0 / 23.976,
+    23.976 / 24.0,
+];
+/// The maximum offset between the subtitles and the reference, in milliseconds.
+const MAX_OFFSET: i64 = 5 * 60 * 1000;
+/// The size of the buckets in which the offsets are counted, in milliseconds.
+const OFFSET_BUCKET: i64 = 100;
+/// The maximum difference between the start of two matching cues, in milliseconds.
+const MATCH_TOLERANCE: i64 = 300;
+const MIN_MATCHES: usize = 5;
+const MIN_CONFIDENCE: f64 = 0.4;
+
+/// The cues against which the timing of the addon subtitles is synced.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(tag = "type", content = "content")]
+pub enum SubtitlesReference {
+    /// The cues of an embedded subtitles track, as extracted by the player.
+    Embedded(Vec<SubtitlesCue>),
+    /// Another addon subtitles file, e.g. in the secondary subtitles language.
+    Url(Url),
+}
+
+/// The estimated timing difference between a subtitles file and a reference,
+/// such that `reference_time = time * drift + offset`.
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SubtitlesSync {
+    /// In milliseconds
+    pub offset: i64,
+    /// `1.0` unless the subtitles are timed for a release with a different framerate.
+    pub drift: f64,
+    /// The constant delay which fits best when the drift can't be applied,
+    /// i.e. the suggested [`StreamItemState::subtitle_delay`].
+    ///
+    /// [`StreamItemState::subtitle_delay`]: crate::types::streams::StreamItemState::subtitle_delay
+    pub delay: i64,
+    /// The share of the cues matching the reference, between `0.0` and `1.0`.
+    pub confidence: f64,
+}
+
+impl SubtitlesSync {
+    /// Estimates the offset and the drift by matching the start of the cues to the reference.
+    ///
+    /// For each of the common framerate ratios the most frequent offset between
+    /// the cues is found and the ratio with the most matching cues is picked.
+    /// Returns `None` when the subtitles don't match the reference well enough.
+    pub fn estimate(cues: &[SubtitlesCue], reference: &[SubtitlesCue]) -> Option<Self> {
+        let mut reference_starts = reference
+            .iter()
+            .map(|cue| cue.start as i64)
+            .collect::<Vec<_>>();
+        reference_starts.sort_unstable();
+        let (drift, offset, matches) = FRAMERATE_RATIOS
+            .iter()
+            .filter_map(|drift| {
+                let starts = cues
+                    .iter()
+                    .map(|cue| (cue.start as f64 * drift).round() as i64)
+                    .collect::<Vec<_>>();
+                estimate_offset(&starts, &reference_starts)
+                    .map(|(offset, matches)| (*drift, offset, matches))
+            })
+            // the first ratio is kept on equal matches, so no drift is preferred
+            .fold(
+                None,
+                |best: Option<(f64, i64, usize)>, candidate| match best {
+                    Some(best) if best.2 >= candidate.2 => Some(best),
+                    _ => Some(candidate),
+                },
+            )?;
+        let confidence = matches as f64 / cues.len().min(reference.len()) as f64;
+        if matches < MIN_MATCHES || confidence < MIN_CONFIDENCE {
+            return None;
+        }
+        let first_start = cues.iter().map(|cue| cue.start).min().unwrap_or_default();
+        let last_end = cues.iter().map(|cue| cue.end).max().unwrap_or_default();
+        let middle = (first_start + last_end) as f64 / 2.0;
+        Some(SubtitlesSync {
+            offset,
+            drift,
+            delay: (middle * drift + offset as f64 - middle).round() as i64,
+            confidence,
+        })
+    }
+}
+
+/// Returns the offset and the number of the matching cues.
+fn estimate_offset(starts: &[i64], reference_starts: &[i64]) -> Option<(i64, usize)> {
+    let mut histogram = HashMap::<i64, usize>::new();
+    for start in starts {
+        let from = reference_starts.partition_point(|reference| *reference < start - MAX_OFFSET);
+        let to = reference_starts.partition_point(|reference| *reference <= start + MAX_OFFSET);
+        for reference in &reference_starts[from..to] {
+            *histogram
+                .entry((reference - start).div_euclid(OFFSET_BUCKET))
+                .or_default() += 1;
+        }
+    }
+    let bucket_count = |bucket: i64| histogram.get(&bucket).copied().unwrap_or_default();
+    let coarse_offset = histogram
+        .keys()
+        .max_by_key(|bucket| {
+            (
+                bucket_count(**bucket - 1) + bucket_count(**bucket) + bucket_count(**bucket + 1),
+                // the HashMap order is random, prefer the smaller offsets on equal counts
+                -bucket.abs(),
+                **bucket,
+            )
+        })
+        .map(|bucket| bucket * OFFSET_BUCKET + OFFSET_BUCKET / 2)?;
+    let mut differences = starts
+        .iter()
+        .filter_map(|start| {
+            let target = start + coarse_offset;
+            let position = reference_starts.partition_point(|reference| *reference < target);
+            [position.checked_sub(1), Some(position)]
+                .into_iter()
+                .flatten()
+                .filter_map(|position| reference_starts.get(position))
+                .map(|reference| reference - start)
+                .min_by_key(|difference| (difference - coarse_offset).abs())
+                .filter(|difference| (difference - coarse_offset).abs() <= MATCH_TOLERANCE)
+        })
+        .collect::<Vec<_>>();
+    if differences.is_empty() {
+        return None;
+    }
+    differences.sort_unstable();
+    Some((differences[differences.len() / 2], differences.len()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{SubtitlesCue, SubtitlesSync};
+
+    fn cues(starts: impl Iterator<Item = i64>) -> Vec<SubtitlesCue> {
+        starts
+            .map(|start| SubtitlesCue {
+                start: start as u64,
+                end: start as u64 + 1500,
+                text: "text".to_owned(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn estimate_offset_and_drift() {
+        let reference_starts = (0..200)
+            .map(|index: i64| 10_000 + index * 3_700 + (index * index % 7) * 450)
+            .collect::<Vec<_>>();
+        let reference = cues(reference_starts.iter().copied());
+
+        let delayed = cues(reference_starts.iter().map(|start| start + 2_000));
+        let sync = SubtitlesSync::estimate(&delayed, &reference).expect("Should be in sync");
+        assert_eq!(sync.drift, 1.0);
+        assert_eq!(sync.offset, -2_000);
+        assert_eq!(sync.delay, -2_000);
+        assert_eq!(sync.confidence, 1.0);
+
+        let drift = 25.0 / 23.976;
+        let drifted = cues(
+            reference_starts
+                .iter()
+                .map(|start| ((start - 1_500) as f64 / drift).round() as i64),
+        );
+        let sync = SubtitlesSync::estimate(&drifted, &reference).expect("Should be in sync");
+        assert_eq!(sync.drift, drift);
+        assert!((sync.offset - 1_500).abs() <= 1, "offset: {}", sync.offset);
+        assert!(sync.confidence > 0.9);
+
+        let unrelated = cues((0..200).map(|index| 1_000 + index * 1_000));
+        assert_eq!(SubtitlesSync::estimate(&unrelated, &reference), None);
+    }
+}
@@ -0,0 +1,2 @@
+mod torrent_info;
+pub use torrent_info::*;
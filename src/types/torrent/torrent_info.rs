@@ -0,0 +1,237 @@
+use std::fmt::Display;
+
+use itertools::Itertools;
+use magnet_url::Magnet;
+use percent_encoding::percent_decode_str;
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use stremio_serde_hex::{SerHex, Strict};
+use url::Url;
+
+use crate::types::resource::{
+    MetaItemPreview, PosterShape, Stream, StreamBehaviorHints, StreamSource,
+};
+use crate::types::streaming_server::File;
+
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
+#[serde(tag = "type", content = "content")]
+pub enum TorrentInfoError {
+    /// The .torrent file could not be decoded.
+    Torrent(String),
+    /// The url is not a magnet with a hex or base32 encoded info hash.
+    Magnet,
+    /// The files of the magnet could not be fetched from the streaming server.
+    Files(String),
+}
+
+impl Display for TorrentInfoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TorrentInfoError::Torrent(message) => write!(f, "{message}"),
+            TorrentInfoError::Magnet => write!(f, "Invalid magnet url"),
+            TorrentInfoError::Files(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+/// The metadata of a torrent, decoded from a .torrent file or a magnet.
+#[derive(Clone, PartialEq, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TorrentInfo {
+    #[serde(with = "SerHex::<Strict>")]
+    pub info_hash: [u8; 20],
+    pub name: Option<String>,
+    /// The total size of the files in bytes, `None` when unknown.
+    pub size: Option<u64>,
+    /// Empty for magnets until the torrent is created on the streaming server.
+    pub files: Vec<File>,
+    pub announce: Vec<String>,
+}
+
+impl TorrentInfo {
+    pub fn from_torrent(torrent: &[u8]) -> Result<Self, TorrentInfoError> {
+        #[derive(Deserialize)]
+        struct TorrentFile {
+            info: serde_bencode::value::Value,
+            #[serde(default)]
+            announce: Option<String>,
+            #[serde(default)]
+            #[serde(rename = "announce-list")]
+            announce_list: Option<Vec<Vec<String>>>,
+        }
+        #[derive(Deserialize)]
+        struct InfoFile {
+            length: u64,
+            path: Vec<String>,
+        }
+        #[derive(Deserialize)]
+        struct Info {
+            name: String,
+            #[serde(default)]
+            length: Option<u64>,
+            #[serde(default)]
+            files: Vec<InfoFile>,
+        }
+        let torrent_file = serde_bencode::from_bytes::<TorrentFile>(torrent)
+            .map_err(|error| TorrentInfoError::Torrent(error.to_string()))?;
+        let info_bytes = serde_bencode::to_bytes(&torrent_file.info)
+            .map_err(|error| TorrentInfoError::Torrent(error.to_string()))?;
+        let info = serde_bencode::from_bytes::<Info>(&info_bytes)
+            .map_err(|error| TorrentInfoError::Torrent(error.to_string()))?;
+        let mut hasher = Sha1::new();
+        hasher.update(info_bytes);
+        let mut info_hash = [0; 20];
+        info_hash.copy_from_slice(&hasher.finalize());
+        let overflow_error = || TorrentInfoError::Torrent("File length overflow".to_owned());
+        // single file torrents have the length of the file in the info
+        let files = match info.length {
+            Some(length) => vec![File {
+                name: info.name.to_owned(),
+                path: info.name.to_owned(),
+                length,
+                offset: 0,
+            }],
+            None => {
+                let mut offset = 0_u64;
+                info.files
+                    .iter()
+                    .map(|file| {
+                        let file_offset = offset;
+                        offset = offset.checked_add(file.length).ok_or_else(overflow_error)?;
+                        Ok(File {
+                            name: file.path.last().cloned().unwrap_or_default(),
+                            path: format!("{}/{}", info.name, file.path.join("/")),
+                            length: file.length,
+                            offset: file_offset,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?
+            }
+        };
+        let size = files_size(&files).ok_or_else(overflow_error)?;
+        let announce = torrent_file
+            .announce
+            .into_iter()
+            .chain(torrent_file.announce_list.into_iter().flatten().flatten())
+            .unique()
+            .collect();
+        Ok(TorrentInfo {
+            info_hash,
+            name: Some(info.name),
+            size: Some(size),
+            files,
+            announce,
+        })
+    }
+    pub fn from_magnet(magnet: &Url) -> Result<Self, TorrentInfoError> {
+        let magnet = Magnet::new(magnet.as_str()).map_err(|_| TorrentInfoError::Magnet)?;
+        let info_hash = magnet
+            .xt
+            .and_then(|info_hash| decode_info_hash(&info_hash))
+            .ok_or(TorrentInfoError::Magnet)?;
+        let name = magnet.dn.map(|name| {
+            percent_decode_str(&name.replace('+', " "))
+                .decode_utf8_lossy()
+                .into_owned()
+        });
+        let announce = magnet
+            .tr
+            .iter()
+            .map(|tracker| percent_decode_str(tracker).decode_utf8_lossy().into_owned())
+            .unique()
+            .collect();
+        Ok(TorrentInfo {
+            info_hash,
+            name,
+            size: magnet.xl,
+            files: vec![],
+            announce,
+        })
+    }
+    /// The stream of a file of the torrent,
+    /// the streaming server chooses the largest file without a `file_idx`.
+    pub fn stream(&self, file_idx: Option<u16>) -> Stream {
+        let file = file_idx.and_then(|file_idx| self.files.get(file_idx as usize));
+        let mut behavior_hints = StreamBehaviorHints::default();
+        if let Some(file) = file {
+            behavior_hints
+                .other
+                .insert("filename".to_owned(), file.name.to_owned().into());
+            behavior_hints
+                .other
+                .insert("videoSize".to_owned(), file.length.into());
+        };
+        Stream {
+            source: StreamSource::Torrent {
+                info_hash: self.info_hash,
+                file_idx,
+                announce: self.announce.to_owned(),
+            },
+            name: self.name.to_owned(),
+            description: file.map(|file| file.path.to_owned()),
+            thumbnail: None,
+            subtitles: vec![],
+            behavior_hints,
+        }
+    }
+    /// The meta item for adding the torrent to the library,
+    /// with the same id as the streaming server uses for the torrents.
+    pub fn meta_item(&self) -> MetaItemPreview {
+        let info_hash = hex::encode(self.info_hash);
+        MetaItemPreview {
+            id: format!("bt:{info_hash}"),
+            r#type: "other".to_owned(),
+            name: self.name.to_owned().unwrap_or(info_hash),
+            poster: None,
+            background: None,
+            logo: None,
+            description: None,
+            release_info: None,
+            runtime: None,
+            released: None,
+            poster_shape: PosterShape::default(),
+            links: vec![],
+            trailer_streams: vec![],
+            behavior_hints: Default::default(),
+        }
+    }
+}
+
+/// The total length of the files, `None` on overflow.
+pub fn files_size(files: &[File]) -> Option<u64> {
+    files
+        .iter()
+        .try_fold(0_u64, |size, file| size.checked_add(file.length))
+}
+
+/// Decodes a magnet info hash, either 40 hex or 32 base32 characters.
+fn decode_info_hash(info_hash: &str) -> Option<[u8; 20]> {
+    let info_hash = match info_hash.len() {
+        40 => hex::decode(info_hash).ok()?,
+        32 => decode_base32(info_hash)?,
+        _ => return None,
+    };
+    info_hash.try_into().ok()
+}
+
+/// Decodes unpadded RFC 4648 base32.
+fn decode_base32(value: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(value.len() * 5 / 8);
+    let mut buffer = 0_u32;
+    let mut bits = 0;
+    for character in value.bytes() {
+        let digit = match character.to_ascii_uppercase() {
+            character @ b'A'..=b'Z' => character - b'A',
+            character @ b'2'..=b'7' => character - b'2' + 26,
+            _ => return None,
+        };
+        buffer = (buffer << 5) | u32::from(digit);
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(bytes)
+}
@@ -0,0 +1,263 @@
+use crate::constants::{CINEMETA_URL, OFFICIAL_ADDONS};
+use crate::models::calendar::{Calendar, Selected};
+use crate::models::common::{Loadable, ResourceLoadable};
+use crate::models::ctx::Ctx;
+use crate::runtime::msg::{Action, ActionCtx, ActionLoad};
+use crate::runtime::{EnvError, EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture};
+use crate::types::addon::{
+    Descriptor, Manifest, ManifestResource, ResourcePath, ResourceRequest, ResourceResponse,
+};
+use crate::types::library::{LibraryBucket, LibraryItem};
+use crate::types::profile::Profile;
+use crate::types::resource::{MetaItem, MetaItemPreview, SeriesInfo, Video};
+use crate::unit_tests::{default_fetch_handler, Request, TestEnv, FETCH_HANDLER, REQUESTS};
+use chrono::{NaiveDate, TimeZone, Utc};
+use futures::future;
+use semver::Version;
+use std::any::Any;
+use stremio_derive::Model;
+
+fn meta_item(id: &str, released: &[(u32, u32, u32)]) -> MetaItem {
+    MetaItem {
+        preview: MetaItemPreview {
+            id: id.to_owned(),
+            r#type: "series".to_owned(),
+            name: id.to_owned(),
+            ..Default::default()
+        },
+        videos: released
+            .iter()
+            .enumerate()
+            .map(|(index, (month, day, hour))| {
+                let episode = index as u32 + 1;
+                Video {
+                    id: format!("{id}:1:{episode}"),
+                    released: Some(
+                        Utc.with_ymd_and_hms(2024, *month, *day, *hour, 0, 0)
+                            .unwrap(),
+                    ),
+                    series_info: Some(SeriesInfo { season: 1, episode }),
+                    ..Default::default()
+                }
+            })
+            .collect(),
+    }
+}
+
+fn library_item(id: &str) -> LibraryItem {
+    LibraryItem {
+        id: id.to_owned(),
+        name: id.to_owned(),
+        r#type: "series".to_owned(),
+        poster: None,
+        poster_shape: Default::default(),
+        removed: false,
+        temp: false,
+        ctime: None,
+        mtime: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+        state: Default::default(),
+        behavior_hints: Default::default(),
+    }
+}
+
+#[test]
+fn calendar_groups_videos_by_day() {
+    #[derive(Model, Default, Clone, Debug)]
+    #[model(TestEnv)]
+    struct TestModel {
+        ctx: Ctx,
+        calendar: Calendar,
+    }
+    fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+        match request {
+            Request { url, .. } if url == "https://v3-cinemeta.strem.io/meta/series/tt2.json" => {
+                future::ok(Box::new(ResourceResponse::Meta {
+                    // the second episode is released late in the evening in UTC+2
+                    meta: meta_item("tt2", &[(2, 1, 12), (2, 2, 22), (3, 1, 12)]),
+                }) as Box<dyn Any + Send>)
+                .boxed_env()
+            }
+            _ => default_fetch_handler(request),
+        }
+    }
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+    let notification_catalog = ResourceLoadable {
+        request: ResourceRequest::new(
+            "https://addon_1.com/manifest.json".parse().unwrap(),
+            ResourcePath::without_extra("catalog", "series", "lastVideosIds"),
+        ),
+        content: Some(Loadable::Ready(vec![meta_item(
+            "tt1",
+            &[(1, 31, 12), (2, 3, 12)],
+        )])),
+    };
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                profile: Profile {
+                    addons: OFFICIAL_ADDONS
+                        .iter()
+                        .filter(|addon| addon.transport_url == *CINEMETA_URL)
+                        .cloned()
+                        .collect(),
+                    ..Default::default()
+                },
+                library: LibraryBucket::new(None, vec![library_item("tt1"), library_item("tt2")]),
+                notification_catalogs: vec![notification_catalog],
+                ..Default::default()
+            },
+            calendar: Default::default(),
+        },
+        vec![],
+        1000,
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Load(ActionLoad::Calendar(Selected {
+                from: NaiveDate::from_ymd_opt(2024, 2, 1).unwrap(),
+                to: NaiveDate::from_ymd_opt(2024, 2, 29).unwrap(),
+                utc_offset: 120,
+            })),
+        })
+    });
+    assert_eq!(
+        REQUESTS.read().unwrap().len(),
+        1,
+        "Only the meta item missing from the notification catalogs should be requested"
+    );
+    let model = runtime.model().unwrap();
+    let days = model
+        .calendar
+        .days
+        .iter()
+        .map(|day| {
+            (
+                day.date,
+                day.items
+                    .iter()
+                    .map(|item| item.video.id.as_str())
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        days,
+        vec![
+            (
+                NaiveDate::from_ymd_opt(2024, 2, 1).unwrap(),
+                vec!["tt2:1:1"]
+            ),
+            (
+                NaiveDate::from_ymd_opt(2024, 2, 3).unwrap(),
+                vec!["tt2:1:2", "tt1:1:2"]
+            ),
+        ]
+    );
+    assert_eq!(
+        model.calendar.days[1].items[1].request.path,
+        ResourcePath::without_extra("meta", "series", "tt1")
+    );
+}
+
+#[test]
+fn calendar_falls_back_to_the_next_addon() {
+    #[derive(Model, Default, Clone, Debug)]
+    #[model(TestEnv)]
+    struct TestModel {
+        ctx: Ctx,
+        calendar: Calendar,
+    }
+    fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+        match request {
+            Request { url, .. } if url == "https://failing_addon/meta/series/tt1.json" => {
+                future::err(EnvError::Fetch("Connection refused".to_owned())).boxed_env()
+            }
+            Request { url, .. } if url == "https://v3-cinemeta.strem.io/meta/series/tt1.json" => {
+                future::ok(Box::new(ResourceResponse::Meta {
+                    meta: meta_item("tt1", &[(2, 1, 12)]),
+                }) as Box<dyn Any + Send>)
+                .boxed_env()
+            }
+            _ => default_fetch_handler(request),
+        }
+    }
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+    let failing_addon = Descriptor {
+        manifest: Manifest {
+            id: "failing_addon".to_owned(),
+            version: Version::new(0, 0, 1),
+            name: "failing_addon".to_owned(),
+            contact_email: None,
+            description: None,
+            logo: None,
+            background: None,
+            types: vec!["series".to_owned()],
+            resources: vec![ManifestResource::Short("meta".to_owned())],
+            id_prefixes: None,
+            catalogs: vec![],
+            addon_catalogs: vec![],
+            behavior_hints: Default::default(),
+        },
+        transport_url: "https://failing_addon/manifest.json".parse().unwrap(),
+        flags: Default::default(),
+    };
+    let cinemeta_addon = OFFICIAL_ADDONS
+        .iter()
+        .find(|addon| addon.transport_url == *CINEMETA_URL)
+        .cloned()
+        .unwrap();
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                profile: Profile {
+                    addons: vec![failing_addon],
+                    ..Default::default()
+                },
+                library: LibraryBucket::new(None, vec![library_item("tt1")]),
+                ..Default::default()
+            },
+            calendar: Default::default(),
+        },
+        vec![],
+        1000,
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Load(ActionLoad::Calendar(Selected {
+                from: NaiveDate::from_ymd_opt(2024, 2, 1).unwrap(),
+                to: NaiveDate::from_ymd_opt(2024, 2, 29).unwrap(),
+                utc_offset: 0,
+            })),
+        })
+    });
+    assert!(runtime.model().unwrap().calendar.days.is_empty());
+
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Ctx(ActionCtx::InstallAddon(cinemeta_addon)),
+        })
+    });
+    let meta_requests = REQUESTS
+        .read()
+        .unwrap()
+        .iter()
+        .map(|request| request.url.to_owned())
+        .filter(|url| url.contains("/meta/"))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        meta_requests,
+        vec![
+            "https://failing_addon/meta/series/tt1.json".to_owned(),
+            "https://v3-cinemeta.strem.io/meta/series/tt1.json".to_owned(),
+        ],
+        "The installed addon should be requested after the failed one"
+    );
+    let model = runtime.model().unwrap();
+    assert_eq!(model.calendar.days.len(), 1);
+    assert_eq!(model.calendar.days[0].items[0].video.id, "tt1:1:1");
+}
@@ -0,0 +1,300 @@
+use std::any::Any;
+use std::sync::RwLock;
+
+use chrono::{Duration, TimeZone, Utc};
+use futures::future;
+use once_cell::sync::Lazy;
+use stremio_derive::Model;
+use url::Url;
+
+use crate::constants::META_RESOURCE_NAME;
+use crate::models::cast_session::{CastSession, CastStatus, Selected};
+use crate::models::common::Loadable;
+use crate::models::ctx::Ctx;
+use crate::runtime::msg::{Action, ActionCastSession, ActionLoad};
+use crate::runtime::{EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture};
+use crate::types::addon::{ResourcePath, ResourceRequest, ResourceResponse};
+use crate::types::library::{LibraryBucket, LibraryItem, LibraryItemState};
+use crate::types::resource::{MetaItem, MetaItemPreview, Stream};
+use crate::types::streaming_server::ActiveServer;
+use crate::unit_tests::{default_fetch_handler, Request, TestEnv, FETCH_HANDLER, NOW, REQUESTS};
+
+const DEVICE_PLAYER_URL: &str = "http://127.0.0.1:11470/casting/chromecast-1/player";
+
+/// The playback status of the mocked device.
+static DEVICE: Lazy<RwLock<Option<CastStatus>>> = Lazy::new(Default::default);
+
+#[derive(Model, Clone, Debug)]
+#[model(TestEnv)]
+struct TestModel {
+    ctx: Ctx,
+    cast_session: CastSession,
+}
+
+fn device_fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+    match request {
+        Request {
+            url, method, body, ..
+        } if url == DEVICE_PLAYER_URL && method == "POST" => {
+            let command = serde_json::from_str::<serde_json::Value>(&body).unwrap();
+            let mut device = DEVICE.write().unwrap();
+            if command.get("source").is_some() {
+                *device = Some(CastStatus {
+                    time: 0,
+                    duration: 3_600_000,
+                    paused: false,
+                });
+            };
+            if let Some(status) = device.as_mut() {
+                if let Some(time) = command.get("time").and_then(|time| time.as_u64()) {
+                    status.time = time;
+                };
+                if let Some(paused) = command.get("paused").and_then(|paused| paused.as_bool()) {
+                    status.paused = paused;
+                };
+            };
+            if command.get("stop").is_some() {
+                *device = None;
+            };
+            future::ok(Box::new(serde_json::Value::Null) as Box<dyn Any + Send>).boxed_env()
+        }
+        Request { url, method, .. } if url == DEVICE_PLAYER_URL && method == "GET" => {
+            future::ok(Box::new(DEVICE.read().unwrap().to_owned()) as Box<dyn Any + Send>)
+                .boxed_env()
+        }
+        Request { url, .. } if url.starts_with("https://transport_url/meta/movie/") => {
+            let id = url
+                .trim_start_matches("https://transport_url/meta/movie/")
+                .trim_end_matches(".json");
+            future::ok(Box::new(ResourceResponse::Meta {
+                meta: MetaItem {
+                    preview: MetaItemPreview {
+                        id: id.to_owned(),
+                        r#type: "movie".to_owned(),
+                        name: "name".to_owned(),
+                        ..Default::default()
+                    },
+                    videos: vec![],
+                },
+            }) as Box<dyn Any + Send>)
+            .boxed_env()
+        }
+        _ => default_fetch_handler(request),
+    }
+}
+
+fn meta_request(id: &str) -> ResourceRequest {
+    ResourceRequest {
+        base: "https://transport_url/manifest.json".parse().unwrap(),
+        path: ResourcePath {
+            resource: META_RESOURCE_NAME.to_owned(),
+            r#type: "movie".to_owned(),
+            id: id.to_owned(),
+            extra: vec![],
+        },
+    }
+}
+
+#[test]
+fn cast_session() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(device_fetch_handler);
+    *DEVICE.write().unwrap() = None;
+    let start_time = Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap();
+    let library_item = LibraryItem {
+        id: "tt1".to_owned(),
+        name: "name".to_owned(),
+        r#type: "movie".to_owned(),
+        poster: None,
+        poster_shape: Default::default(),
+        removed: false,
+        temp: false,
+        ctime: None,
+        mtime: start_time,
+        state: LibraryItemState {
+            time_offset: 60_000,
+            duration: 3_600_000,
+            video_id: Some("tt1".to_owned()),
+            ..Default::default()
+        },
+        behavior_hints: Default::default(),
+    };
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                library: LibraryBucket::new(None, vec![library_item]),
+                ..Default::default()
+            },
+            cast_session: CastSession::default(),
+        },
+        vec![],
+        1000,
+    );
+    let dispatch_at = |seconds: i64, action: Action| {
+        *NOW.write().unwrap() = start_time + Duration::seconds(seconds);
+        TestEnv::run(|| {
+            runtime.dispatch(RuntimeAction {
+                field: None,
+                action,
+            })
+        });
+    };
+    let source = Url::parse("http://127.0.0.1:11470/yt/aqz-KE-bpKQ").unwrap();
+    dispatch_at(
+        0,
+        Action::Load(ActionLoad::CastSession(Selected {
+            device: "chromecast-1".to_owned(),
+            stream: Stream::youtube("yt_id:aqz-KE-bpKQ").unwrap(),
+            source,
+            meta_request: Some(meta_request("tt1")),
+            video_id: Some("tt1".to_owned()),
+            time: Some(60_000),
+        })),
+    );
+    assert_eq!(
+        runtime.model().unwrap().cast_session.status,
+        Some(Loadable::Ready(CastStatus {
+            time: 60_000,
+            duration: 3_600_000,
+            paused: false,
+        })),
+        "The device should play from the given time"
+    );
+    DEVICE.write().unwrap().as_mut().unwrap().time = 70_000;
+    dispatch_at(10, Action::CastSession(ActionCastSession::Refresh));
+    dispatch_at(11, Action::CastSession(ActionCastSession::SetPaused(true)));
+    assert!(DEVICE.read().unwrap().as_ref().unwrap().paused);
+    dispatch_at(12, Action::CastSession(ActionCastSession::Seek(300_000)));
+    dispatch_at(15, Action::CastSession(ActionCastSession::Refresh));
+    assert_eq!(
+        runtime.model().unwrap().cast_session.status,
+        Some(Loadable::Ready(CastStatus {
+            time: 300_000,
+            duration: 3_600_000,
+            paused: true,
+        }))
+    );
+    dispatch_at(16, Action::CastSession(ActionCastSession::Stop));
+    assert_eq!(
+        *DEVICE.read().unwrap(),
+        None,
+        "The device should be stopped"
+    );
+    let model = runtime.model().unwrap();
+    assert_eq!(model.cast_session.selected, None);
+    let library_item_state = &model.ctx.library.items.get("tt1").unwrap().state;
+    assert_eq!(library_item_state.time_offset, 300_000);
+    assert_eq!(
+        library_item_state.time_watched, 15_000,
+        "Seeking should not be counted as watched"
+    );
+}
+
+#[test]
+fn cast_session_not_in_library() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(device_fetch_handler);
+    *DEVICE.write().unwrap() = None;
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx::default(),
+            cast_session: CastSession::default(),
+        },
+        vec![],
+        1000,
+    );
+    let dispatch = |action: Action| {
+        TestEnv::run(|| {
+            runtime.dispatch(RuntimeAction {
+                field: None,
+                action,
+            })
+        });
+    };
+    dispatch(Action::Load(ActionLoad::CastSession(Selected {
+        device: "chromecast-1".to_owned(),
+        stream: Stream::youtube("yt_id:aqz-KE-bpKQ").unwrap(),
+        source: Url::parse("http://127.0.0.1:11470/yt/aqz-KE-bpKQ").unwrap(),
+        meta_request: Some(meta_request("tt2")),
+        video_id: Some("tt2".to_owned()),
+        time: None,
+    })));
+    assert_eq!(
+        runtime
+            .model()
+            .unwrap()
+            .cast_session
+            .library_item
+            .as_ref()
+            .map(|library_item| library_item.id.to_owned()),
+        Some("tt2".to_owned()),
+        "The library item should be created from the meta item"
+    );
+    DEVICE.write().unwrap().as_mut().unwrap().time = 10_000;
+    dispatch(Action::CastSession(ActionCastSession::Refresh));
+    dispatch(Action::CastSession(ActionCastSession::Stop));
+    let model = runtime.model().unwrap();
+    let library_item = model
+        .ctx
+        .library
+        .items
+        .get("tt2")
+        .expect("The progress should be saved in the library");
+    assert_eq!(library_item.name, "name");
+    assert_eq!(library_item.state.time_offset, 10_000);
+}
+
+#[test]
+fn cast_session_active_server() {
+    fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+        match request {
+            Request { url, method, .. }
+                if url == "http://192.168.0.2:11470/casting/chromecast-1/player"
+                    && method == "POST" =>
+            {
+                future::ok(Box::new(serde_json::Value::Null) as Box<dyn Any + Send>).boxed_env()
+            }
+            Request { url, method, .. }
+                if url == "http://192.168.0.2:11470/casting/chromecast-1/player"
+                    && method == "GET" =>
+            {
+                future::ok(Box::new(Option::<CastStatus>::None) as Box<dyn Any + Send>).boxed_env()
+            }
+            _ => default_fetch_handler(request),
+        }
+    }
+
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                streaming_server: ActiveServer::new(
+                    Url::parse("http://192.168.0.2:11470").unwrap(),
+                ),
+                ..Default::default()
+            },
+            cast_session: CastSession::default(),
+        },
+        vec![],
+        1000,
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Load(ActionLoad::CastSession(Selected {
+                device: "chromecast-1".to_owned(),
+                stream: Stream::youtube("yt_id:aqz-KE-bpKQ").unwrap(),
+                source: Url::parse("http://192.168.0.2:11470/yt/aqz-KE-bpKQ").unwrap(),
+                meta_request: None,
+                video_id: None,
+                time: None,
+            })),
+        })
+    });
+    assert_eq!(
+        REQUESTS.read().unwrap()[0].url,
+        "http://192.168.0.2:11470/casting/chromecast-1/player",
+        "The device should be found on the streaming server in use"
+    );
+}
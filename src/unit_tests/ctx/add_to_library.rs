@@ -4,6 +4,7 @@ use crate::runtime::msg::{Action, ActionCtx};
 use crate::runtime::{Env, EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture};
 use crate::types::api::{APIResult, SuccessResponse};
 use crate::types::events::DismissedEventsBucket;
+use crate::types::intro_outro::IntroOutroBucket;
 use crate::types::library::{LibraryBucket, LibraryItem, LibraryItemState};
 use crate::types::notifications::NotificationsBucket;
 use crate::types::profile::{Auth, AuthKey, GDPRConsent, Profile, User};
@@ -110,6 +111,7 @@ fn actionctx_addtolibrary() {
                 NotificationsBucket::new::<TestEnv>(None, vec![]),
                 SearchHistoryBucket::default(),
                 DismissedEventsBucket::default(),
+                IntroOutroBucket::default(),
             ),
         },
         vec![],
@@ -247,6 +249,7 @@ fn actionctx_addtolibrary_already_added() {
                 NotificationsBucket::new::<TestEnv>(None, vec![]),
                 SearchHistoryBucket::default(),
                 DismissedEventsBucket::default(),
+                IntroOutroBucket::default(),
             ),
         },
         vec![],
@@ -1,4 +1,5 @@
 use crate::types::events::DismissedEventsBucket;
+use crate::types::intro_outro::IntroOutroBucket;
 use crate::types::notifications::NotificationsBucket;
 use crate::types::search_history::SearchHistoryBucket;
 use crate::types::streams::StreamsBucket;
@@ -93,6 +94,7 @@ fn actionctx_authenticate_login() {
         NotificationsBucket::new::<TestEnv>(None, vec![]),
         SearchHistoryBucket::default(),
         DismissedEventsBucket::default(),
+        IntroOutroBucket::default(),
     );
     let (runtime, _rx) = Runtime::<TestEnv, _>::new(TestModel { ctx }, vec![], 1000);
     TestEnv::run(|| {
@@ -300,6 +302,7 @@ fn actionctx_authenticate_login_with_token() {
         NotificationsBucket::new::<TestEnv>(None, vec![]),
         SearchHistoryBucket::default(),
         DismissedEventsBucket::default(),
+        IntroOutroBucket::default(),
     );
     let (runtime, _rx) = Runtime::<TestEnv, _>::new(TestModel { ctx }, vec![], 1000);
     TestEnv::run(|| {
@@ -506,6 +509,7 @@ fn actionctx_authenticate_register() {
         NotificationsBucket::new::<TestEnv>(None, vec![]),
         SearchHistoryBucket::default(),
         DismissedEventsBucket::default(),
+        IntroOutroBucket::default(),
     );
     let (runtime, _rx) = Runtime::<TestEnv, _>::new(TestModel { ctx }, vec![], 1000);
     TestEnv::run(|| {
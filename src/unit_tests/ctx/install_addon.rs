@@ -5,6 +5,7 @@ use crate::runtime::{Env, EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture};
 use crate::types::addon::{Descriptor, Manifest};
 use crate::types::api::{APIResult, SuccessResponse};
 use crate::types::events::DismissedEventsBucket;
+use crate::types::intro_outro::IntroOutroBucket;
 use crate::types::library::LibraryBucket;
 use crate::types::notifications::NotificationsBucket;
 use crate::types::profile::{Auth, AuthKey, GDPRConsent, Profile, User};
@@ -59,6 +60,7 @@ fn actionctx_installaddon_install() {
                 NotificationsBucket::new::<TestEnv>(None, vec![]),
                 SearchHistoryBucket::default(),
                 DismissedEventsBucket::default(),
+                IntroOutroBucket::default(),
             ),
         },
         vec![],
@@ -165,6 +167,7 @@ fn actionctx_installaddon_install_with_user() {
                 NotificationsBucket::new::<TestEnv>(None, vec![]),
                 SearchHistoryBucket::default(),
                 DismissedEventsBucket::default(),
+                IntroOutroBucket::default(),
             ),
         },
         vec![],
@@ -288,6 +291,7 @@ fn actionctx_installaddon_update() {
                 NotificationsBucket::new::<TestEnv>(None, vec![]),
                 SearchHistoryBucket::default(),
                 DismissedEventsBucket::default(),
+                IntroOutroBucket::default(),
             ),
         },
         vec![],
@@ -365,6 +369,7 @@ fn actionctx_installaddon_already_installed() {
                 NotificationsBucket::new::<TestEnv>(None, vec![]),
                 SearchHistoryBucket::default(),
                 DismissedEventsBucket::default(),
+                IntroOutroBucket::default(),
             ),
         },
         vec![],
@@ -1,6 +1,6 @@
 use std::{
     any::Any,
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     sync::{Arc, RwLock},
 };
 
@@ -31,9 +31,10 @@ use crate::{
             ResourceResponse,
         },
         events::DismissedEventsBucket,
+        intro_outro::IntroOutroBucket,
         library::{LibraryBucket, LibraryItem, LibraryItemState},
-        notifications::{NotificationItem, NotificationsBucket},
-        profile::Profile,
+        notifications::{NotificationDigest, NotificationItem, NotificationsBucket},
+        profile::{Profile, QuietHours, Settings},
         resource::{
             MetaItem, MetaItemId, MetaItemPreview, PosterShape, SeriesInfo, Stream, StreamSource,
             Video, VideoId,
@@ -234,6 +235,7 @@ fn test_pull_notifications_and_play_in_player() {
                             duration: 101,
                             video_id: Some("tt1:1:5".to_string()),
                             no_notif: false,
+                            notif_prefs: Default::default(),
                         },
                         behavior_hints: Default::default(),
                     }],
@@ -242,6 +244,7 @@ fn test_pull_notifications_and_play_in_player() {
                 NotificationsBucket::new::<TestEnv>(None, vec![]),
                 SearchHistoryBucket::default(),
                 DismissedEventsBucket::default(),
+                IntroOutroBucket::default(),
             ),
             player: Default::default(),
         },
@@ -321,6 +324,7 @@ fn test_pull_notifications_and_play_in_player() {
                     },
                 }),
                 subtitles_path: None,
+                playback: None,
             }))),
         });
         runtime.dispatch(RuntimeAction {
@@ -392,6 +396,7 @@ fn test_pull_notifications_test_cases() {
                     NotificationsBucket::new::<TestEnv>(None, test.notification_items),
                     SearchHistoryBucket::default(),
                     DismissedEventsBucket::default(),
+                    IntroOutroBucket::default(),
                 ),
             },
             vec![],
@@ -454,6 +459,7 @@ fn test_dismiss_notification() {
                                 video_id: Some("tt1:1".into()),
                                 watched: None,
                                 no_notif: false,
+                                notif_prefs: Default::default(),
                             },
                             behavior_hints: Default::default(),
                         },
@@ -480,6 +486,7 @@ fn test_dismiss_notification() {
                                 video_id: Some("tt1:1".into()),
                                 watched: None,
                                 no_notif: false,
+                                notif_prefs: Default::default(),
                             },
                             behavior_hints: Default::default(),
                         },
@@ -503,6 +510,7 @@ fn test_dismiss_notification() {
                 ),
                 SearchHistoryBucket::default(),
                 DismissedEventsBucket::default(),
+                IntroOutroBucket::default(),
             ),
         },
         vec![],
@@ -616,3 +624,225 @@ fn test_dismiss_notification() {
         );
     }
 }
+
+#[test]
+fn test_notification_digests_quiet_hours() {
+    #[derive(Model, Clone, Debug)]
+    #[model(TestEnv)]
+    struct TestModel {
+        ctx: Ctx,
+    }
+
+    let _env_lock = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+
+    let library_item = |id: &str, name: &str| LibraryItem {
+        id: id.to_string(),
+        name: name.to_string(),
+        r#type: "series".to_string(),
+        poster: None,
+        poster_shape: PosterShape::Poster,
+        removed: false,
+        temp: false,
+        ctime: Some(Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap()),
+        mtime: Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap(),
+        state: LibraryItemState {
+            last_watched: Some(Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap()),
+            times_watched: 1,
+            video_id: Some(format!("{id}:1:1")),
+            ..Default::default()
+        },
+        behavior_hints: Default::default(),
+    };
+    let notification_item = |meta_id: &str, video_id: &str, hour: u32| NotificationItem {
+        meta_id: meta_id.to_string(),
+        video_id: video_id.to_string(),
+        video_released: Utc.with_ymd_and_hms(2024, 1, 1, hour, 0, 0).unwrap(),
+    };
+    let mut notifications = NotificationsBucket::new::<TestEnv>(
+        None,
+        vec![
+            notification_item("tt1", "tt1:1:3", 21),
+            notification_item("tt1", "tt1:1:2", 20),
+            notification_item("tt2", "tt2:1:2", 18),
+            // released before the bucket was created
+            notification_item("tt3", "tt3:1:2", 1),
+        ],
+    );
+    notifications.created = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+
+    let (runtime, rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx::new(
+                Profile {
+                    settings: Settings {
+                        // 22:00 - 08:00 in UTC+2
+                        notifications_quiet_hours: Some(QuietHours {
+                            start: 22 * 60,
+                            end: 8 * 60,
+                            utc_offset: 2 * 60,
+                        }),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                LibraryBucket::new(
+                    None,
+                    vec![
+                        library_item("tt1", "Item 1"),
+                        library_item("tt2", "Item 2"),
+                        library_item("tt3", "Item 3"),
+                    ],
+                ),
+                StreamsBucket::default(),
+                notifications,
+                SearchHistoryBucket::default(),
+                DismissedEventsBucket::default(),
+                IntroOutroBucket::default(),
+            ),
+        },
+        vec![],
+        1000,
+    );
+    let runtime = Arc::new(RwLock::new(runtime));
+    // 23:30 local time
+    *NOW.write().unwrap() = Utc.with_ymd_and_hms(2024, 1, 1, 21, 30, 0).unwrap();
+
+    TestEnv::run_with_runtime(
+        rx,
+        runtime.clone(),
+        enclose!((runtime) move || {
+            let runtime = runtime.read().unwrap();
+            runtime.dispatch(RuntimeAction {
+                field: None,
+                action: Action::Ctx(ActionCtx::PullNotifications),
+            });
+            assert_eq!(
+                runtime.model().unwrap().ctx.notifications.last_delivered,
+                None,
+                "Digests should not be delivered during the quiet hours"
+            );
+
+            // 08:30 local time
+            *NOW.write().unwrap() = Utc.with_ymd_and_hms(2024, 1, 2, 6, 30, 0).unwrap();
+            runtime.dispatch(RuntimeAction {
+                field: None,
+                action: Action::Ctx(ActionCtx::PullNotifications),
+            });
+            runtime.dispatch(RuntimeAction {
+                field: None,
+                action: Action::Ctx(ActionCtx::PullNotifications),
+            });
+        }),
+    );
+
+    assert_eq!(
+        runtime
+            .read()
+            .unwrap()
+            .model()
+            .unwrap()
+            .ctx
+            .notifications
+            .last_delivered,
+        Some(Utc.with_ymd_and_hms(2024, 1, 2, 6, 30, 0).unwrap())
+    );
+    assert_eq!(
+        runtime
+            .read()
+            .unwrap()
+            .model()
+            .unwrap()
+            .ctx
+            .notifications
+            .delivered,
+        HashMap::from([
+            (
+                "tt1".to_owned(),
+                HashSet::from(["tt1:1:2".to_owned(), "tt1:1:3".to_owned()])
+            ),
+            ("tt2".to_owned(), HashSet::from(["tt2:1:2".to_owned()])),
+        ]),
+        "Delivered videos should be tracked"
+    );
+
+    let events = EVENTS.read().unwrap();
+    let digests = events
+        .iter()
+        .filter_map(|event| {
+            match event
+                .downcast_ref::<RuntimeEvent<TestEnv, TestModel>>()
+                .unwrap()
+            {
+                RuntimeEvent::CoreEvent(Event::NotificationDigestsDue { digests }) => Some(digests),
+                _ => None,
+            }
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(digests.len(), 1, "Digests should be delivered only once");
+
+    let digests = digests[0];
+    assert_eq!(digests.len(), 2, "Should have a digest for tt1 and tt2");
+    assert_eq!(digests[0].meta_id, "tt1");
+    assert_eq!(digests[0].name, "Item 1");
+    assert_eq!(
+        digests[0]
+            .items
+            .iter()
+            .map(|item| item.video_id.as_str())
+            .collect::<Vec<_>>(),
+        vec!["tt1:1:2", "tt1:1:3"],
+        "Videos should be ordered by release date"
+    );
+    assert_eq!(digests[1].meta_id, "tt2");
+}
+
+#[test]
+fn test_notification_digests_late_pull() {
+    let _env_lock = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+
+    let notification_item = |video_id: &str, hour: u32| NotificationItem {
+        meta_id: "tt1".to_string(),
+        video_id: video_id.to_string(),
+        video_released: Utc.with_ymd_and_hms(2024, 1, 1, hour, 0, 0).unwrap(),
+    };
+    let mut notifications = NotificationsBucket::new::<TestEnv>(
+        None,
+        vec![
+            notification_item("tt1:1:2", 14),
+            // pulled after the last delivery although it was released before it
+            notification_item("tt1:1:3", 16),
+        ],
+    );
+    notifications.created = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+    notifications.last_delivered = Some(Utc.with_ymd_and_hms(2024, 1, 1, 18, 0, 0).unwrap());
+    notifications.delivered =
+        HashMap::from([("tt1".to_owned(), HashSet::from(["tt1:1:2".to_owned()]))]);
+    let library = LibraryBucket::new(
+        None,
+        vec![LibraryItem {
+            id: "tt1".to_string(),
+            name: "Item 1".to_string(),
+            r#type: "series".to_string(),
+            poster: None,
+            poster_shape: PosterShape::Poster,
+            removed: false,
+            temp: false,
+            ctime: Some(Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap()),
+            mtime: Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap(),
+            state: LibraryItemState::default(),
+            behavior_hints: Default::default(),
+        }],
+    );
+
+    let digests = NotificationDigest::pending(&notifications, &library);
+    assert_eq!(digests.len(), 1, "Should have a digest for tt1");
+    assert_eq!(
+        digests[0]
+            .items
+            .iter()
+            .map(|item| item.video_id.as_str())
+            .collect::<Vec<_>>(),
+        vec!["tt1:1:3"],
+        "Only the video which was not delivered yet should be included"
+    );
+}
@@ -11,9 +11,9 @@ use crate::{
         Env, Runtime, RuntimeAction,
     },
     types::{
-        addon::ExtraValue, events::DismissedEventsBucket, library::LibraryBucket,
-        notifications::NotificationsBucket, profile::Profile, search_history::SearchHistoryBucket,
-        streams::StreamsBucket,
+        addon::ExtraValue, events::DismissedEventsBucket, intro_outro::IntroOutroBucket,
+        library::LibraryBucket, notifications::NotificationsBucket, profile::Profile,
+        search_history::SearchHistoryBucket, streams::StreamsBucket,
     },
     unit_tests::{TestEnv, STORAGE},
 };
@@ -36,6 +36,7 @@ fn test_search_history_update() {
         NotificationsBucket::new::<TestEnv>(None, vec![]),
         SearchHistoryBucket::default(),
         DismissedEventsBucket::default(),
+        IntroOutroBucket::default(),
     );
 
     let catalogs_with_extra = CatalogsWithExtra::default();
@@ -110,6 +111,7 @@ fn test_search_history_clear_items() {
         NotificationsBucket::new::<TestEnv>(None, vec![]),
         SearchHistoryBucket::default(),
         DismissedEventsBucket::default(),
+        IntroOutroBucket::default(),
     );
 
     let catalogs_with_extra = CatalogsWithExtra::default();
@@ -3,9 +3,10 @@ use crate::models::ctx::Ctx;
 use crate::runtime::msg::{Action, ActionCtx};
 use crate::runtime::{Runtime, RuntimeAction};
 use crate::types::events::DismissedEventsBucket;
+use crate::types::intro_outro::IntroOutroBucket;
 use crate::types::library::LibraryBucket;
 use crate::types::notifications::NotificationsBucket;
-use crate::types::profile::{Profile, Settings};
+use crate::types::profile::{Profile, QuietHours, Settings};
 use crate::types::search_history::SearchHistoryBucket;
 use crate::types::streams::StreamsBucket;
 use crate::unit_tests::{TestEnv, REQUESTS, STORAGE};
@@ -31,6 +32,7 @@ fn actionctx_updatesettings() {
         NotificationsBucket::new::<TestEnv>(None, vec![]),
         SearchHistoryBucket::default(),
         DismissedEventsBucket::default(),
+        IntroOutroBucket::default(),
     );
     let (runtime, _rx) = Runtime::<TestEnv, _>::new(TestModel { ctx }, vec![], 1000);
     TestEnv::run(|| {
@@ -90,6 +92,7 @@ fn actionctx_updatesettings_not_changed() {
                 NotificationsBucket::new::<TestEnv>(None, vec![]),
                 SearchHistoryBucket::default(),
                 DismissedEventsBucket::default(),
+                IntroOutroBucket::default(),
             ),
         },
         vec![],
@@ -121,3 +124,54 @@ fn actionctx_updatesettings_not_changed() {
         "No requests have been sent"
     );
 }
+
+#[test]
+fn actionctx_updatesettings_invalid_quiet_hours() {
+    #[derive(Model, Clone, Default)]
+    #[model(TestEnv)]
+    struct TestModel {
+        ctx: Ctx,
+    }
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    let ctx = Ctx::new(
+        Profile::default(),
+        LibraryBucket::default(),
+        StreamsBucket::default(),
+        NotificationsBucket::new::<TestEnv>(None, vec![]),
+        SearchHistoryBucket::default(),
+        DismissedEventsBucket::default(),
+        IntroOutroBucket::default(),
+    );
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(TestModel { ctx }, vec![], 1000);
+    for quiet_hours in [
+        QuietHours {
+            start: 24 * 60,
+            end: 8 * 60,
+            utc_offset: 0,
+        },
+        QuietHours {
+            start: 22 * 60,
+            end: 8 * 60,
+            utc_offset: 15 * 60,
+        },
+    ] {
+        TestEnv::run(|| {
+            runtime.dispatch(RuntimeAction {
+                field: None,
+                action: Action::Ctx(ActionCtx::UpdateSettings(Settings {
+                    notifications_quiet_hours: Some(quiet_hours),
+                    ..Settings::default()
+                })),
+            })
+        });
+    }
+    assert_eq!(
+        runtime.model().unwrap().ctx.profile.settings,
+        Settings::default(),
+        "Settings not updated in memory"
+    );
+    assert!(
+        STORAGE.read().unwrap().get(PROFILE_STORAGE_KEY).is_none(),
+        "Settings not updated in storage"
+    );
+}
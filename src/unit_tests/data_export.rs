@@ -1,18 +1,21 @@
+use crate::constants::{PROFILE_STORAGE_KEY, SCHEMA_VERSION, SCHEMA_VERSION_STORAGE_KEY};
 use crate::models::common::Loadable;
 use crate::models::ctx::Ctx;
 use crate::models::data_export::DataExport;
-use crate::runtime::msg::{Action, ActionLoad};
-use crate::runtime::{EnvFutureExt, Runtime, RuntimeAction, RuntimeEvent, TryEnvFuture};
+use crate::runtime::msg::{Action, ActionCtx, ActionDataExport, ActionLoad};
+use crate::runtime::{Env, EnvFutureExt, Runtime, RuntimeAction, RuntimeEvent, TryEnvFuture};
 use crate::types::api::{APIResult, DataExportResponse};
+use crate::types::data_archive::DataArchive;
 use crate::types::events::DismissedEventsBucket;
-use crate::types::library::LibraryBucket;
+use crate::types::intro_outro::IntroOutroBucket;
+use crate::types::library::{LibraryBucket, LibraryItem};
 use crate::types::notifications::NotificationsBucket;
 use crate::types::profile::Profile;
 use crate::types::profile::{Auth, AuthKey, User};
 use crate::types::search_history::SearchHistoryBucket;
 use crate::types::streams::StreamsBucket;
 use crate::unit_tests::{
-    default_fetch_handler, Request, TestEnv, EVENTS, FETCH_HANDLER, REQUESTS, STATES,
+    default_fetch_handler, Request, TestEnv, EVENTS, FETCH_HANDLER, REQUESTS, STATES, STORAGE,
 };
 use assert_matches::assert_matches;
 use enclose::enclose;
@@ -58,6 +61,7 @@ fn data_export_with_user() {
         NotificationsBucket::new::<TestEnv>(None, vec![]),
         SearchHistoryBucket::default(),
         DismissedEventsBucket::default(),
+        IntroOutroBucket::default(),
     );
     ctx.profile.auth = Some(Auth {
         key: AuthKey("user_key".into()),
@@ -140,6 +144,7 @@ fn data_export_without_a_user() {
         NotificationsBucket::new::<TestEnv>(None, vec![]),
         SearchHistoryBucket::default(),
         DismissedEventsBucket::default(),
+        IntroOutroBucket::default(),
     );
 
     assert!(
@@ -176,3 +181,231 @@ fn data_export_without_a_user() {
     let requests = REQUESTS.read().unwrap();
     assert!(requests.is_empty());
 }
+
+fn library_item() -> LibraryItem {
+    LibraryItem {
+        id: "tt1".to_owned(),
+        name: "Movie".to_owned(),
+        r#type: "movie".to_owned(),
+        poster: None,
+        poster_shape: Default::default(),
+        removed: false,
+        temp: false,
+        ctime: Some(TestEnv::now()),
+        mtime: TestEnv::now(),
+        state: Default::default(),
+        behavior_hints: Default::default(),
+    }
+}
+
+#[test]
+fn data_archive_without_a_user() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    let ctx = Ctx::new(
+        Profile::default(),
+        LibraryBucket::new(None, vec![library_item()]),
+        StreamsBucket::default(),
+        NotificationsBucket::new::<TestEnv>(None, vec![]),
+        SearchHistoryBucket::default(),
+        DismissedEventsBucket::default(),
+        IntroOutroBucket::default(),
+    );
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx,
+            data_export: DataExport::default(),
+        },
+        vec![],
+        1000,
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::DataExport(ActionDataExport::CreateArchive),
+        })
+    });
+    let model = runtime.model().unwrap();
+    let (uid, archive) = model
+        .data_export
+        .archive
+        .as_ref()
+        .expect("Archive should be created without a user");
+    assert!(uid.is_none());
+    assert_eq!(archive.schema_version, SCHEMA_VERSION);
+    assert_eq!(archive.created, TestEnv::now());
+    assert_eq!(
+        archive.library["items"]["tt1"]["name"],
+        serde_json::Value::from("Movie")
+    );
+    assert!(
+        REQUESTS.read().unwrap().is_empty(),
+        "No request should be made"
+    );
+}
+
+#[test]
+fn import_data_archive_with_older_schema() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    let mut profile = serde_json::to_value(Profile::default()).unwrap();
+    profile["settings"]
+        .as_object_mut()
+        .unwrap()
+        .remove("subtitlesOpacity");
+    let library = LibraryBucket::new(None, vec![library_item()]);
+    let archive = DataArchive {
+        schema_version: 13,
+        created: TestEnv::now(),
+        profile,
+        library: serde_json::to_value(library).unwrap(),
+        streams: serde_json::to_value(StreamsBucket::default()).unwrap(),
+        search_history: serde_json::to_value(SearchHistoryBucket::default()).unwrap(),
+        notifications: serde_json::to_value(NotificationsBucket::new::<TestEnv>(None, vec![]))
+            .unwrap(),
+        dismissed_events: serde_json::to_value(DismissedEventsBucket::default()).unwrap(),
+    };
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx::default(),
+            data_export: DataExport::default(),
+        },
+        vec![],
+        1000,
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Ctx(ActionCtx::ImportDataArchive(archive)),
+        })
+    });
+    let model = runtime.model().unwrap();
+    assert_eq!(
+        model.ctx.profile.settings.subtitles_opacity, 100,
+        "Archive should be migrated to the current schema"
+    );
+    assert!(model.ctx.library.items.contains_key("tt1"));
+    assert_eq!(
+        STORAGE
+            .read()
+            .unwrap()
+            .get(SCHEMA_VERSION_STORAGE_KEY)
+            .cloned(),
+        Some(SCHEMA_VERSION.to_string())
+    );
+}
+
+#[test]
+fn import_data_archive_with_newer_schema() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    let archive = DataArchive {
+        schema_version: SCHEMA_VERSION + 1,
+        created: TestEnv::now(),
+        profile: serde_json::to_value(Profile::default()).unwrap(),
+        library: serde_json::to_value(LibraryBucket::default()).unwrap(),
+        streams: serde_json::to_value(StreamsBucket::default()).unwrap(),
+        search_history: serde_json::to_value(SearchHistoryBucket::default()).unwrap(),
+        notifications: serde_json::to_value(NotificationsBucket::new::<TestEnv>(None, vec![]))
+            .unwrap(),
+        dismissed_events: serde_json::to_value(DismissedEventsBucket::default()).unwrap(),
+    };
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx::default(),
+            data_export: DataExport::default(),
+        },
+        vec![],
+        1000,
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Ctx(ActionCtx::ImportDataArchive(archive)),
+        })
+    });
+    assert!(
+        STORAGE.read().unwrap().get(PROFILE_STORAGE_KEY).is_none(),
+        "Storage should not be modified"
+    );
+}
+
+#[test]
+fn data_archive_keeps_the_current_auth() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    let auth = |id: &str| Auth {
+        key: AuthKey(format!("{id}_key")),
+        user: User {
+            id: id.to_owned(),
+            ..Default::default()
+        },
+    };
+    let mut ctx = Ctx::new(
+        Profile::default(),
+        LibraryBucket::new(Some("user".to_owned()), vec![library_item()]),
+        StreamsBucket::default(),
+        NotificationsBucket::new::<TestEnv>(None, vec![]),
+        SearchHistoryBucket::default(),
+        DismissedEventsBucket::default(),
+        IntroOutroBucket::default(),
+    );
+    ctx.profile.auth = Some(auth("user"));
+    ctx.profile.settings.subtitles_size = 150;
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx,
+            data_export: DataExport::default(),
+        },
+        vec![],
+        1000,
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::DataExport(ActionDataExport::CreateArchive),
+        })
+    });
+    let (_, archive) = runtime
+        .model()
+        .unwrap()
+        .data_export
+        .archive
+        .to_owned()
+        .expect("Archive should be created");
+    assert!(
+        archive.profile["auth"].is_null(),
+        "Auth should not be exported"
+    );
+
+    let mut ctx = Ctx::default();
+    ctx.profile.auth = Some(auth("other_user"));
+    ctx.intro_outro = IntroOutroBucket::new(Some("previous_user".to_owned()));
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx,
+            data_export: DataExport::default(),
+        },
+        vec![],
+        1000,
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Ctx(ActionCtx::ImportDataArchive(archive)),
+        })
+    });
+    let model = runtime.model().unwrap();
+    assert_eq!(
+        model.ctx.profile.auth,
+        Some(auth("other_user")),
+        "Current auth should be kept"
+    );
+    assert_eq!(
+        model.ctx.profile.settings.subtitles_size, 150,
+        "Settings should be restored"
+    );
+    assert_eq!(model.ctx.library.uid, Some("other_user".to_owned()));
+    assert!(model.ctx.library.items.contains_key("tt1"));
+    assert_eq!(
+        model.ctx.intro_outro,
+        IntroOutroBucket::new(Some("other_user".to_owned())),
+        "Intro and outro records should be reset"
+    );
+}
@@ -166,3 +166,37 @@ fn external_player_link_player_frame() {
     assert_eq!(epl.playlist, None);
     assert_eq!(epl.file_name, None);
 }
+
+#[test]
+fn external_player_link_fallback_server() {
+    let info_hash = [
+        0xdd, 0x82, 0x55, 0xec, 0xdc, 0x7c, 0xa5, 0x5f, 0xb0, 0xbb, 0xf8, 0x13, 0x23, 0xd8, 0x70,
+        0x62, 0xdb, 0x1f, 0x6d, 0x1c,
+    ];
+    let stream = Stream {
+        source: StreamSource::Torrent {
+            info_hash,
+            file_idx: Some(0),
+            announce: vec![],
+        },
+        name: None,
+        description: None,
+        thumbnail: None,
+        subtitles: vec![],
+        behavior_hints: Default::default(),
+    };
+    let streaming_server_url = Some(Url::parse("http://192.168.0.10:11470").unwrap());
+    let settings = Settings::default();
+    let epl = ExternalPlayerLink::try_from((&stream, &streaming_server_url, &settings)).unwrap();
+    assert_eq!(
+        epl.playlist,
+        Some(format!(
+            "data:application/octet-stream;charset=utf-8;base64,{}",
+            BASE64.encode(format!(
+                "#EXTM3U\n#EXTINF:0\nhttp://192.168.0.10:11470/{}/0",
+                hex::encode(info_hash)
+            ))
+        )),
+        "The server in use is streaming instead of the one in the settings"
+    );
+}
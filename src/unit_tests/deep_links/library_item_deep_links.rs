@@ -77,6 +77,7 @@ fn library_item_deep_links_no_video() {
             video_id: None,
             watched: None,
             no_notif: true,
+            notif_prefs: Default::default(),
         },
         behavior_hints: Default::default(),
     };
@@ -126,6 +127,7 @@ fn library_item_deep_links_state_video_id_no_time_offset_infuse_player() {
             video_id: Some("tt13622776:1:5".to_string()),
             watched: None,
             no_notif: true,
+            notif_prefs: Default::default(),
         },
         behavior_hints: Default::default(),
     };
@@ -180,6 +182,7 @@ fn library_item_deep_links_state_video_id() {
             video_id: Some("tt13622776:1:5".to_string()),
             watched: None,
             no_notif: true,
+            notif_prefs: Default::default(),
         },
         behavior_hints: Default::default(),
     };
@@ -232,6 +235,7 @@ fn library_item_deep_links_behavior_hints_default_video_id() {
             video_id: None,
             watched: None,
             no_notif: true,
+            notif_prefs: Default::default(),
         },
         behavior_hints: MetaItemBehaviorHints {
             default_video_id: Some("tt13622776:1:5".to_string()),
@@ -289,6 +293,7 @@ fn library_item_deep_links_state_and_behavior_hints_default_video_id() {
             video_id: Some("video_id".to_string()),
             watched: None,
             no_notif: true,
+            notif_prefs: Default::default(),
         },
         behavior_hints: MetaItemBehaviorHints {
             default_video_id: Some("bh_video_id".to_string()),
@@ -346,6 +351,7 @@ fn library_item_deep_links_state_no_time_offset_and_behavior_hints_default_video
             video_id: Some("video_id".to_string()),
             watched: None,
             no_notif: true,
+            notif_prefs: Default::default(),
         },
         behavior_hints: MetaItemBehaviorHints {
             default_video_id: Some("bh_video_id".to_string()),
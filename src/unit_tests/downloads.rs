@@ -0,0 +1,258 @@
+use std::any::Any;
+
+use futures::future;
+use stremio_derive::Model;
+use url::Url;
+
+use crate::constants::DOWNLOADS_STORAGE_KEY;
+use crate::models::ctx::Ctx;
+use crate::models::downloads::Downloads;
+use crate::runtime::msg::{Action, ActionDownloads};
+use crate::runtime::{EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture};
+use crate::types::downloads::{DownloadState, DownloadsBucket};
+use crate::types::resource::{Stream, StreamSource};
+use crate::types::streaming_server::{
+    ActiveServer, Growler, Options, PeerSearch, Statistics, SwarmCap,
+};
+use crate::unit_tests::{
+    default_fetch_handler, Request, TestEnv, FETCH_HANDLER, REQUESTS, STORAGE,
+};
+
+const INFO_HASH: &str = "6d0a1e5b7e0ef5c2d7ae8fbee3e1fa6b6c6fd16e";
+const MISSING_INFO_HASH: &str = "0b1b6a0e8f5e9de0c8f3b3c8e0e4c1d7a6f2b9e4";
+
+#[derive(Model, Clone, Debug)]
+#[model(TestEnv)]
+struct TestModel {
+    ctx: Ctx,
+    downloads: Downloads,
+}
+
+fn stream(source: StreamSource) -> Stream {
+    Stream {
+        source,
+        name: Some("Stream".to_owned()),
+        description: None,
+        thumbnail: None,
+        subtitles: vec![],
+        behavior_hints: Default::default(),
+    }
+}
+
+fn statistics(stream_progress: f64) -> Statistics {
+    Statistics {
+        name: "Movie".to_owned(),
+        info_hash: INFO_HASH.to_owned(),
+        files: vec![],
+        sources: vec![],
+        opts: Options {
+            connections: 0,
+            dht: true,
+            growler: Growler { flood: 0, pulse: 0 },
+            handshake_timeout: 0,
+            path: String::new(),
+            peer_search: PeerSearch {
+                max: 0,
+                min: 0,
+                sources: vec![],
+            },
+            swarm_cap: SwarmCap {
+                max_speed: 0.0,
+                min_peers: 0,
+            },
+            timeout: 0,
+            tracker: true,
+            r#virtual: true,
+        },
+        download_speed: 0.0,
+        upload_speed: 0.0,
+        downloaded: 0,
+        uploaded: 0,
+        unchoked: 0,
+        peers: 0,
+        queued: 0,
+        unique: 0,
+        connection_tries: 0,
+        peer_search_running: false,
+        stream_len: 0,
+        stream_name: String::new(),
+        stream_progress,
+        swarm_connections: 0,
+        swarm_paused: false,
+        swarm_size: 0,
+    }
+}
+
+fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+    match request {
+        Request { url, method, .. }
+            if url == "http://127.0.0.1:11470/download" && method == "POST" =>
+        {
+            future::ok(Box::new(serde_json::Value::Null) as Box<dyn Any + Send>).boxed_env()
+        }
+        Request { url, .. }
+            if url == format!("http://127.0.0.1:11470/{INFO_HASH}/1/stats.json") =>
+        {
+            future::ok(Box::new(Some(statistics(1.0))) as Box<dyn Any + Send>).boxed_env()
+        }
+        Request { url, .. }
+            if url == format!("http://127.0.0.1:11470/{MISSING_INFO_HASH}/1/stats.json") =>
+        {
+            future::ok(Box::new(Option::<Statistics>::None) as Box<dyn Any + Send>).boxed_env()
+        }
+        _ => default_fetch_handler(request),
+    }
+}
+
+#[test]
+fn downloads_queue() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+    let torrent_stream = stream(StreamSource::Torrent {
+        info_hash: hex::decode(INFO_HASH).unwrap().try_into().unwrap(),
+        file_idx: Some(1),
+        announce: vec![],
+    });
+    let url_stream = stream(StreamSource::Url {
+        url: Url::parse("https://example.com/video.mp4").unwrap(),
+    });
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx::default(),
+            downloads: Downloads::default(),
+        },
+        vec![],
+        1000,
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Downloads(ActionDownloads::Add {
+                stream: torrent_stream,
+                library_item_id: Some("tt1".to_owned()),
+                video_id: Some("tt1:1:1".to_owned()),
+            }),
+        });
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Downloads(ActionDownloads::Add {
+                stream: url_stream,
+                library_item_id: None,
+                video_id: None,
+            }),
+        });
+    });
+    assert_eq!(
+        runtime
+            .model()
+            .unwrap()
+            .downloads
+            .bucket
+            .items
+            .iter()
+            .map(|item| item.state.to_owned())
+            .collect::<Vec<_>>(),
+        vec![
+            DownloadState::Downloading {
+                progress: 0.0,
+                downloaded: 0,
+                download_speed: 0.0,
+            },
+            DownloadState::Queued,
+        ],
+        "Only the first download should be started"
+    );
+    assert_eq!(REQUESTS.read().unwrap().len(), 1);
+    assert_eq!(
+        REQUESTS.read().unwrap()[0].body,
+        format!(
+            r#"{{"id":"{INFO_HASH}/1","torrent":{{"infoHash":"{INFO_HASH}","fileIdx":1,"announce":[]}}}}"#
+        )
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Downloads(ActionDownloads::Refresh),
+        })
+    });
+    let downloads = &runtime.model().unwrap().downloads;
+    assert_eq!(
+        downloads.bucket.items[0].state,
+        DownloadState::Completed,
+        "The torrent should be completed"
+    );
+    assert_eq!(
+        downloads.bucket.items[0].streaming_server_url,
+        Some(Url::parse("http://127.0.0.1:11470").unwrap()),
+        "The server of the download should be recorded"
+    );
+    let ctx = Ctx {
+        streaming_server: ActiveServer::new(Url::parse("http://192.168.0.2:11470").unwrap()),
+        ..Default::default()
+    };
+    assert_eq!(
+        downloads.local_stream(&downloads.bucket.items[0].id, &ctx),
+        Some(stream(StreamSource::Url {
+            url: Url::parse(&format!("http://127.0.0.1:11470/{INFO_HASH}/1")).unwrap(),
+        })),
+        "The torrent should be played with a local stream from the server it was downloaded on"
+    );
+    assert!(
+        downloads.bucket.items[1].is_active(),
+        "The next download should be started"
+    );
+    assert_eq!(
+        REQUESTS.read().unwrap().last().unwrap().body,
+        format!(
+            r#"{{"id":"{}","url":"https://example.com/video.mp4"}}"#,
+            downloads.bucket.items[1].id
+        )
+    );
+    assert_eq!(
+        serde_json::from_str::<DownloadsBucket>(
+            STORAGE.read().unwrap().get(DOWNLOADS_STORAGE_KEY).unwrap()
+        )
+        .unwrap(),
+        downloads.bucket,
+        "Downloads should be persisted"
+    );
+}
+
+#[test]
+fn downloads_missing_on_server() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx::default(),
+            downloads: Downloads::default(),
+        },
+        vec![],
+        1000,
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Downloads(ActionDownloads::Add {
+                stream: stream(StreamSource::Torrent {
+                    info_hash: hex::decode(MISSING_INFO_HASH).unwrap().try_into().unwrap(),
+                    file_idx: Some(1),
+                    announce: vec![],
+                }),
+                library_item_id: None,
+                video_id: None,
+            }),
+        });
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Downloads(ActionDownloads::Refresh),
+        });
+    });
+    assert!(
+        matches!(
+            runtime.model().unwrap().downloads.bucket.items[0].state,
+            DownloadState::Failed { .. }
+        ),
+        "The download should fail when the server has no statistics for it"
+    );
+}
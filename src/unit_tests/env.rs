@@ -1,6 +1,7 @@
 use crate::models::ctx::Ctx;
 use crate::models::streaming_server::StreamingServer;
 use crate::runtime::{Env, EnvFuture, EnvFutureExt, Model, Runtime, RuntimeEvent, TryEnvFuture};
+use crate::types::resource::PlaybackCapabilities;
 use chrono::{DateTime, Utc};
 use enclose::enclose;
 use futures::channel::mpsc::Receiver;
@@ -22,6 +23,7 @@ lazy_static! {
     pub static ref EVENTS: RwLock<Vec<Box<dyn Any + Send + Sync + 'static>>> = Default::default();
     pub static ref STATES: RwLock<Vec<Box<dyn Any + Send + Sync + 'static>>> = Default::default();
     pub static ref NOW: RwLock<DateTime<Utc>> = RwLock::new(Utc::now());
+    pub static ref PLAYBACK_CAPABILITIES: RwLock<Option<PlaybackCapabilities>> = Default::default();
     pub static ref ENV_MUTEX: Mutex<()> = Default::default();
 }
 
@@ -64,6 +66,7 @@ impl TestEnv {
         *EVENTS.write().unwrap() = vec![];
         *STATES.write().unwrap() = vec![];
         *NOW.write().unwrap() = Utc::now();
+        *PLAYBACK_CAPABILITIES.write().unwrap() = None;
         env_mutex
     }
     pub fn run<F: FnOnce()>(runnable: F) {
@@ -117,6 +120,9 @@ impl Env for TestEnv {
             })
             .boxed_env()
     }
+    fn fetch_bytes(request: http::Request<()>) -> TryEnvFuture<Vec<u8>> {
+        Self::fetch(request)
+    }
     fn get_storage<T: for<'de> Deserialize<'de> + 'static>(key: &str) -> TryEnvFuture<Option<T>> {
         future::ok(
             STORAGE
@@ -157,6 +163,9 @@ impl Env for TestEnv {
     fn log(message: String) {
         println!("{message}")
     }
+    fn playback_capabilities() -> Option<PlaybackCapabilities> {
+        PLAYBACK_CAPABILITIES.read().unwrap().to_owned()
+    }
 }
 
 pub fn default_fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
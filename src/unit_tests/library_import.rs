@@ -0,0 +1,191 @@
+use crate::constants::{CINEMETA_URL, OFFICIAL_ADDONS};
+use crate::models::common::Loadable;
+use crate::models::ctx::Ctx;
+use crate::models::library_import::LibraryImport;
+use crate::runtime::msg::{Action, ActionLibraryImport};
+use crate::runtime::{EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture};
+use crate::types::addon::ResourceResponse;
+use crate::types::library::{
+    parse_library_import, LibraryImportError, LibraryImportSource, LibraryItemState,
+};
+use crate::types::profile::Profile;
+use crate::types::resource::{MetaItem, MetaItemPreview, SeriesInfo, Video};
+use crate::unit_tests::{default_fetch_handler, Request, TestEnv, FETCH_HANDLER};
+use chrono::{TimeZone, Utc};
+use futures::future;
+use std::any::Any;
+use stremio_derive::Model;
+
+#[test]
+fn parse_imdb_ratings() {
+    let content = "\u{feff}Const,Your Rating,Date Rated,Title,URL,Title Type,IMDb Rating,Runtime (mins),Year\r\n\
+        tt0111161,10,2023-01-15,\"Shawshank Redemption, The\",https://www.imdb.com/title/tt0111161,movie,9.3,142,1994\r\n\
+        tt0903747,9,2023-02-01,Breaking Bad,https://www.imdb.com/title/tt0903747,tvSeries,9.5,49,2008\r\n\
+        tt0959621,8,2023-02-02,Pilot,https://www.imdb.com/title/tt0959621,tvEpisode,9.0,58,2008\r\n";
+    let entries = parse_library_import(LibraryImportSource::IMDbRatings, content).unwrap();
+    assert_eq!(entries.len(), 2, "Episodes should be skipped");
+    assert_eq!(entries[0].name, "Shawshank Redemption, The");
+    assert_eq!(entries[0].r#type, "movie");
+    assert_eq!(entries[0].year, Some(1994));
+    assert_eq!(entries[0].imdb_id.as_deref(), Some("tt0111161"));
+    assert!(entries[0].watched);
+    assert_eq!(
+        entries[0].last_watched,
+        Some(Utc.with_ymd_and_hms(2023, 1, 15, 0, 0, 0).unwrap())
+    );
+    assert_eq!(entries[1].r#type, "series");
+}
+
+#[test]
+fn parse_letterboxd_watchlist_missing_column() {
+    let content = "Date,Name,Letterboxd URI\n2023-01-01,Heat,https://boxd.it/1\n";
+    assert_eq!(
+        parse_library_import(LibraryImportSource::LetterboxdWatchlist, content),
+        Err(LibraryImportError::MissingColumn("Year".to_owned()))
+    );
+}
+
+#[test]
+fn import_trakt_history() {
+    #[derive(Model, Default, Clone, Debug)]
+    #[model(TestEnv)]
+    struct TestModel {
+        ctx: Ctx,
+        library_import: LibraryImport,
+    }
+    fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+        match request {
+            Request { url, .. } if url == "https://v3-cinemeta.strem.io/meta/series/tt2.json" => {
+                future::ok(Box::new(ResourceResponse::Meta {
+                    meta: MetaItem {
+                        preview: MetaItemPreview {
+                            id: "tt2".to_owned(),
+                            r#type: "series".to_owned(),
+                            name: "Series".to_owned(),
+                            ..Default::default()
+                        },
+                        videos: (1..=3)
+                            .map(|episode| Video {
+                                id: format!("tt2:1:{episode}"),
+                                series_info: Some(SeriesInfo { season: 1, episode }),
+                                ..Default::default()
+                            })
+                            .collect(),
+                    },
+                }) as Box<dyn Any + Send>)
+                .boxed_env()
+            }
+            _ => default_fetch_handler(request),
+        }
+    }
+    let content = r#"[
+        {
+            "id": 1,
+            "watched_at": "2023-01-01T20:00:00.000Z",
+            "action": "watch",
+            "type": "episode",
+            "episode": { "season": 1, "number": 1, "title": "Pilot" },
+            "show": { "title": "Series", "year": 2020, "ids": { "imdb": "tt2" } }
+        },
+        {
+            "id": 2,
+            "watched_at": "2023-01-02T20:00:00.000Z",
+            "action": "watch",
+            "type": "episode",
+            "episode": { "season": 1, "number": 2, "title": "Second" },
+            "show": { "title": "Series", "year": 2020, "ids": { "imdb": "tt2" } }
+        }
+    ]"#;
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                profile: Profile {
+                    addons: OFFICIAL_ADDONS
+                        .iter()
+                        .filter(|addon| addon.transport_url == *CINEMETA_URL)
+                        .cloned()
+                        .collect(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            library_import: Default::default(),
+        },
+        vec![],
+        1000,
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::LibraryImport(ActionLibraryImport::Preview {
+                source: LibraryImportSource::TraktHistory,
+                content: content.to_owned(),
+            }),
+        })
+    });
+    {
+        let model = runtime.model().unwrap();
+        let items = match &model.library_import.items {
+            Some(Loadable::Ready(items)) => items,
+            _ => panic!("Import preview should be ready"),
+        };
+        assert_eq!(items.len(), 1, "Episodes should be merged into one series");
+        assert_eq!(
+            items[0].entry.episodes,
+            vec![
+                SeriesInfo {
+                    season: 1,
+                    episode: 1
+                },
+                SeriesInfo {
+                    season: 1,
+                    episode: 2
+                }
+            ]
+        );
+        let library_item = items[0]
+            .library_item
+            .as_ref()
+            .expect("Series should be resolved through Cinemeta");
+        assert_eq!(library_item.id, "tt2");
+        assert!(!library_item.removed && !library_item.temp);
+        assert_eq!(
+            library_item.state.last_watched,
+            Some(Utc.with_ymd_and_hms(2023, 1, 2, 20, 0, 0).unwrap())
+        );
+        assert_eq!(library_item.state.video_id.as_deref(), Some("tt2:1:2"));
+        assert!(
+            model.ctx.library.items.is_empty(),
+            "Preview should not modify the library"
+        );
+    }
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::LibraryImport(ActionLibraryImport::Commit),
+        })
+    });
+    let model = runtime.model().unwrap();
+    assert!(model.library_import.items.is_none());
+    let library_item = model
+        .ctx
+        .library
+        .items
+        .get("tt2")
+        .expect("Series should be imported into the library");
+    let watched = LibraryItemState::watched_bitfield(
+        &library_item.state,
+        &(1..=3)
+            .map(|episode| Video {
+                id: format!("tt2:1:{episode}"),
+                series_info: Some(SeriesInfo { season: 1, episode }),
+                ..Default::default()
+            })
+            .collect::<Vec<_>>(),
+    );
+    assert!(watched.get_video("tt2:1:1"));
+    assert!(watched.get_video("tt2:1:2"));
+    assert!(!watched.get_video("tt2:1:3"));
+}
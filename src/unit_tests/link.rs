@@ -5,6 +5,7 @@ use crate::runtime::msg::{Action, ActionLink, ActionLoad};
 use crate::runtime::{EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture};
 use crate::types::api::{APIResult, LinkAuthKey, LinkCodeResponse, LinkDataResponse};
 use crate::types::events::DismissedEventsBucket;
+use crate::types::intro_outro::IntroOutroBucket;
 use crate::types::library::LibraryBucket;
 use crate::types::notifications::NotificationsBucket;
 use crate::types::profile::Profile;
@@ -66,6 +67,7 @@ fn create_link_code() {
             NotificationsBucket::new::<TestEnv>(None, vec![]),
             SearchHistoryBucket::default(),
             DismissedEventsBucket::default(),
+            IntroOutroBucket::default(),
         ),
         link: Link::default(),
     };
@@ -1 +1,2 @@
 mod override_selected;
+mod sorted_streams;
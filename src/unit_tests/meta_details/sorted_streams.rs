@@ -0,0 +1,176 @@
+use crate::constants::{META_RESOURCE_NAME, STREAM_RESOURCE_NAME};
+use crate::models::ctx::Ctx;
+use crate::models::meta_details::{HiddenStreamReason, MetaDetails, Selected};
+use crate::runtime::msg::{Action, ActionLoad};
+use crate::runtime::{EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture};
+use crate::types::addon::{
+    Descriptor, Manifest, ManifestBehaviorHints, ManifestResource, ResourcePath, ResourceResponse,
+};
+use crate::types::profile::{Profile, Settings, StreamPreferences};
+use crate::types::resource::{Stream, StreamSource};
+use crate::unit_tests::{default_fetch_handler, Request, TestEnv, FETCH_HANDLER};
+use futures::future;
+use semver::Version;
+use std::any::Any;
+use stremio_derive::Model;
+use url::Url;
+
+fn create_addon(transport_url: &str, p2p: bool) -> Descriptor {
+    Descriptor {
+        manifest: Manifest {
+            id: transport_url.to_owned(),
+            version: Version::new(0, 0, 1),
+            name: "name".to_owned(),
+            contact_email: None,
+            description: None,
+            logo: None,
+            background: None,
+            types: vec!["movie".to_owned()],
+            resources: vec![ManifestResource::Short(STREAM_RESOURCE_NAME.to_owned())],
+            id_prefixes: None,
+            catalogs: vec![],
+            addon_catalogs: vec![],
+            behavior_hints: ManifestBehaviorHints {
+                p2p,
+                ..Default::default()
+            },
+        },
+        transport_url: Url::parse(transport_url).unwrap(),
+        flags: Default::default(),
+    }
+}
+
+fn create_stream(name: &str, description: &str, is_torrent: bool) -> Stream {
+    Stream {
+        source: if is_torrent {
+            StreamSource::Torrent {
+                info_hash: [0; 20],
+                file_idx: None,
+                announce: vec![],
+            }
+        } else {
+            StreamSource::Url {
+                url: "https://source_url".parse().unwrap(),
+            }
+        },
+        name: Some(name.to_owned()),
+        description: Some(description.to_owned()),
+        thumbnail: None,
+        subtitles: vec![],
+        behavior_hints: Default::default(),
+    }
+}
+
+#[test]
+fn sorted_streams() {
+    #[derive(Model, Default, Clone, Debug)]
+    #[model(TestEnv)]
+    struct TestModel {
+        ctx: Ctx,
+        meta_details: MetaDetails,
+    }
+    fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+        match request {
+            Request { url, .. } if url == "https://direct/stream/movie/tt1.json" => {
+                future::ok(Box::new(ResourceResponse::Streams {
+                    streams: vec![
+                        create_stream("A", "Movie.2024.720p.WEB-DL.x264-GRP.mkv 💾 1.1 GB", false),
+                        create_stream("B", "Movie.2024.1080p.BluRay.x264-GRP.mkv 💾 12 GB", false),
+                        create_stream("C", "Movie.2024.1080p.HDCAM.x264.mkv 💾 1.5 GB", false),
+                        create_stream("E", "Movie.2024.1080p.WEB-DL.x265 🇮🇹", true),
+                        create_stream("F", "Movie.2024.1080p.WEB-DL.x265 🇮🇹", false),
+                    ],
+                }) as Box<dyn Any + Send>)
+                .boxed_env()
+            }
+            Request { url, .. } if url == "https://p2p/stream/movie/tt1.json" => {
+                future::ok(Box::new(ResourceResponse::Streams {
+                    streams: vec![create_stream("D", "Movie.2024.1080p.WEB-DL 🇮🇹", true)],
+                }) as Box<dyn Any + Send>)
+                .boxed_env()
+            }
+            _ => default_fetch_handler(request),
+        }
+    }
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                profile: Profile {
+                    addons: vec![
+                        create_addon("https://direct/manifest.json", false),
+                        create_addon("https://p2p/manifest.json", true),
+                    ],
+                    settings: Settings {
+                        stream_preferences: StreamPreferences {
+                            preferred_resolutions: vec![1080, 720],
+                            max_size: Some(10_000_000_000),
+                            exclude_cam: true,
+                            preferred_languages: vec!["ita".to_owned()],
+                            hide_p2p: true,
+                            prefer_direct_urls: true,
+                        },
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            meta_details: Default::default(),
+        },
+        vec![],
+        1000,
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Load(ActionLoad::MetaDetails(Selected {
+                meta_path: ResourcePath {
+                    resource: META_RESOURCE_NAME.to_owned(),
+                    r#type: "movie".to_owned(),
+                    id: "tt1".to_owned(),
+                    extra: vec![],
+                },
+                stream_path: Some(ResourcePath {
+                    resource: STREAM_RESOURCE_NAME.to_owned(),
+                    r#type: "movie".to_owned(),
+                    id: "tt1".to_owned(),
+                    extra: vec![],
+                }),
+                guess_stream: false,
+            })),
+        })
+    });
+    let model = runtime.model().unwrap();
+    let sorted_streams = model
+        .meta_details
+        .sorted_streams
+        .iter()
+        .map(|sorted_stream| {
+            (
+                sorted_stream.stream.name.as_deref().unwrap_or_default(),
+                sorted_stream.hidden_reasons.to_owned(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        sorted_streams,
+        vec![
+            ("F", vec![]),
+            ("E", vec![]),
+            ("A", vec![]),
+            ("D", vec![HiddenStreamReason::P2P]),
+            ("B", vec![HiddenStreamReason::Size]),
+            ("C", vec![HiddenStreamReason::Cam]),
+        ],
+        "The hidden streams should be listed last, the rest by the preferences"
+    );
+    let meta_details = serde_json::to_value(&model.meta_details).unwrap();
+    let stream = &meta_details["streams"][0]["content"]["content"][0];
+    assert_eq!(stream["name"], "A");
+    assert_eq!(
+        stream["metadata"]["resolution"], 720,
+        "The streams should be serialized with their metadata"
+    );
+}
@@ -1,12 +1,18 @@
 mod env;
 pub use env::*;
 
+mod calendar;
+mod cast_session;
 mod catalog_with_filters;
 mod ctx;
 mod data_export;
 mod deep_links;
+mod downloads;
+mod library_import;
 mod link;
 mod meta_details;
 mod player;
 mod serde;
 mod streaming_server;
+mod subtitles_converter;
+mod torrent_details;
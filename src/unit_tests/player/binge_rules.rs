@@ -0,0 +1,228 @@
+use crate::{
+    constants::{META_RESOURCE_NAME, STREAM_RESOURCE_NAME},
+    models::{
+        ctx::Ctx,
+        player::{Player, Selected},
+    },
+    runtime::{
+        msg::{Action, ActionLoad},
+        EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture,
+    },
+    types::{
+        addon::{ResourcePath, ResourceRequest, ResourceResponse},
+        profile::{BingeRules, Profile, Settings},
+        resource::{MetaItem, MetaItemPreview, SeriesInfo, Stream, Video},
+    },
+    unit_tests::{default_fetch_handler, Request, TestEnv, FETCH_HANDLER},
+};
+use chrono::{TimeZone, Utc};
+use futures::future;
+use std::any::Any;
+use stremio_derive::Model;
+
+#[derive(Model, Default, Clone, Debug)]
+#[model(TestEnv)]
+struct TestModel {
+    ctx: Ctx,
+    player: Player,
+}
+
+fn create_video(meta_id: &str, season: u32, episode: u32, released: (i32, u32, u32)) -> Video {
+    let (year, month, day) = released;
+    Video {
+        id: format!("{meta_id}:{season}:{episode}"),
+        title: format!("video_{season}_{episode}"),
+        released: Some(Utc.with_ymd_and_hms(year, month, day, 0, 0, 0).unwrap()),
+        overview: None,
+        thumbnail: None,
+        streams: vec![],
+        series_info: Some(SeriesInfo { season, episode }),
+        trailer_streams: vec![],
+    }
+}
+
+fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+    match request {
+        Request { url, .. } if url == "https://transport_url/meta/series/tt123456.json" => {
+            future::ok(Box::new(ResourceResponse::Meta {
+                meta: MetaItem {
+                    preview: MetaItemPreview {
+                        id: "tt123456".to_owned(),
+                        r#type: "series".to_owned(),
+                        ..Default::default()
+                    },
+                    videos: vec![
+                        create_video("tt123456", 0, 1, (2020, 1, 10)),
+                        create_video("tt123456", 1, 1, (2020, 1, 1)),
+                        create_video("tt123456", 1, 2, (2020, 1, 15)),
+                        create_video("tt123456", 2, 1, (2021, 1, 1)),
+                    ],
+                },
+            }) as Box<dyn Any + Send>)
+            .boxed_env()
+        }
+        // the special is listed in between the episodes
+        Request { url, .. } if url == "https://transport_url/meta/series/tt654321.json" => {
+            future::ok(Box::new(ResourceResponse::Meta {
+                meta: MetaItem {
+                    preview: MetaItemPreview {
+                        id: "tt654321".to_owned(),
+                        r#type: "series".to_owned(),
+                        ..Default::default()
+                    },
+                    videos: vec![
+                        create_video("tt654321", 1, 1, (2020, 1, 1)),
+                        create_video("tt654321", 0, 1, (2020, 1, 10)),
+                        create_video("tt654321", 1, 2, (2020, 1, 15)),
+                        create_video("tt654321", 2, 1, (2021, 1, 1)),
+                    ],
+                },
+            }) as Box<dyn Any + Send>)
+            .boxed_env()
+        }
+        _ => default_fetch_handler(request),
+    }
+}
+
+/// Loads the given video and returns the id of the next one.
+fn next_video_id(video_id: &str, binge_rules: BingeRules) -> Option<String> {
+    let meta_id = video_id
+        .split(':')
+        .next()
+        .expect("Video id should have a meta id");
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                profile: Profile {
+                    settings: Settings {
+                        binge_watching: true,
+                        binge_rules,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            player: Player::default(),
+        },
+        vec![],
+        1000,
+    );
+    let request = |resource: &str, id: &str| ResourceRequest {
+        base: "https://transport_url/manifest.json".parse().unwrap(),
+        path: ResourcePath {
+            resource: resource.to_owned(),
+            r#type: "series".to_owned(),
+            id: id.to_owned(),
+            extra: vec![],
+        },
+    };
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Load(ActionLoad::Player(Box::new(Selected {
+                stream: Stream::youtube("yt_id:1").unwrap(),
+                stream_request: Some(request(STREAM_RESOURCE_NAME, video_id)),
+                meta_request: Some(request(META_RESOURCE_NAME, meta_id)),
+                subtitles_path: None,
+                playback: None,
+            }))),
+        });
+    });
+    let model = runtime.model().unwrap();
+    model
+        .player
+        .next_video
+        .as_ref()
+        .map(|video| video.id.to_owned())
+}
+
+#[test]
+fn binge_rules() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+
+    assert_eq!(
+        next_video_id("tt123456:1:1", BingeRules::default()),
+        Some("tt123456:1:2".to_owned()),
+        "The next episode is played"
+    );
+    assert_eq!(
+        next_video_id(
+            "tt123456:1:1",
+            BingeRules {
+                specials_in_aired_order: true,
+                ..Default::default()
+            }
+        ),
+        Some("tt123456:0:1".to_owned()),
+        "The special released in between the episodes is played next"
+    );
+    assert_eq!(
+        next_video_id(
+            "tt123456:0:1",
+            BingeRules {
+                specials_in_aired_order: true,
+                ..Default::default()
+            }
+        ),
+        Some("tt123456:1:2".to_owned()),
+        "The episodes continue after the special"
+    );
+    assert_eq!(
+        next_video_id("tt123456:1:2", BingeRules::default()),
+        Some("tt123456:2:1".to_owned()),
+        "The next season is played after the last episode"
+    );
+    assert_eq!(
+        next_video_id(
+            "tt123456:1:2",
+            BingeRules {
+                stop_at_season_end: true,
+                ..Default::default()
+            }
+        ),
+        None,
+        "Playback stops at the end of the season"
+    );
+    assert_eq!(
+        next_video_id(
+            "tt123456:1:1",
+            BingeRules {
+                play_next_count: Some(0),
+                ..Default::default()
+            }
+        ),
+        None,
+        "There is no next video once the count is reached"
+    );
+}
+
+#[test]
+fn binge_rules_default() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+
+    // the order before the binge rules: the video following the current one
+    // unless it's a special and the current video isn't
+    assert_eq!(
+        next_video_id("tt654321:1:1", BingeRules::default()),
+        None,
+        "The special following the episode is not played"
+    );
+    assert_eq!(
+        next_video_id("tt654321:0:1", BingeRules::default()),
+        Some("tt654321:1:2".to_owned()),
+        "The episode following the special is played"
+    );
+    assert_eq!(
+        next_video_id("tt654321:1:2", BingeRules::default()),
+        Some("tt654321:2:1".to_owned()),
+        "The next season is played"
+    );
+    assert_eq!(
+        next_video_id("tt654321:2:1", BingeRules::default()),
+        None,
+        "There is no next video after the last one"
+    );
+}
@@ -0,0 +1,244 @@
+use crate::{
+    constants::{META_RESOURCE_NAME, STREAM_RESOURCE_NAME},
+    models::{
+        ctx::Ctx,
+        player::{Player, Selected, VideoChapter, VideoParams},
+    },
+    runtime::{
+        msg::{Action, ActionLoad, ActionPlayer},
+        EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture,
+    },
+    types::{
+        addon::{ResourcePath, ResourceRequest, ResourceResponse},
+        api::SeekLog,
+        intro_outro::IntroOutroBucket,
+        library::{LibraryBucket, LibraryItem, LibraryItemState},
+        player::{IntroData, IntroOutro},
+        resource::{MetaItem, MetaItemPreview, SeriesInfo, Stream, Video},
+    },
+    unit_tests::{default_fetch_handler, Request, TestEnv, FETCH_HANDLER, NOW},
+};
+use chrono::{TimeZone, Utc};
+use futures::future;
+use std::any::Any;
+use stremio_derive::Model;
+
+#[derive(Model, Default, Clone, Debug)]
+#[model(TestEnv)]
+struct TestModel {
+    ctx: Ctx,
+    player: Player,
+}
+
+fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+    match request {
+        Request { url, .. } if url == "https://transport_url/meta/series/tt1.json" => {
+            future::ok(Box::new(ResourceResponse::Meta {
+                meta: MetaItem {
+                    preview: MetaItemPreview {
+                        id: "tt1".to_owned(),
+                        r#type: "series".to_owned(),
+                        ..Default::default()
+                    },
+                    videos: (1..=3)
+                        .map(|episode| Video {
+                            id: format!("tt1:1:{episode}"),
+                            title: format!("video_1_{episode}"),
+                            released: Some(
+                                Utc.with_ymd_and_hms(2020, 1, episode, 0, 0, 0).unwrap(),
+                            ),
+                            overview: None,
+                            thumbnail: None,
+                            streams: vec![],
+                            series_info: Some(SeriesInfo { season: 1, episode }),
+                            trailer_streams: vec![],
+                        })
+                        .collect(),
+                },
+            }) as Box<dyn Any + Send>)
+            .boxed_env()
+        }
+        _ => default_fetch_handler(request),
+    }
+}
+
+#[test]
+fn intro_outro_from_local_records() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+    *NOW.write().unwrap() = Utc.with_ymd_and_hms(2020, 2, 1, 0, 0, 0).unwrap();
+    let mut intro_outro = IntroOutroBucket::default();
+    for (video_id, duration, from, to, outro) in [
+        ("tt1:1:1", 2_400_000, 30_000, 110_000, 2_310_000),
+        ("tt1:1:3", 2_300_000, 32_000, 112_000, 2_212_000),
+    ] {
+        intro_outro.record(
+            "tt1",
+            video_id,
+            1,
+            duration,
+            &[SeekLog { from, to }],
+            Some(outro),
+            Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap(),
+        );
+    }
+    let library_item = LibraryItem {
+        id: "tt1".to_owned(),
+        name: "name".to_owned(),
+        r#type: "series".to_owned(),
+        poster: None,
+        poster_shape: Default::default(),
+        removed: false,
+        temp: false,
+        ctime: None,
+        mtime: Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap(),
+        state: LibraryItemState {
+            duration: 2_000_000,
+            video_id: Some("tt1:1:2".to_owned()),
+            ..Default::default()
+        },
+        behavior_hints: Default::default(),
+    };
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                library: LibraryBucket::new(None, vec![library_item]),
+                intro_outro,
+                ..Default::default()
+            },
+            player: Player::default(),
+        },
+        vec![],
+        1000,
+    );
+    let request = |resource: &str, id: &str| ResourceRequest {
+        base: "https://transport_url/manifest.json".parse().unwrap(),
+        path: ResourcePath {
+            resource: resource.to_owned(),
+            r#type: "series".to_owned(),
+            id: id.to_owned(),
+            extra: vec![],
+        },
+    };
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Load(ActionLoad::Player(Box::new(Selected {
+                stream: Stream::youtube("yt_id:1").unwrap(),
+                stream_request: Some(request(STREAM_RESOURCE_NAME, "tt1:1:2")),
+                meta_request: Some(request(META_RESOURCE_NAME, "tt1")),
+                subtitles_path: None,
+                playback: None,
+            }))),
+        })
+    });
+    assert_eq!(
+        runtime.model().unwrap().player.intro_outro,
+        Some(IntroOutro {
+            intro: Some(IntroData {
+                from: 32_000,
+                to: 112_000,
+                duration: None,
+            }),
+            outro: Some(1_910_000),
+        }),
+        "The intro and the outro should be inferred from the other episodes"
+    );
+
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Player(ActionPlayer::VideoParamsChanged {
+                video_params: Some(VideoParams {
+                    hash: None,
+                    size: None,
+                    filename: None,
+                    chapters: ["Opening", "Episode", "End Credits"]
+                        .into_iter()
+                        .zip([5_000, 65_000, 1_950_000])
+                        .map(|(title, start)| VideoChapter {
+                            title: title.to_owned(),
+                            start,
+                            end: None,
+                        })
+                        .collect(),
+                }),
+            }),
+        })
+    });
+    assert_eq!(
+        runtime.model().unwrap().player.intro_outro,
+        Some(IntroOutro {
+            intro: Some(IntroData {
+                from: 5_000,
+                to: 65_000,
+                duration: None,
+            }),
+            outro: Some(1_950_000),
+        }),
+        "The chapters should be preferred"
+    );
+
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Player(ActionPlayer::VideoParamsChanged {
+                video_params: Some(VideoParams {
+                    hash: None,
+                    size: None,
+                    filename: None,
+                    chapters: ["Ed's Story", "OP", "Episode", "ED"]
+                        .into_iter()
+                        .zip([0, 5_000, 65_000, 1_950_000])
+                        .map(|(title, start)| VideoChapter {
+                            title: title.to_owned(),
+                            start,
+                            end: None,
+                        })
+                        .collect(),
+                }),
+            }),
+        })
+    });
+    assert_eq!(
+        runtime.model().unwrap().player.intro_outro,
+        Some(IntroOutro {
+            intro: Some(IntroData {
+                from: 5_000,
+                to: 65_000,
+                duration: None,
+            }),
+            outro: Some(1_950_000),
+        }),
+        "OP and ED should match only the whole chapter title"
+    );
+
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Player(ActionPlayer::TimeChanged {
+                time: 1_905_000,
+                duration: 2_000_000,
+                device: "chromecast".to_owned(),
+            }),
+        });
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Player(ActionPlayer::NextVideo),
+        });
+    });
+    let outros = runtime.model().unwrap().ctx.intro_outro.items["tt1"]
+        .outros
+        .iter()
+        .map(|record| (record.video_id.to_owned(), record.remaining))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        outros,
+        vec![
+            ("tt1:1:1".to_owned(), 90_000),
+            ("tt1:1:3".to_owned(), 88_000),
+            ("tt1:1:2".to_owned(), 95_000),
+        ],
+        "The outro of the played episode should be recorded"
+    );
+}
@@ -1 +1,9 @@
+mod binge_rules;
+mod intro_outro;
 mod next_stream;
+mod playback;
+mod qoe;
+mod resume;
+mod statistics;
+mod subtitles_sync;
+mod torrent_file;
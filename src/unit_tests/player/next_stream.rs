@@ -136,6 +136,7 @@ fn next_stream() {
                 stream_request: Some(stream_request),
                 meta_request: Some(meta_request),
                 subtitles_path: None,
+                playback: None,
             }))),
         });
     });
@@ -154,3 +155,118 @@ fn next_stream() {
         "next stream has same binge group"
     );
 }
+
+#[test]
+fn next_stream_with_changed_binge_group() {
+    #[derive(Model, Default, Clone, Debug)]
+    #[model(TestEnv)]
+    struct TestModel {
+        ctx: Ctx,
+        player: Player,
+    }
+
+    fn create_torrentio_stream(binge_group: &str, description: &str) -> Stream {
+        Stream {
+            name: Some("Torrentio".to_owned()),
+            description: Some(description.to_owned()),
+            ..create_stream(binge_group)
+        }
+    }
+
+    fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+        match request {
+            Request { url, .. } if url == "https://transport_url/meta/series/tt123456.json" => {
+                future::ok(Box::new(ResourceResponse::Meta {
+                    meta: MetaItem {
+                        preview: MetaItemPreview {
+                            id: "tt123456".to_owned(),
+                            r#type: "series".to_owned(),
+                            ..Default::default()
+                        },
+                        videos: vec![create_video(1, 1), create_video(1, 2)],
+                    },
+                }) as Box<dyn Any + Send>)
+                .boxed_env()
+            }
+            Request { url, .. }
+                if url == "https://transport_url/stream/series/tt123456%3A1%3A2.json" =>
+            {
+                future::ok(Box::new(ResourceResponse::Streams {
+                    streams: vec![
+                        create_torrentio_stream(
+                            "torrentio-720p-web-dl",
+                            "Show.S01E02.720p.WEB-DL.x265-OTHER.mkv\n💾 700 MB",
+                        ),
+                        create_torrentio_stream(
+                            "torrentio-1080p-web-dl",
+                            "Show.S01E02.1080p.WEB-DL.x265-GRP.mkv\n💾 1.3 GB",
+                        ),
+                    ],
+                }) as Box<dyn Any + Send>)
+                .boxed_env()
+            }
+            _ => default_fetch_handler(request),
+        }
+    }
+
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                profile: Profile {
+                    settings: Settings {
+                        binge_watching: true,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            player: Player::default(),
+        },
+        vec![],
+        1000,
+    );
+
+    let stream = create_torrentio_stream(
+        "torrentio|1080p|WEB-DL",
+        "Show.S01E01.1080p.WEB-DL.x265-GRP.mkv\n💾 1.2 GB",
+    );
+    let request = |resource: &str, id: &str| ResourceRequest {
+        base: "https://transport_url/manifest.json".parse().unwrap(),
+        path: ResourcePath {
+            resource: resource.to_owned(),
+            r#type: "series".to_owned(),
+            id: id.to_owned(),
+            extra: vec![],
+        },
+    };
+
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Load(ActionLoad::Player(Box::new(Selected {
+                stream: stream.clone(),
+                stream_request: Some(request(STREAM_RESOURCE_NAME, "tt123456:1:1")),
+                meta_request: Some(request(META_RESOURCE_NAME, "tt123456")),
+                subtitles_path: None,
+                playback: None,
+            }))),
+        });
+    });
+
+    assert_eq!(
+        runtime
+            .model()
+            .unwrap()
+            .player
+            .next_stream
+            .as_ref()
+            .and_then(|next_stream| next_stream.behavior_hints.binge_group.as_deref()),
+        Some("torrentio-1080p-web-dl"),
+        "next stream is the same release despite the changed binge group format"
+    );
+}
@@ -0,0 +1,188 @@
+use stremio_derive::Model;
+use url::Url;
+
+use crate::{
+    models::{
+        ctx::Ctx,
+        player::{Player, Selected},
+    },
+    runtime::{
+        msg::{Action, ActionLoad},
+        Runtime, RuntimeAction,
+    },
+    types::{
+        resource::{
+            PlaybackCapabilities, PlaybackMethod, Stream, StreamSource, VideoCodec, VideoContainer,
+        },
+        streaming_server::{ActiveServer, ServerCompatibility},
+    },
+    unit_tests::{TestEnv, PLAYBACK_CAPABILITIES},
+};
+
+#[derive(Model, Clone, Debug)]
+#[model(TestEnv)]
+struct TestModel {
+    ctx: Ctx,
+    player: Player,
+}
+
+fn torrent_selected() -> Selected {
+    Selected {
+        stream: Stream {
+            source: StreamSource::Torrent {
+                info_hash: [0; 20],
+                file_idx: Some(0),
+                announce: vec![],
+            },
+            name: None,
+            description: Some("Movie.2024.1080p.WEB.x264.mkv".to_owned()),
+            thumbnail: None,
+            subtitles: vec![],
+            behavior_hints: Default::default(),
+        },
+        stream_request: None,
+        meta_request: None,
+        subtitles_path: None,
+        playback: None,
+    }
+}
+
+#[test]
+fn choose_playback_method() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                streaming_server: ActiveServer {
+                    url: Url::parse("http://127.0.0.1:11470").unwrap(),
+                    compatibility: Some(ServerCompatibility::new("4.20.8")),
+                },
+                ..Default::default()
+            },
+            player: Player::default(),
+        },
+        vec![],
+        1000,
+    );
+    let selected = torrent_selected();
+    let load = || {
+        TestEnv::run(|| {
+            runtime.dispatch(RuntimeAction {
+                field: None,
+                action: Action::Load(ActionLoad::Player(Box::new(selected.to_owned()))),
+            })
+        });
+        runtime
+            .model()
+            .unwrap()
+            .player
+            .selected
+            .as_ref()
+            .and_then(|selected| selected.playback.to_owned())
+    };
+    assert_eq!(
+        load(),
+        None,
+        "Playback method should not be chosen without capabilities"
+    );
+    *PLAYBACK_CAPABILITIES.write().unwrap() = Some(PlaybackCapabilities {
+        containers: vec![VideoContainer::Matroska, VideoContainer::Mp4],
+        video_codecs: vec![VideoCodec::H264],
+        max_resolution: None,
+    });
+    let playback = load().expect("Playback method should be chosen");
+    assert_eq!(playback.method, PlaybackMethod::DirectPlay);
+    assert_eq!(
+        playback.url,
+        "http://127.0.0.1:11470/0000000000000000000000000000000000000000/0"
+    );
+    *PLAYBACK_CAPABILITIES.write().unwrap() = Some(PlaybackCapabilities {
+        containers: vec![VideoContainer::Mp4],
+        video_codecs: vec![VideoCodec::H264],
+        max_resolution: Some(720),
+    });
+    let playback = load().expect("Playback method should be chosen");
+    assert_eq!(
+        playback.method,
+        PlaybackMethod::Transcode,
+        "Resolution above the max resolution should be transcoded"
+    );
+    assert!(playback.url.starts_with("http://127.0.0.1:11470/hlsv2/"));
+}
+
+#[test]
+fn playback_without_streaming_server() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *PLAYBACK_CAPABILITIES.write().unwrap() = Some(PlaybackCapabilities {
+        containers: vec![VideoContainer::Matroska, VideoContainer::Mp4],
+        video_codecs: vec![VideoCodec::H264],
+        max_resolution: None,
+    });
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx::default(),
+            player: Player::default(),
+        },
+        vec![],
+        1000,
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Load(ActionLoad::Player(Box::new(torrent_selected()))),
+        })
+    });
+    assert_eq!(
+        runtime
+            .model()
+            .unwrap()
+            .player
+            .selected
+            .as_ref()
+            .and_then(|selected| selected.playback.to_owned()),
+        None,
+        "Torrent should not be played until the streaming server responds"
+    );
+}
+
+#[test]
+fn playback_with_outdated_streaming_server() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *PLAYBACK_CAPABILITIES.write().unwrap() = Some(PlaybackCapabilities {
+        containers: vec![VideoContainer::Mp4],
+        video_codecs: vec![VideoCodec::H264],
+        max_resolution: Some(720),
+    });
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                streaming_server: ActiveServer {
+                    url: Url::parse("http://127.0.0.1:11470").unwrap(),
+                    compatibility: Some(ServerCompatibility::new("3.13.0")),
+                },
+                ..Default::default()
+            },
+            player: Player::default(),
+        },
+        vec![],
+        1000,
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Load(ActionLoad::Player(Box::new(torrent_selected()))),
+        })
+    });
+    assert_eq!(
+        runtime
+            .model()
+            .unwrap()
+            .player
+            .selected
+            .as_ref()
+            .and_then(|selected| selected.playback.as_ref())
+            .map(|playback| playback.method),
+        Some(PlaybackMethod::DirectPlay),
+        "Outdated server should serve the stream as it is"
+    );
+}
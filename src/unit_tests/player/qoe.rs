@@ -0,0 +1,118 @@
+use std::sync::{Arc, RwLock};
+
+use chrono::{Duration, TimeZone, Utc};
+use enclose::enclose;
+use stremio_derive::Model;
+
+use crate::{
+    constants::{META_RESOURCE_NAME, STREAM_RESOURCE_NAME},
+    models::{
+        ctx::Ctx,
+        player::{Player, Selected},
+    },
+    runtime::{
+        msg::{Action, ActionLoad, ActionPlayer, Event},
+        Runtime, RuntimeAction, RuntimeEvent,
+    },
+    types::{
+        addon::{ResourcePath, ResourceRequest},
+        player::QoeSummary,
+        resource::Stream,
+    },
+    unit_tests::{TestEnv, EVENTS, NOW},
+};
+
+#[derive(Model, Default, Clone, Debug)]
+#[model(TestEnv)]
+struct TestModel {
+    ctx: Ctx,
+    player: Player,
+}
+
+#[test]
+fn qoe_summary() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    let load_time = Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap();
+    let (runtime, rx) = Runtime::<TestEnv, _>::new(TestModel::default(), vec![], 1000);
+    let runtime = Arc::new(RwLock::new(runtime));
+    let request = |resource: &str| ResourceRequest {
+        base: "https://transport_url/manifest.json".parse().unwrap(),
+        path: ResourcePath {
+            resource: resource.to_owned(),
+            r#type: "movie".to_owned(),
+            id: "tt1".to_owned(),
+            extra: vec![],
+        },
+    };
+    TestEnv::run_with_runtime(
+        rx,
+        runtime.clone(),
+        enclose!((runtime) move || {
+            let runtime = runtime.read().unwrap();
+            let dispatch_at = |seconds: i64, action: Action| {
+                *NOW.write().unwrap() = load_time + Duration::seconds(seconds);
+                runtime.dispatch(RuntimeAction { field: None, action });
+            };
+            dispatch_at(
+                0,
+                Action::Load(ActionLoad::Player(Box::new(Selected {
+                    stream: Stream::youtube("yt_id:1").unwrap(),
+                    stream_request: Some(request(STREAM_RESOURCE_NAME)),
+                    meta_request: Some(request(META_RESOURCE_NAME)),
+                    subtitles_path: None,
+                    playback: None,
+                }))),
+            );
+            dispatch_at(1, Action::Player(ActionPlayer::BufferingChanged { buffering: true }));
+            dispatch_at(2, Action::Player(ActionPlayer::PausedChanged { paused: false }));
+            dispatch_at(2, Action::Player(ActionPlayer::BufferingChanged { buffering: false }));
+            dispatch_at(
+                2,
+                Action::Player(ActionPlayer::QualityChanged {
+                    width: 1920,
+                    height: 1080,
+                    bitrate: Some(5_000_000),
+                }),
+            );
+            dispatch_at(12, Action::Player(ActionPlayer::BufferingChanged { buffering: true }));
+            dispatch_at(13, Action::Player(ActionPlayer::BufferingChanged { buffering: false }));
+            dispatch_at(
+                13,
+                Action::Player(ActionPlayer::QualityChanged {
+                    width: 1280,
+                    height: 720,
+                    bitrate: Some(2_500_000),
+                }),
+            );
+            dispatch_at(17, Action::Player(ActionPlayer::PlaybackError { code: 3 }));
+            dispatch_at(21, Action::Player(ActionPlayer::BufferingChanged { buffering: true }));
+            dispatch_at(22, Action::Unload);
+        }),
+    );
+    let events = EVENTS.read().unwrap();
+    let qoe = events
+        .iter()
+        .filter_map(|event| {
+            match event
+                .downcast_ref::<RuntimeEvent<TestEnv, TestModel>>()
+                .unwrap()
+            {
+                RuntimeEvent::CoreEvent(Event::PlayerQoe { qoe, .. }) => Some(qoe.to_owned()),
+                _ => None,
+            }
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        qoe,
+        vec![QoeSummary {
+            startup_time: Some(2_000),
+            stalls: 2,
+            stall_time: 2_000,
+            stall_permille: 100,
+            quality_changes: 1,
+            bitrate: Some(2_500_000),
+            error_codes: vec![3],
+        }],
+        "The buffering before the playback started should not be a stall"
+    );
+}
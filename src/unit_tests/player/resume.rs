@@ -0,0 +1,248 @@
+use crate::{
+    constants::{META_RESOURCE_NAME, STREAM_RESOURCE_NAME},
+    models::{
+        ctx::Ctx,
+        player::{Player, Selected},
+    },
+    runtime::{
+        msg::{Action, ActionLoad},
+        EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture,
+    },
+    types::{
+        addon::{ResourcePath, ResourceRequest, ResourceResponse},
+        library::{LibraryBucket, LibraryItem, LibraryItemState},
+        player::ResumeDecision,
+        profile::{Profile, Settings},
+        resource::{MetaItem, MetaItemPreview, SeriesInfo, Stream, Video},
+    },
+    unit_tests::{default_fetch_handler, Request, TestEnv, FETCH_HANDLER, NOW},
+};
+use chrono::{TimeZone, Utc};
+use futures::future;
+use std::any::Any;
+use stremio_derive::Model;
+
+#[derive(Model, Default, Clone, Debug)]
+#[model(TestEnv)]
+struct TestModel {
+    ctx: Ctx,
+    player: Player,
+}
+
+/// Loads the movie with the given stored offset, watched a day and a half ago.
+fn load_player(time_offset: u64) -> (Option<ResumeDecision>, LibraryItem) {
+    let library_item = LibraryItem {
+        id: "tt1".to_owned(),
+        name: "name".to_owned(),
+        r#type: "movie".to_owned(),
+        poster: None,
+        poster_shape: Default::default(),
+        removed: false,
+        temp: false,
+        ctime: None,
+        mtime: Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap(),
+        state: LibraryItemState {
+            last_watched: Some(Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap()),
+            time_offset,
+            duration: 1_000_000,
+            video_id: Some("tt1".to_owned()),
+            ..Default::default()
+        },
+        behavior_hints: Default::default(),
+    };
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                library: LibraryBucket::new(None, vec![library_item]),
+                ..Default::default()
+            },
+            player: Player::default(),
+        },
+        vec![],
+        1000,
+    );
+    let request = |resource: &str| ResourceRequest {
+        base: "https://transport_url/manifest.json".parse().unwrap(),
+        path: ResourcePath {
+            resource: resource.to_owned(),
+            r#type: "movie".to_owned(),
+            id: "tt1".to_owned(),
+            extra: vec![],
+        },
+    };
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Load(ActionLoad::Player(Box::new(Selected {
+                stream: Stream::youtube("yt_id:1").unwrap(),
+                stream_request: Some(request(STREAM_RESOURCE_NAME)),
+                meta_request: Some(request(META_RESOURCE_NAME)),
+                subtitles_path: None,
+                playback: None,
+            }))),
+        })
+    });
+    let model = runtime.model().unwrap();
+    (
+        model.player.resume.to_owned(),
+        model
+            .player
+            .library_item
+            .to_owned()
+            .expect("Should have a library item"),
+    )
+}
+
+#[test]
+fn resume() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *NOW.write().unwrap() = Utc.with_ymd_and_hms(2020, 1, 2, 12, 0, 0).unwrap();
+
+    let (resume, library_item) = load_player(600_000);
+    assert_eq!(
+        resume,
+        Some(ResumeDecision::Resume {
+            time: 585_000,
+            rewound: 15_000,
+        }),
+        "The offset should be rewound after a long break"
+    );
+    assert_eq!(library_item.state.time_offset, 600_000);
+
+    let (resume, library_item) = load_player(950_000);
+    assert_eq!(
+        resume,
+        Some(ResumeDecision::Finished),
+        "An offset in the credits should finish the video"
+    );
+    assert_eq!(library_item.state.time_offset, 0);
+    assert_eq!(library_item.state.times_watched, 1);
+
+    let (resume, _) = load_player(0);
+    assert_eq!(resume, Some(ResumeDecision::Start));
+}
+
+fn series_videos() -> Vec<Video> {
+    (1..=2)
+        .map(|episode| Video {
+            id: format!("tt2:1:{episode}"),
+            title: format!("video_1_{episode}"),
+            released: Some(Utc.with_ymd_and_hms(2020, 1, episode, 0, 0, 0).unwrap()),
+            overview: None,
+            thumbnail: None,
+            streams: vec![],
+            series_info: Some(SeriesInfo { season: 1, episode }),
+            trailer_streams: vec![],
+        })
+        .collect()
+}
+
+#[test]
+fn resume_series() {
+    fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+        match request {
+            Request { url, .. } if url == "https://transport_url/meta/series/tt2.json" => {
+                future::ok(Box::new(ResourceResponse::Meta {
+                    meta: MetaItem {
+                        preview: MetaItemPreview {
+                            id: "tt2".to_owned(),
+                            r#type: "series".to_owned(),
+                            ..Default::default()
+                        },
+                        videos: series_videos(),
+                    },
+                }) as Box<dyn Any + Send>)
+                .boxed_env()
+            }
+            _ => default_fetch_handler(request),
+        }
+    }
+
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+    *NOW.write().unwrap() = Utc.with_ymd_and_hms(2020, 1, 2, 12, 0, 0).unwrap();
+
+    let library_item = LibraryItem {
+        id: "tt2".to_owned(),
+        name: "name".to_owned(),
+        r#type: "series".to_owned(),
+        poster: None,
+        poster_shape: Default::default(),
+        removed: false,
+        temp: false,
+        ctime: None,
+        mtime: Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap(),
+        state: LibraryItemState {
+            last_watched: Some(Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap()),
+            time_offset: 950_000,
+            duration: 1_000_000,
+            video_id: Some("tt2:1:1".to_owned()),
+            ..Default::default()
+        },
+        behavior_hints: Default::default(),
+    };
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                profile: Profile {
+                    settings: Settings {
+                        binge_watching: true,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                library: LibraryBucket::new(None, vec![library_item]),
+                ..Default::default()
+            },
+            player: Player::default(),
+        },
+        vec![],
+        1000,
+    );
+    let request = |resource: &str, id: &str| ResourceRequest {
+        base: "https://transport_url/manifest.json".parse().unwrap(),
+        path: ResourcePath {
+            resource: resource.to_owned(),
+            r#type: "series".to_owned(),
+            id: id.to_owned(),
+            extra: vec![],
+        },
+    };
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Load(ActionLoad::Player(Box::new(Selected {
+                stream: Stream::youtube("yt_id:1").unwrap(),
+                stream_request: Some(request(STREAM_RESOURCE_NAME, "tt2:1:1")),
+                meta_request: Some(request(META_RESOURCE_NAME, "tt2")),
+                subtitles_path: None,
+                playback: None,
+            }))),
+        })
+    });
+
+    let model = runtime.model().unwrap();
+    let library_item = model
+        .player
+        .library_item
+        .as_ref()
+        .expect("Should have a library item");
+    assert_eq!(
+        model.player.resume,
+        Some(ResumeDecision::Finished),
+        "An offset in the credits should finish the episode"
+    );
+    assert_eq!(
+        library_item.state.video_id,
+        Some("tt2:1:2".to_owned()),
+        "The next episode should be continued"
+    );
+    assert_eq!(library_item.state.time_offset, 1);
+    assert!(
+        library_item
+            .state
+            .watched_bitfield(&series_videos())
+            .get_video("tt2:1:1"),
+        "The finished episode should be watched"
+    );
+}
@@ -0,0 +1,183 @@
+use std::any::Any;
+
+use chrono::{Duration, TimeZone, Utc};
+use futures::future;
+use stremio_derive::Model;
+
+use crate::{
+    models::{
+        ctx::Ctx,
+        player::{Player, Selected},
+        streaming_server::StreamingServer,
+    },
+    runtime::{
+        msg::{Action, ActionLoad, ActionPlayer},
+        EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture,
+    },
+    types::{
+        profile::Profile,
+        resource::{Stream, StreamSource},
+        streaming_server::{BufferingVerdict, Growler, Options, PeerSearch, Statistics, SwarmCap},
+    },
+    unit_tests::{default_fetch_handler, Request, TestEnv, FETCH_HANDLER, NOW, REQUESTS},
+};
+
+const INFO_HASH: &str = "6d0a1e5b7e0ef5c2d7ae8fbee3e1fa6b6c6fd16e";
+
+#[derive(Model, Clone, Debug)]
+#[model(TestEnv)]
+struct TestModel {
+    ctx: Ctx,
+    player: Player,
+    streaming_server: StreamingServer,
+}
+
+fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+    match request {
+        Request { url, .. }
+            if url == format!("http://127.0.0.1:11470/{INFO_HASH}/0/stats.json") =>
+        {
+            future::ok(Box::new(Some(Statistics {
+                name: "Video".to_owned(),
+                info_hash: INFO_HASH.to_owned(),
+                files: vec![],
+                sources: vec![],
+                opts: Options {
+                    connections: 0,
+                    dht: true,
+                    growler: Growler { flood: 0, pulse: 0 },
+                    handshake_timeout: 0,
+                    path: String::new(),
+                    peer_search: PeerSearch {
+                        max: 0,
+                        min: 0,
+                        sources: vec![],
+                    },
+                    swarm_cap: SwarmCap {
+                        max_speed: 0.0,
+                        min_peers: 0,
+                    },
+                    timeout: 0,
+                    tracker: true,
+                    r#virtual: true,
+                },
+                download_speed: 1_000_000.0,
+                upload_speed: 0.0,
+                downloaded: 100_000_000,
+                uploaded: 0,
+                unchoked: 0,
+                peers: 20,
+                queued: 0,
+                unique: 0,
+                connection_tries: 0,
+                peer_search_running: false,
+                stream_len: 1_000_000_000,
+                stream_name: "Video".to_owned(),
+                stream_progress: 0.1,
+                swarm_connections: 0,
+                swarm_paused: false,
+                swarm_size: 0,
+            })) as Box<dyn Any + Send>)
+            .boxed_env()
+        }
+        _ => default_fetch_handler(request),
+    }
+}
+
+#[test]
+fn poll_statistics_while_playing() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+    let load_time = Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap();
+    let (streaming_server, ..) = StreamingServer::new::<TestEnv>(&Profile::default());
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx::default(),
+            player: Player::default(),
+            streaming_server,
+        },
+        vec![],
+        1000,
+    );
+    let dispatch_at = |seconds: i64, action: Action| {
+        *NOW.write().unwrap() = load_time + Duration::seconds(seconds);
+        TestEnv::run(|| {
+            runtime.dispatch(RuntimeAction {
+                field: None,
+                action,
+            })
+        });
+    };
+    dispatch_at(
+        0,
+        Action::Load(ActionLoad::Player(Box::new(Selected {
+            stream: Stream {
+                source: StreamSource::Torrent {
+                    info_hash: hex::decode(INFO_HASH).unwrap().try_into().unwrap(),
+                    file_idx: Some(0),
+                    announce: vec![],
+                },
+                name: None,
+                description: None,
+                thumbnail: None,
+                subtitles: vec![],
+                behavior_hints: Default::default(),
+            },
+            stream_request: None,
+            meta_request: None,
+            subtitles_path: None,
+            playback: None,
+        }))),
+    );
+    for seconds in [0, 2, 5, 7, 10] {
+        dispatch_at(
+            seconds,
+            Action::Player(ActionPlayer::TimeChanged {
+                time: seconds as u64 * 1000,
+                duration: 3_600_000,
+                device: "chrome".to_owned(),
+            }),
+        );
+    }
+    assert_eq!(
+        REQUESTS.read().unwrap().len(),
+        3,
+        "Statistics should be polled every 5 seconds"
+    );
+    {
+        let model = runtime.model().unwrap();
+        let statistics = model.player.statistics.as_ref().unwrap();
+        assert_eq!(statistics.samples.len(), 3);
+        // 900MB left at 1MB/s takes 900s, while there are almost 3600s left to play
+        assert_eq!(statistics.buffering, Some(BufferingVerdict::Smooth));
+        let streaming_server_statistics =
+            model.streaming_server.statistics_history.as_ref().unwrap();
+        assert_eq!(streaming_server_statistics.samples.len(), 3);
+        assert_eq!(
+            streaming_server_statistics.buffering,
+            Some(BufferingVerdict::Smooth)
+        );
+    }
+    dispatch_at(
+        12,
+        Action::Player(ActionPlayer::TimeChanged {
+            time: 3_000_000,
+            duration: 3_600_000,
+            device: "chrome".to_owned(),
+        }),
+    );
+    assert_eq!(
+        runtime
+            .model()
+            .unwrap()
+            .player
+            .statistics
+            .as_ref()
+            .unwrap()
+            .buffering,
+        Some(BufferingVerdict::WillBuffer),
+        "The playback should catch up with the download"
+    );
+    dispatch_at(13, Action::Unload);
+    assert_eq!(runtime.model().unwrap().player.statistics, None);
+}
@@ -0,0 +1,104 @@
+use crate::{
+    models::{
+        ctx::Ctx,
+        player::{Player, Selected},
+    },
+    runtime::{
+        msg::{Action, ActionPlayer},
+        EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture,
+    },
+    types::{
+        resource::{Stream, Subtitles},
+        subtitles::{SubtitlesCue, SubtitlesReference},
+    },
+    unit_tests::{default_fetch_handler, Request, TestEnv, FETCH_HANDLER},
+};
+use futures::future;
+use std::any::Any;
+use stremio_derive::Model;
+
+fn reference_starts() -> impl Iterator<Item = u64> {
+    (0..30).map(|index| 5_000 + index * 4_100 + (index * index % 5) * 600)
+}
+
+#[test]
+fn subtitles_sync_against_embedded_track() {
+    #[derive(Model, Default, Clone, Debug)]
+    #[model(TestEnv)]
+    struct TestModel {
+        ctx: Ctx,
+        player: Player,
+    }
+    fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+        match request {
+            Request { url, .. } if url == "https://subtitles/eng.srt" => {
+                // the addon subtitles are shown 2 seconds later than the embedded ones
+                let srt = reference_starts()
+                    .enumerate()
+                    .map(|(index, start)| {
+                        let start = start + 2_000;
+                        format!(
+                            "{}\n00:{:02}:{:02},{:03} --> 00:{:02}:{:02},{:03}\nLine {index}\n\n",
+                            index + 1,
+                            start / 60_000,
+                            start / 1000 % 60,
+                            start % 1000,
+                            (start + 1_000) / 60_000,
+                            (start + 1_000) / 1000 % 60,
+                            (start + 1_000) % 1000,
+                        )
+                    })
+                    .collect::<String>();
+                future::ok(Box::new(srt.into_bytes()) as Box<dyn Any + Send>).boxed_env()
+            }
+            _ => default_fetch_handler(request),
+        }
+    }
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx::default(),
+            player: Player {
+                selected: Some(Selected {
+                    stream: Stream::youtube("yt_id:1").unwrap(),
+                    stream_request: None,
+                    meta_request: None,
+                    subtitles_path: None,
+                    playback: None,
+                }),
+                selected_subtitles: Some(Subtitles {
+                    lang: "eng".to_owned(),
+                    url: "https://subtitles/eng.srt".parse().unwrap(),
+                }),
+                ..Default::default()
+            },
+        },
+        vec![],
+        1000,
+    );
+    let embedded_cues = reference_starts()
+        .map(|start| SubtitlesCue {
+            start,
+            end: start + 1_000,
+            text: "Embedded".to_owned(),
+        })
+        .collect();
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Player(ActionPlayer::SubtitlesReferenceChanged {
+                reference: Some(SubtitlesReference::Embedded(embedded_cues)),
+            }),
+        })
+    });
+    let subtitles_sync = runtime
+        .model()
+        .unwrap()
+        .player
+        .subtitles_sync
+        .to_owned()
+        .expect("Should suggest a subtitles sync");
+    assert_eq!(subtitles_sync.delay, -2_000);
+    assert_eq!(subtitles_sync.drift, 1.0);
+}
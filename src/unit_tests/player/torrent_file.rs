@@ -0,0 +1,267 @@
+use std::any::Any;
+
+use futures::future;
+use stremio_derive::Model;
+
+use crate::{
+    constants::{META_RESOURCE_NAME, STREAM_RESOURCE_NAME},
+    models::{
+        common::Loadable,
+        ctx::Ctx,
+        player::{Player, Selected},
+    },
+    runtime::{
+        msg::{Action, ActionLoad},
+        EnvError, EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture,
+    },
+    types::{
+        addon::{ResourcePath, ResourceRequest, ResourceResponse},
+        resource::{MetaItem, MetaItemPreview, SeriesInfo, Stream, StreamSource, Video},
+        streaming_server::{File, Growler, Options, PeerSearch, Statistics, SwarmCap},
+    },
+    unit_tests::{default_fetch_handler, Request, TestEnv, FETCH_HANDLER, REQUESTS},
+};
+
+const INFO_HASH: &str = "6d0a1e5b7e0ef5c2d7ae8fbee3e1fa6b6c6fd16e";
+
+#[derive(Model, Default, Clone, Debug)]
+#[model(TestEnv)]
+struct TestModel {
+    ctx: Ctx,
+    player: Player,
+}
+
+fn file(name: &str, length: u64) -> File {
+    File {
+        name: name.to_owned(),
+        path: format!("Show.S01.1080p.WEB.x264/{name}"),
+        length,
+        offset: 0,
+    }
+}
+
+fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+    match request {
+        Request { url, .. } if url == "https://transport_url/meta/series/tt1.json" => {
+            future::ok(Box::new(ResourceResponse::Meta {
+                meta: MetaItem {
+                    preview: MetaItemPreview {
+                        id: "tt1".to_owned(),
+                        r#type: "series".to_owned(),
+                        ..Default::default()
+                    },
+                    videos: (1..=3)
+                        .map(|episode| Video {
+                            id: format!("tt1:1:{episode}"),
+                            title: format!("video_1_{episode}"),
+                            released: None,
+                            overview: None,
+                            thumbnail: None,
+                            streams: vec![],
+                            series_info: Some(SeriesInfo { season: 1, episode }),
+                            trailer_streams: vec![],
+                        })
+                        .collect(),
+                },
+            }) as Box<dyn Any + Send>)
+            .boxed_env()
+        }
+        Request { url, method, .. }
+            if url == format!("http://127.0.0.1:11470/{INFO_HASH}/create") && method == "POST" =>
+        {
+            future::ok(Box::new(Statistics {
+                name: "Show.S01.1080p.WEB.x264".to_owned(),
+                info_hash: INFO_HASH.to_owned(),
+                files: vec![
+                    file("show.s01e01.sample.mkv", 50_000_000),
+                    file("Show.S01E01.1080p.WEB.x264.mkv", 1_200_000_000),
+                    file("Show.S01E02.1080p.WEB.x264.mkv", 1_100_000_000),
+                    file("Show.S01E03.1080p.WEB.x264.mkv", 1_300_000_000),
+                    file("Show.S01.1080p.WEB.x264.nfo", 2_000),
+                ],
+                sources: vec![],
+                opts: Options {
+                    connections: 0,
+                    dht: true,
+                    growler: Growler { flood: 0, pulse: 0 },
+                    handshake_timeout: 0,
+                    path: String::new(),
+                    peer_search: PeerSearch {
+                        max: 0,
+                        min: 0,
+                        sources: vec![],
+                    },
+                    swarm_cap: SwarmCap {
+                        max_speed: 0.0,
+                        min_peers: 0,
+                    },
+                    timeout: 0,
+                    tracker: true,
+                    r#virtual: true,
+                },
+                download_speed: 0.0,
+                upload_speed: 0.0,
+                downloaded: 0,
+                uploaded: 0,
+                unchoked: 0,
+                peers: 0,
+                queued: 0,
+                unique: 0,
+                connection_tries: 0,
+                peer_search_running: false,
+                stream_len: 0,
+                stream_name: String::new(),
+                stream_progress: 0.0,
+                swarm_connections: 0,
+                swarm_paused: false,
+                swarm_size: 0,
+            }) as Box<dyn Any + Send>)
+            .boxed_env()
+        }
+        _ => default_fetch_handler(request),
+    }
+}
+
+#[test]
+fn match_episode_file_in_season_pack() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(TestModel::default(), vec![], 1000);
+    let request = |resource: &str, id: &str| ResourceRequest {
+        base: "https://transport_url/manifest.json".parse().unwrap(),
+        path: ResourcePath {
+            resource: resource.to_owned(),
+            r#type: "series".to_owned(),
+            id: id.to_owned(),
+            extra: vec![],
+        },
+    };
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Load(ActionLoad::Player(Box::new(Selected {
+                stream: Stream {
+                    source: StreamSource::Torrent {
+                        info_hash: hex::decode(INFO_HASH).unwrap().try_into().unwrap(),
+                        file_idx: None,
+                        announce: vec![],
+                    },
+                    name: None,
+                    description: None,
+                    thumbnail: None,
+                    subtitles: vec![],
+                    behavior_hints: Default::default(),
+                },
+                stream_request: Some(request(STREAM_RESOURCE_NAME, "tt1:1:2")),
+                meta_request: Some(request(META_RESOURCE_NAME, "tt1")),
+                subtitles_path: None,
+                playback: None,
+            }))),
+        })
+    });
+    assert!(
+        REQUESTS
+            .read()
+            .unwrap()
+            .iter()
+            .any(|request| request.url == format!("http://127.0.0.1:11470/{INFO_HASH}/create")),
+        "Torrent files should be requested"
+    );
+    let model = runtime.model().unwrap();
+    assert!(
+        matches!(
+            model
+                .player
+                .selected
+                .as_ref()
+                .map(|selected| &selected.stream.source),
+            Some(StreamSource::Torrent {
+                file_idx: Some(2),
+                ..
+            })
+        ),
+        "The file of the second episode should be selected"
+    );
+    assert_eq!(
+        model
+            .player
+            .video_params
+            .as_ref()
+            .and_then(|video_params| video_params.filename.as_deref()),
+        Some("Show.S01E02.1080p.WEB.x264.mkv")
+    );
+    assert!(
+        model.player.statistics.is_some(),
+        "Statistics should be polled for the selected file"
+    );
+}
+
+#[test]
+fn torrent_files_error() {
+    fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+        match request {
+            Request { url, method, .. }
+                if url == format!("http://127.0.0.1:11470/{INFO_HASH}/create")
+                    && method == "POST" =>
+            {
+                future::err(EnvError::Fetch("Connection refused".to_owned())).boxed_env()
+            }
+            _ => default_fetch_handler(request),
+        }
+    }
+
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(TestModel::default(), vec![], 1000);
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Load(ActionLoad::Player(Box::new(Selected {
+                stream: Stream {
+                    source: StreamSource::Torrent {
+                        info_hash: hex::decode(INFO_HASH).unwrap().try_into().unwrap(),
+                        file_idx: None,
+                        announce: vec!["udp://tracker.example.com:80".to_owned()],
+                    },
+                    name: None,
+                    description: None,
+                    thumbnail: None,
+                    subtitles: vec![],
+                    behavior_hints: Default::default(),
+                },
+                stream_request: None,
+                meta_request: None,
+                subtitles_path: None,
+                playback: None,
+            }))),
+        })
+    });
+    assert_eq!(
+        REQUESTS
+            .read()
+            .unwrap()
+            .iter()
+            .find(|request| request.url == format!("http://127.0.0.1:11470/{INFO_HASH}/create"))
+            .map(|request| request.body.to_owned())
+            .unwrap(),
+        format!(
+            r#"{{"torrent":{{"infoHash":"{INFO_HASH}"}},"peerSearch":{{"sources":["dht:{INFO_HASH}","udp://tracker.example.com:80"],"min":40,"max":200}}}}"#
+        ),
+        "The peers should be searched on the trackers of the stream"
+    );
+    let player = &runtime.model().unwrap().player;
+    assert!(
+        matches!(player.torrent_files, Some(Loadable::Err(_))),
+        "The error should be kept"
+    );
+    assert!(
+        matches!(
+            player
+                .selected
+                .as_ref()
+                .map(|selected| &selected.stream.source),
+            Some(StreamSource::Torrent { file_idx: None, .. })
+        ),
+        "The largest file should be played"
+    );
+}
@@ -371,16 +371,33 @@ impl DefaultTokens for Settings {
         vec![
             Token::Struct {
                 name: "Settings",
-                len: 27,
+                len: 31,
             },
             Token::Str("interfaceLanguage"),
             Token::Str("eng"),
             Token::Str("streamingServerUrl"),
             Token::Str("http://127.0.0.1:11470/"),
+            Token::Str("streamingServers"),
+            Token::Seq { len: Some(0) },
+            Token::SeqEnd,
             Token::Str("playerType"),
             Token::None,
             Token::Str("bingeWatching"),
             Token::Bool(true),
+            Token::Str("bingeRules"),
+            Token::Struct {
+                name: "BingeRules",
+                len: 4,
+            },
+            Token::Str("skipWatched"),
+            Token::Bool(false),
+            Token::Str("stopAtSeasonEnd"),
+            Token::Bool(false),
+            Token::Str("specialsInAiredOrder"),
+            Token::Bool(false),
+            Token::Str("playNextCount"),
+            Token::None,
+            Token::StructEnd,
             Token::Str("playInBackground"),
             Token::Bool(true),
             Token::Str("hardwareDecoding"),
@@ -430,8 +447,30 @@ impl DefaultTokens for Settings {
             Token::Bool(false),
             Token::Str("surroundSound"),
             Token::Bool(false),
+            Token::Str("streamPreferences"),
+            Token::Struct {
+                name: "StreamPreferences",
+                len: 6,
+            },
+            Token::Str("preferredResolutions"),
+            Token::Seq { len: Some(0) },
+            Token::SeqEnd,
+            Token::Str("maxSize"),
+            Token::None,
+            Token::Str("excludeCam"),
+            Token::Bool(false),
+            Token::Str("preferredLanguages"),
+            Token::Seq { len: Some(0) },
+            Token::SeqEnd,
+            Token::Str("hideP2p"),
+            Token::Bool(false),
+            Token::Str("preferDirectUrls"),
+            Token::Bool(false),
+            Token::StructEnd,
             Token::Str("streamingServerWarningDismissed"),
             Token::None,
+            Token::Str("notificationsQuietHours"),
+            Token::None,
             Token::StructEnd,
         ]
     }
@@ -1,4 +1,4 @@
-use crate::types::library::LibraryItemState;
+use crate::types::library::{LibraryItemState, NotificationPreferences};
 use chrono::{TimeZone, Utc};
 use serde_test::{assert_de_tokens, assert_tokens, Token};
 
@@ -17,6 +17,11 @@ fn library_item_state() {
                 video_id: Some("tt2934286:1:5".to_owned()),
                 watched: Some("tt2934286:1:5:5:eJyTZwAAAEAAIA==".parse().unwrap()),
                 no_notif: true,
+                notif_prefs: NotificationPreferences {
+                    new_seasons_only: true,
+                    after_current_episode: false,
+                    watched_within_days: Some(30),
+                },
             },
             LibraryItemState {
                 last_watched: None,
@@ -29,13 +34,14 @@ fn library_item_state() {
                 video_id: None,
                 watched: None,
                 no_notif: false,
+                notif_prefs: Default::default(),
             },
         ],
         &[
             Token::Seq { len: Some(2) },
             Token::Struct {
                 name: "LibraryItemState",
-                len: 10,
+                len: 11,
             },
             Token::Str("lastWatched"),
             Token::Some,
@@ -60,6 +66,19 @@ fn library_item_state() {
             Token::Str("tt2934286:1:5:5:eJyTZwAAAEAAIA=="),
             Token::Str("noNotif"),
             Token::Bool(true),
+            Token::Str("notifPrefs"),
+            Token::Struct {
+                name: "NotificationPreferences",
+                len: 3,
+            },
+            Token::Str("newSeasonsOnly"),
+            Token::Bool(true),
+            Token::Str("afterCurrentEpisode"),
+            Token::Bool(false),
+            Token::Str("watchedWithinDays"),
+            Token::Some,
+            Token::U32(30),
+            Token::StructEnd,
             Token::StructEnd,
             Token::Struct {
                 name: "LibraryItemState",
@@ -102,6 +121,7 @@ fn library_item_state() {
                 video_id: None,
                 watched: None,
                 no_notif: false,
+                notif_prefs: Default::default(),
             },
             LibraryItemState {
                 last_watched: None,
@@ -114,6 +134,7 @@ fn library_item_state() {
                 video_id: None,
                 watched: None,
                 no_notif: false,
+                notif_prefs: Default::default(),
             },
         ],
         &[
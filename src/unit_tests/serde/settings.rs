@@ -1,4 +1,6 @@
-use crate::types::profile::{FrameRateMatchingStrategy, Settings};
+use crate::types::profile::{
+    BingeRules, FrameRateMatchingStrategy, QuietHours, Settings, StreamPreferences,
+};
 use chrono::{TimeZone, Utc};
 use serde_test::{assert_de_tokens, assert_tokens, Token};
 use url::Url;
@@ -9,8 +11,15 @@ fn settings() {
         &Settings {
             interface_language: "interface_language".to_owned(),
             streaming_server_url: Url::parse("https://streaming_server_url").unwrap(),
+            streaming_servers: vec![Url::parse("https://fallback_streaming_server_url").unwrap()],
             player_type: Some("player".to_owned()),
             binge_watching: true,
+            binge_rules: BingeRules {
+                skip_watched: true,
+                stop_at_season_end: false,
+                specials_in_aired_order: true,
+                play_next_count: Some(3),
+            },
             play_in_background: true,
             hardware_decoding: true,
             frame_rate_matching_strategy: FrameRateMatchingStrategy::FrameRateAndResolution,
@@ -33,24 +42,56 @@ fn settings() {
             seek_short_time_duration: 3,
             pause_on_minimize: true,
             surround_sound: false,
+            stream_preferences: StreamPreferences {
+                preferred_resolutions: vec![1080, 2160],
+                max_size: Some(10_000_000_000),
+                exclude_cam: true,
+                preferred_languages: vec!["eng".to_owned()],
+                hide_p2p: true,
+                prefer_direct_urls: true,
+            },
             streaming_server_warning_dismissed: Some(
                 Utc.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap(),
             ),
+            notifications_quiet_hours: Some(QuietHours {
+                start: 1320,
+                end: 480,
+                utc_offset: 120,
+            }),
         },
         &[
             Token::Struct {
                 name: "Settings",
-                len: 27,
+                len: 31,
             },
             Token::Str("interfaceLanguage"),
             Token::Str("interface_language"),
             Token::Str("streamingServerUrl"),
             Token::Str("https://streaming_server_url/"),
+            Token::Str("streamingServers"),
+            Token::Seq { len: Some(1) },
+            Token::Str("https://fallback_streaming_server_url/"),
+            Token::SeqEnd,
             Token::Str("playerType"),
             Token::Some,
             Token::Str("player"),
             Token::Str("bingeWatching"),
             Token::Bool(true),
+            Token::Str("bingeRules"),
+            Token::Struct {
+                name: "BingeRules",
+                len: 4,
+            },
+            Token::Str("skipWatched"),
+            Token::Bool(true),
+            Token::Str("stopAtSeasonEnd"),
+            Token::Bool(false),
+            Token::Str("specialsInAiredOrder"),
+            Token::Bool(true),
+            Token::Str("playNextCount"),
+            Token::Some,
+            Token::U32(3),
+            Token::StructEnd,
             Token::Str("playInBackground"),
             Token::Bool(true),
             Token::Str("hardwareDecoding"),
@@ -102,9 +143,46 @@ fn settings() {
             Token::Bool(true),
             Token::Str("surroundSound"),
             Token::Bool(false),
+            Token::Str("streamPreferences"),
+            Token::Struct {
+                name: "StreamPreferences",
+                len: 6,
+            },
+            Token::Str("preferredResolutions"),
+            Token::Seq { len: Some(2) },
+            Token::U32(1080),
+            Token::U32(2160),
+            Token::SeqEnd,
+            Token::Str("maxSize"),
+            Token::Some,
+            Token::U64(10000000000),
+            Token::Str("excludeCam"),
+            Token::Bool(true),
+            Token::Str("preferredLanguages"),
+            Token::Seq { len: Some(1) },
+            Token::Str("eng"),
+            Token::SeqEnd,
+            Token::Str("hideP2p"),
+            Token::Bool(true),
+            Token::Str("preferDirectUrls"),
+            Token::Bool(true),
+            Token::StructEnd,
             Token::Str("streamingServerWarningDismissed"),
             Token::Some,
             Token::Str("2021-01-01T00:00:00Z"),
+            Token::Str("notificationsQuietHours"),
+            Token::Some,
+            Token::Struct {
+                name: "QuietHours",
+                len: 3,
+            },
+            Token::Str("start"),
+            Token::U16(1320),
+            Token::Str("end"),
+            Token::U16(480),
+            Token::Str("utcOffset"),
+            Token::I32(120),
+            Token::StructEnd,
             Token::StructEnd,
         ],
     );
@@ -117,16 +195,33 @@ fn settings_de() {
         &[
             Token::Struct {
                 name: "Settings",
-                len: 22,
+                len: 26,
             },
             Token::Str("interfaceLanguage"),
             Token::Str("eng"),
             Token::Str("streamingServerUrl"),
             Token::Str("http://127.0.0.1:11470/"),
+            Token::Str("streamingServers"),
+            Token::Seq { len: Some(0) },
+            Token::SeqEnd,
             Token::Str("playerType"),
             Token::None,
             Token::Str("bingeWatching"),
             Token::Bool(true),
+            Token::Str("bingeRules"),
+            Token::Struct {
+                name: "BingeRules",
+                len: 4,
+            },
+            Token::Str("skipWatched"),
+            Token::Bool(false),
+            Token::Str("stopAtSeasonEnd"),
+            Token::Bool(false),
+            Token::Str("specialsInAiredOrder"),
+            Token::Bool(false),
+            Token::Str("playNextCount"),
+            Token::None,
+            Token::StructEnd,
             Token::Str("playInBackground"),
             Token::Bool(true),
             Token::Str("hardwareDecoding"),
@@ -172,8 +267,30 @@ fn settings_de() {
             Token::Bool(false),
             Token::Str("surroundSound"),
             Token::Bool(false),
+            Token::Str("streamPreferences"),
+            Token::Struct {
+                name: "StreamPreferences",
+                len: 6,
+            },
+            Token::Str("preferredResolutions"),
+            Token::Seq { len: Some(0) },
+            Token::SeqEnd,
+            Token::Str("maxSize"),
+            Token::None,
+            Token::Str("excludeCam"),
+            Token::Bool(false),
+            Token::Str("preferredLanguages"),
+            Token::Seq { len: Some(0) },
+            Token::SeqEnd,
+            Token::Str("hideP2p"),
+            Token::Bool(false),
+            Token::Str("preferDirectUrls"),
+            Token::Bool(false),
+            Token::StructEnd,
             Token::Str("streamingServerWarningDismissed"),
             Token::None,
+            Token::Str("notificationsQuietHours"),
+            Token::None,
             Token::StructEnd,
         ],
     );
@@ -0,0 +1,204 @@
+use std::any::Any;
+
+use futures::future;
+use stremio_derive::Model;
+use url::Url;
+
+use crate::{
+    models::{
+        ctx::Ctx,
+        streaming_server::{PlaybackDevice, StatisticsRequest, StreamingServer},
+    },
+    runtime::{
+        msg::{Action, ActionStreamingServer},
+        EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture,
+    },
+    types::{
+        api::SuccessResponse,
+        profile::{Auth, AuthKey, Profile},
+        streaming_server::{
+            NetworkInfo, ServerFeature, Settings as StreamingServerSettings, SettingsResponse,
+        },
+        True,
+    },
+    unit_tests::{default_fetch_handler, Request, TestEnv, FETCH_HANDLER, REQUESTS},
+};
+
+const STREAMING_SERVER_URL: &str = "http://127.0.0.1:11470";
+
+fn streaming_server_settings() -> StreamingServerSettings {
+    StreamingServerSettings {
+        remote_https: None,
+        app_path: String::new(),
+        cache_root: String::new(),
+        server_version: "3.13.0".to_owned(),
+        cache_size: None,
+        bt_max_connections: 55,
+        bt_handshake_timeout: 20_000,
+        bt_request_timeout: 4_000,
+        bt_download_speed_soft_limit: 2_621_440.0,
+        bt_download_speed_hard_limit: 3_670_016.0,
+        bt_min_peers_for_stable: 5,
+    }
+}
+
+#[test]
+fn outdated_server() {
+    #[derive(Model, Clone, Debug)]
+    #[model(TestEnv)]
+    struct TestModel {
+        ctx: Ctx,
+        streaming_server: StreamingServer,
+    }
+
+    fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+        match request {
+            Request { url, method, .. }
+                if method == "GET" && url == "http://127.0.0.1:11470/settings" =>
+            {
+                future::ok(Box::new(SettingsResponse {
+                    base_url: Url::parse(STREAMING_SERVER_URL).unwrap(),
+                    values: streaming_server_settings(),
+                }) as Box<dyn Any + Send>)
+                .boxed_env()
+            }
+            Request { url, method, .. }
+                if method == "POST" && url == "http://127.0.0.1:11470/settings" =>
+            {
+                future::ok(Box::new(SuccessResponse { success: True }) as Box<dyn Any + Send>)
+                    .boxed_env()
+            }
+            Request { url, .. } if url == "http://127.0.0.1:11470/casting" => {
+                future::ok(Box::<Vec<PlaybackDevice>>::default() as Box<dyn Any + Send>).boxed_env()
+            }
+            Request { url, .. } if url == "http://127.0.0.1:11470/network-info" => {
+                future::ok(Box::new(NetworkInfo {
+                    available_interfaces: vec!["192.168.0.10".to_owned()],
+                }) as Box<dyn Any + Send>)
+                .boxed_env()
+            }
+            _ => default_fetch_handler(request),
+        }
+    }
+
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+
+    let profile = Profile {
+        auth: Some(Auth {
+            key: AuthKey("auth_key".to_owned()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    let (streaming_server, ..) = StreamingServer::new::<TestEnv>(&profile);
+
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                profile,
+                ..Default::default()
+            },
+            streaming_server,
+        },
+        vec![],
+        1000,
+    );
+
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::StreamingServer(ActionStreamingServer::Reload),
+        });
+    });
+
+    assert_eq!(
+        runtime
+            .model()
+            .unwrap()
+            .streaming_server
+            .compatibility
+            .as_ref()
+            .map(|compatibility| compatibility.unsupported_features.to_owned()),
+        Some(vec![
+            ServerFeature::Statistics,
+            ServerFeature::Casting,
+            ServerFeature::RemoteHttps,
+            ServerFeature::Transcoding,
+        ]),
+        "Outdated server should be warned about"
+    );
+    assert!(
+        runtime
+            .model()
+            .unwrap()
+            .streaming_server
+            .playback_devices
+            .is_err(),
+        "Casting should not be available"
+    );
+
+    REQUESTS.write().unwrap().clear();
+
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::StreamingServer(ActionStreamingServer::Reload),
+        });
+    });
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::StreamingServer(ActionStreamingServer::GetStatistics(
+                StatisticsRequest {
+                    info_hash: "6d0724e5b1f5b4b2bb8d3e2f4c3b2d1a5e6f7a8b".to_owned(),
+                    file_idx: 0,
+                },
+            )),
+        });
+    });
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::StreamingServer(ActionStreamingServer::UpdateSettings(
+                StreamingServerSettings {
+                    remote_https: Some("192.168.0.10".to_owned()),
+                    ..streaming_server_settings()
+                },
+            )),
+        });
+    });
+
+    assert!(
+        matches!(
+            runtime.model().unwrap().streaming_server.statistics,
+            Some(ref statistics) if statistics.is_err()
+        ),
+        "Statistics should not be available"
+    );
+    assert!(
+        runtime
+            .model()
+            .unwrap()
+            .streaming_server
+            .remote_url
+            .is_none(),
+        "Remote url should not be set"
+    );
+    assert_eq!(
+        REQUESTS
+            .read()
+            .unwrap()
+            .iter()
+            .map(|request| (request.method.as_str(), request.url.as_str()))
+            .collect::<Vec<_>>(),
+        vec![
+            ("GET", "http://127.0.0.1:11470/settings"),
+            ("GET", "http://127.0.0.1:11470/network-info"),
+            ("POST", "http://127.0.0.1:11470/settings"),
+        ],
+        "Unsupported features should not be requested"
+    );
+}
@@ -0,0 +1,230 @@
+use std::any::Any;
+use std::task::Poll;
+
+use futures::future;
+use stremio_derive::Model;
+use url::Url;
+
+use crate::{
+    models::{
+        ctx::Ctx,
+        streaming_server::{PlaybackDevice, StreamingServer},
+    },
+    runtime::{
+        msg::{Action, ActionStreamingServer},
+        EnvError, EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture,
+    },
+    types::{
+        profile::{Profile, Settings},
+        streaming_server::{NetworkInfo, Settings as StreamingServerSettings, SettingsResponse},
+    },
+    unit_tests::{Request, TestEnv, FETCH_HANDLER},
+};
+
+const FALLBACK_STREAMING_SERVER_URL: &str = "http://192.168.0.2:11470/";
+
+#[test]
+fn failover_to_next_healthy_server() {
+    #[derive(Model, Clone, Debug)]
+    #[model(TestEnv)]
+    struct TestModel {
+        ctx: Ctx,
+        streaming_server: StreamingServer,
+    }
+
+    fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+        match request {
+            Request { url, .. } if url == "http://192.168.0.2:11470/settings" => {
+                future::ok(Box::new(SettingsResponse {
+                    base_url: Url::parse(FALLBACK_STREAMING_SERVER_URL).unwrap(),
+                    values: StreamingServerSettings {
+                        remote_https: None,
+                        app_path: String::new(),
+                        cache_root: String::new(),
+                        server_version: "4.20.8".to_owned(),
+                        cache_size: None,
+                        bt_max_connections: 0,
+                        bt_handshake_timeout: 0,
+                        bt_request_timeout: 0,
+                        bt_download_speed_soft_limit: 0.0,
+                        bt_download_speed_hard_limit: 0.0,
+                        bt_min_peers_for_stable: 0,
+                    },
+                }) as Box<dyn Any + Send>)
+                .boxed_env()
+            }
+            Request { url, .. } if url == "http://192.168.0.2:11470/casting" => {
+                future::ok(Box::<Vec<PlaybackDevice>>::default() as Box<dyn Any + Send>).boxed_env()
+            }
+            Request { url, .. } if url == "http://192.168.0.2:11470/network-info" => {
+                future::ok(Box::new(NetworkInfo {
+                    available_interfaces: vec![],
+                }) as Box<dyn Any + Send>)
+                .boxed_env()
+            }
+            _ => future::err(EnvError::Fetch("Connection refused".to_owned())).boxed_env(),
+        }
+    }
+
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+    let profile = Profile {
+        settings: Settings {
+            streaming_servers: vec![Url::parse(FALLBACK_STREAMING_SERVER_URL).unwrap()],
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let (streaming_server, ..) = StreamingServer::new::<TestEnv>(&profile);
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                profile,
+                ..Default::default()
+            },
+            streaming_server,
+        },
+        vec![],
+        1000,
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::StreamingServer(ActionStreamingServer::Reload),
+        });
+    });
+    let model = runtime.model().unwrap();
+    let streaming_server = &model.streaming_server;
+    assert_eq!(
+        streaming_server.selected.transport_url.as_str(),
+        FALLBACK_STREAMING_SERVER_URL,
+        "The fallback server should be selected"
+    );
+    assert!(streaming_server.settings.is_ready());
+    assert!(streaming_server.network_info.is_ready());
+    assert_eq!(
+        streaming_server
+            .servers
+            .iter()
+            .map(|server| (server.url.as_str(), server.settings.is_ready()))
+            .collect::<Vec<_>>(),
+        vec![
+            ("http://127.0.0.1:11470/", false),
+            (FALLBACK_STREAMING_SERVER_URL, true),
+        ]
+    );
+    assert!(streaming_server.servers[0].settings.is_err());
+    assert_eq!(
+        model.ctx.streaming_server.ready_url(),
+        Some(&Url::parse(FALLBACK_STREAMING_SERVER_URL).unwrap()),
+        "The fallback server should be shared with the other models"
+    );
+}
+
+#[test]
+fn failover_prefers_ready_servers() {
+    #[derive(Model, Clone, Debug)]
+    #[model(TestEnv)]
+    struct TestModel {
+        ctx: Ctx,
+        streaming_server: StreamingServer,
+    }
+
+    /// Resolves once the other requests had a few chances to respond.
+    fn delayed(
+        result: Result<Box<dyn Any + Send>, EnvError>,
+        mut polls: usize,
+    ) -> TryEnvFuture<Box<dyn Any + Send>> {
+        let mut result = Some(result);
+        future::poll_fn(move |cx| {
+            if polls == 0 {
+                return Poll::Ready(result.take().expect("polled after completion"));
+            };
+            polls -= 1;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        })
+        .boxed_env()
+    }
+
+    fn settings_response(base_url: &str) -> Box<dyn Any + Send> {
+        Box::new(SettingsResponse {
+            base_url: Url::parse(base_url).unwrap(),
+            values: StreamingServerSettings {
+                remote_https: None,
+                app_path: String::new(),
+                cache_root: String::new(),
+                server_version: "4.20.8".to_owned(),
+                cache_size: None,
+                bt_max_connections: 0,
+                bt_handshake_timeout: 0,
+                bt_request_timeout: 0,
+                bt_download_speed_soft_limit: 0.0,
+                bt_download_speed_hard_limit: 0.0,
+                bt_min_peers_for_stable: 0,
+            },
+        })
+    }
+
+    fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+        match request {
+            Request { url, .. } if url == "http://127.0.0.1:11470/settings" => {
+                delayed(Err(EnvError::Fetch("Connection refused".to_owned())), 5)
+            }
+            Request { url, .. } if url == "http://192.168.0.3:11470/settings" => {
+                delayed(Ok(settings_response("http://192.168.0.3:11470/")), 20)
+            }
+            Request { url, .. } if url == "http://192.168.0.2:11470/settings" => {
+                future::ok(settings_response(FALLBACK_STREAMING_SERVER_URL)).boxed_env()
+            }
+            Request { url, .. } if url == "http://192.168.0.2:11470/casting" => {
+                future::ok(Box::<Vec<PlaybackDevice>>::default() as Box<dyn Any + Send>).boxed_env()
+            }
+            Request { url, .. } if url == "http://192.168.0.2:11470/network-info" => {
+                future::ok(Box::new(NetworkInfo {
+                    available_interfaces: vec![],
+                }) as Box<dyn Any + Send>)
+                .boxed_env()
+            }
+            _ => future::err(EnvError::Fetch("Connection refused".to_owned())).boxed_env(),
+        }
+    }
+
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+    let profile = Profile {
+        settings: Settings {
+            streaming_servers: vec![
+                Url::parse("http://192.168.0.3:11470/").unwrap(),
+                Url::parse(FALLBACK_STREAMING_SERVER_URL).unwrap(),
+            ],
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let (streaming_server, ..) = StreamingServer::new::<TestEnv>(&profile);
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                profile,
+                ..Default::default()
+            },
+            streaming_server,
+        },
+        vec![],
+        1000,
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::StreamingServer(ActionStreamingServer::Reload),
+        });
+    });
+    let model = runtime.model().unwrap();
+    assert_eq!(
+        model.streaming_server.selected.transport_url.as_str(),
+        FALLBACK_STREAMING_SERVER_URL,
+        "The server which responded should be preferred over the one which is still probed"
+    );
+    assert!(model.streaming_server.settings.is_ready());
+}
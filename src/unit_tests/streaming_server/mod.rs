@@ -1 +1,5 @@
+mod compatibility;
+mod failover;
+mod remote_access;
 mod remote_endpoint;
+mod settings;
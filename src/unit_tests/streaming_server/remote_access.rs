@@ -0,0 +1,288 @@
+use std::any::Any;
+
+use futures::future;
+use stremio_derive::Model;
+use url::Url;
+
+use crate::{
+    models::{
+        common::Loadable,
+        ctx::Ctx,
+        streaming_server::{PlaybackDevice, StreamingServer},
+    },
+    runtime::{
+        msg::{Action, ActionStreamingServer},
+        EnvError, EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture,
+    },
+    types::{
+        api::SuccessResponse,
+        profile::{Auth, AuthKey, Profile},
+        streaming_server::{
+            GetHTTPSResponse, NetworkInfo, RemoteAccess, RemoteAccessError, RemoteAccessStep,
+            Settings as StreamingServerSettings, SettingsResponse,
+        },
+        True,
+    },
+    unit_tests::{default_fetch_handler, Request, TestEnv, FETCH_HANDLER, REQUESTS},
+};
+
+const STREAMING_SERVER_URL: &str = "http://127.0.0.1:11470";
+const REMOTE_URL: &str = "https://192-168-0-10.519b6502d940.stremio.rocks:12470/";
+const STREAMING_SERVER_SETTINGS: StreamingServerSettings = StreamingServerSettings {
+    remote_https: None,
+    app_path: String::new(),
+    cache_root: String::new(),
+    server_version: String::new(),
+    cache_size: None,
+    bt_max_connections: 55,
+    bt_handshake_timeout: 20_000,
+    bt_request_timeout: 4_000,
+    bt_download_speed_soft_limit: 2_621_440.0,
+    bt_download_speed_hard_limit: 3_670_016.0,
+    bt_min_peers_for_stable: 5,
+};
+
+const AVAILABLE_INTERFACE: &str = "192.168.0.10";
+
+#[derive(Model, Clone, Debug)]
+#[model(TestEnv)]
+struct TestModel {
+    ctx: Ctx,
+    streaming_server: StreamingServer,
+}
+
+fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+    match request {
+        Request { url, method, .. }
+            if method == "GET"
+                && (url == "http://127.0.0.1:11470/settings"
+                    || url == format!("{REMOTE_URL}settings")) =>
+        {
+            future::ok(Box::new(SettingsResponse {
+                base_url: Url::parse(STREAMING_SERVER_URL).unwrap(),
+                values: STREAMING_SERVER_SETTINGS,
+            }) as Box<dyn Any + Send>)
+            .boxed_env()
+        }
+        Request { url, method, .. }
+            if method == "POST" && url == "http://127.0.0.1:11470/settings" =>
+        {
+            future::ok(Box::new(SuccessResponse { success: True }) as Box<dyn Any + Send>)
+                .boxed_env()
+        }
+        Request { url, .. } if url == "http://127.0.0.1:11470/casting" => {
+            future::ok(Box::<Vec<PlaybackDevice>>::default() as Box<dyn Any + Send>).boxed_env()
+        }
+        Request { url, .. } if url == "http://127.0.0.1:11470/network-info" => {
+            future::ok(Box::new(NetworkInfo {
+                available_interfaces: vec![AVAILABLE_INTERFACE.to_string()],
+            }) as Box<dyn Any + Send>)
+            .boxed_env()
+        }
+        Request { url, .. }
+            if url
+                == format!(
+                "http://127.0.0.1:11470/get-https?authKey=auth_key&ipAddress={AVAILABLE_INTERFACE}"
+            ) =>
+        {
+            future::ok(Box::new(GetHTTPSResponse {
+                ip_address: AVAILABLE_INTERFACE.to_string(),
+                domain: "192-168-0-10.519b6502d940.stremio.rocks".to_string(),
+                port: 12470,
+            }) as Box<dyn Any + Send>)
+            .boxed_env()
+        }
+        _ => default_fetch_handler(request),
+    }
+}
+
+#[test]
+fn remote_access() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+
+    let profile = Profile {
+        auth: Some(Auth {
+            key: AuthKey("auth_key".to_owned()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    let (streaming_server, ..) = StreamingServer::new::<TestEnv>(&profile);
+
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                profile,
+                ..Default::default()
+            },
+            streaming_server,
+        },
+        vec![],
+        1000,
+    );
+
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::StreamingServer(ActionStreamingServer::Reload),
+        });
+    });
+
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::StreamingServer(ActionStreamingServer::SetupRemoteAccess(
+                "10.0.0.1".to_owned(),
+            )),
+        });
+    });
+
+    assert_eq!(
+        runtime.model().unwrap().streaming_server.remote_access,
+        Some(RemoteAccess {
+            interface: "10.0.0.1".to_owned(),
+            step: RemoteAccessStep::Failed(RemoteAccessError::UnavailableInterface(
+                "10.0.0.1".to_owned()
+            )),
+        }),
+        "Interface which is not available should not be set up"
+    );
+
+    REQUESTS.write().unwrap().clear();
+
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::StreamingServer(ActionStreamingServer::SetupRemoteAccess(
+                AVAILABLE_INTERFACE.to_owned(),
+            )),
+        });
+    });
+
+    let remote_url = Url::parse(REMOTE_URL).unwrap();
+    assert_eq!(
+        runtime.model().unwrap().streaming_server.remote_access,
+        Some(RemoteAccess {
+            interface: AVAILABLE_INTERFACE.to_owned(),
+            step: RemoteAccessStep::Ready(remote_url.to_owned()),
+        }),
+        "Remote access should be ready"
+    );
+    assert_eq!(
+        runtime.model().unwrap().streaming_server.remote_url,
+        Some(remote_url),
+        "Remote url should be set"
+    );
+    assert_eq!(
+        runtime.model().unwrap().streaming_server.settings,
+        Loadable::Ready(StreamingServerSettings {
+            remote_https: Some(AVAILABLE_INTERFACE.to_owned()),
+            ..STREAMING_SERVER_SETTINGS
+        }),
+        "Interface should be saved in the settings"
+    );
+    assert_eq!(
+        REQUESTS
+            .read()
+            .unwrap()
+            .iter()
+            .map(|request| (request.method.as_str(), request.url.as_str()))
+            .collect::<Vec<_>>(),
+        vec![
+            (
+                "GET",
+                "http://127.0.0.1:11470/get-https?authKey=auth_key&ipAddress=192.168.0.10"
+            ),
+            (
+                "GET",
+                "https://192-168-0-10.519b6502d940.stremio.rocks:12470/settings"
+            ),
+            (
+                "GET",
+                "http://127.0.0.1:11470/get-https?authKey=auth_key&ipAddress=192.168.0.10"
+            ),
+            ("POST", "http://127.0.0.1:11470/settings"),
+        ],
+        "Certificate should be requested, then probed and saved"
+    );
+}
+
+#[test]
+fn remote_access_save_failed() {
+    fn save_failed_fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+        match request {
+            Request { url, method, .. }
+                if method == "POST" && url == "http://127.0.0.1:11470/settings" =>
+            {
+                future::err(EnvError::Fetch("Failed to save the settings".to_owned())).boxed_env()
+            }
+            _ => fetch_handler(request),
+        }
+    }
+
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+
+    *FETCH_HANDLER.write().unwrap() = Box::new(save_failed_fetch_handler);
+
+    let profile = Profile {
+        auth: Some(Auth {
+            key: AuthKey("auth_key".to_owned()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    let (streaming_server, ..) = StreamingServer::new::<TestEnv>(&profile);
+
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                profile,
+                ..Default::default()
+            },
+            streaming_server,
+        },
+        vec![],
+        1000,
+    );
+
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::StreamingServer(ActionStreamingServer::Reload),
+        });
+    });
+
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::StreamingServer(ActionStreamingServer::SetupRemoteAccess(
+                AVAILABLE_INTERFACE.to_owned(),
+            )),
+        });
+    });
+
+    assert_eq!(
+        runtime.model().unwrap().streaming_server.remote_access,
+        Some(RemoteAccess {
+            interface: AVAILABLE_INTERFACE.to_owned(),
+            step: RemoteAccessStep::Failed(RemoteAccessError::Save(
+                "Failed to fetch: Failed to save the settings".to_owned()
+            )),
+        }),
+        "Remote access should fail to save"
+    );
+    assert_eq!(
+        runtime.model().unwrap().streaming_server.settings,
+        Loadable::Ready(STREAMING_SERVER_SETTINGS),
+        "Previous remote https should be restored"
+    );
+    assert_eq!(
+        runtime.model().unwrap().streaming_server.remote_url,
+        None,
+        "Remote url should not be set"
+    );
+}
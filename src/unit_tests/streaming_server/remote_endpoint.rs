@@ -31,12 +31,12 @@ const STREAMING_SERVER_SETTINGS: StreamingServerSettings = StreamingServerSettin
     cache_root: String::new(),
     server_version: String::new(),
     cache_size: None,
-    bt_max_connections: 0,
-    bt_handshake_timeout: 0,
-    bt_request_timeout: 0,
-    bt_download_speed_soft_limit: 0.0,
-    bt_download_speed_hard_limit: 0.0,
-    bt_min_peers_for_stable: 0,
+    bt_max_connections: 55,
+    bt_handshake_timeout: 20_000,
+    bt_request_timeout: 4_000,
+    bt_download_speed_soft_limit: 2_621_440.0,
+    bt_download_speed_hard_limit: 3_670_016.0,
+    bt_min_peers_for_stable: 5,
 };
 
 const AVAILABLE_INTERFACE: &str = "192.168.0.10";
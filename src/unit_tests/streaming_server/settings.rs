@@ -0,0 +1,254 @@
+use std::any::Any;
+
+use futures::future;
+use stremio_derive::Model;
+use url::Url;
+
+use crate::{
+    models::{
+        common::Loadable,
+        ctx::Ctx,
+        streaming_server::{PlaybackDevice, StreamingServer},
+    },
+    runtime::{
+        msg::{Action, ActionStreamingServer},
+        EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture,
+    },
+    types::{
+        api::SuccessResponse,
+        profile::Profile,
+        streaming_server::{
+            NetworkInfo, Settings as StreamingServerSettings, SettingsError, SettingsPreset,
+            SettingsResponse,
+        },
+        True,
+    },
+    unit_tests::{default_fetch_handler, Request, TestEnv, FETCH_HANDLER, REQUESTS},
+};
+
+const STREAMING_SERVER_SETTINGS: StreamingServerSettings = StreamingServerSettings {
+    remote_https: None,
+    app_path: String::new(),
+    cache_root: String::new(),
+    server_version: String::new(),
+    cache_size: None,
+    bt_max_connections: 55,
+    bt_handshake_timeout: 20_000,
+    bt_request_timeout: 4_000,
+    bt_download_speed_soft_limit: 2_621_440.0,
+    bt_download_speed_hard_limit: 3_670_016.0,
+    bt_min_peers_for_stable: 5,
+};
+
+#[test]
+fn update_settings_validation() {
+    #[derive(Model, Clone, Debug)]
+    #[model(TestEnv)]
+    struct TestModel {
+        ctx: Ctx,
+        streaming_server: StreamingServer,
+    }
+
+    fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+        match request {
+            Request { url, method, .. }
+                if method == "GET" && url == "http://127.0.0.1:11470/settings" =>
+            {
+                future::ok(Box::new(SettingsResponse {
+                    base_url: Url::parse("http://127.0.0.1:11470").unwrap(),
+                    values: STREAMING_SERVER_SETTINGS,
+                }) as Box<dyn Any + Send>)
+                .boxed_env()
+            }
+            Request { url, method, .. }
+                if method == "POST" && url == "http://127.0.0.1:11470/settings" =>
+            {
+                future::ok(Box::new(SuccessResponse { success: True }) as Box<dyn Any + Send>)
+                    .boxed_env()
+            }
+            Request { url, .. } if url == "http://127.0.0.1:11470/casting" => {
+                future::ok(Box::<Vec<PlaybackDevice>>::default() as Box<dyn Any + Send>).boxed_env()
+            }
+            Request { url, .. } if url == "http://127.0.0.1:11470/network-info" => {
+                future::ok(Box::new(NetworkInfo {
+                    available_interfaces: vec![],
+                }) as Box<dyn Any + Send>)
+                .boxed_env()
+            }
+            _ => default_fetch_handler(request),
+        }
+    }
+
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+    let (streaming_server, ..) = StreamingServer::new::<TestEnv>(&Profile::default());
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx::default(),
+            streaming_server,
+        },
+        vec![],
+        1000,
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::StreamingServer(ActionStreamingServer::Reload),
+        });
+    });
+    let posted_settings = || {
+        REQUESTS
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|request| request.method == "POST")
+            .count()
+    };
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::StreamingServer(ActionStreamingServer::UpdateSettings(
+                StreamingServerSettings {
+                    bt_max_connections: 0,
+                    bt_download_speed_soft_limit: 5_000_000.0,
+                    ..STREAMING_SERVER_SETTINGS
+                },
+            )),
+        });
+    });
+    assert_eq!(posted_settings(), 0, "Invalid settings should not be sent");
+    {
+        let model = runtime.model().unwrap();
+        assert_eq!(
+            model.streaming_server.settings_errors,
+            vec![
+                SettingsError::OutOfRange {
+                    setting: "btMaxConnections".to_owned(),
+                    min: 1.0,
+                    max: 1000.0,
+                },
+                SettingsError::SoftLimitAboveHardLimit,
+            ]
+        );
+        assert_eq!(
+            model.streaming_server.settings,
+            Loadable::Ready(STREAMING_SERVER_SETTINGS),
+            "Settings should not be changed"
+        );
+    }
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::StreamingServer(ActionStreamingServer::ApplySettingsPreset(
+                SettingsPreset::Seedbox,
+            )),
+        });
+    });
+    assert_eq!(posted_settings(), 1, "Preset settings should be sent");
+    let model = runtime.model().unwrap();
+    assert!(model.streaming_server.settings_errors.is_empty());
+    assert_eq!(
+        model.streaming_server.settings,
+        Loadable::Ready(StreamingServerSettings {
+            bt_max_connections: 200,
+            bt_handshake_timeout: 25_000,
+            bt_request_timeout: 6_000,
+            bt_download_speed_soft_limit: 4_194_304.0,
+            bt_download_speed_hard_limit: 39_321_600.0,
+            bt_min_peers_for_stable: 10,
+            ..STREAMING_SERVER_SETTINGS
+        })
+    );
+}
+
+#[test]
+fn update_settings_with_reported_out_of_range_value() {
+    #[derive(Model, Clone, Debug)]
+    #[model(TestEnv)]
+    struct TestModel {
+        ctx: Ctx,
+        streaming_server: StreamingServer,
+    }
+
+    /// The server already uses a value which is out of the allowed range.
+    const REPORTED_SETTINGS: StreamingServerSettings = StreamingServerSettings {
+        bt_max_connections: 2000,
+        ..STREAMING_SERVER_SETTINGS
+    };
+
+    fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+        match request {
+            Request { url, method, .. }
+                if method == "GET" && url == "http://127.0.0.1:11470/settings" =>
+            {
+                future::ok(Box::new(SettingsResponse {
+                    base_url: Url::parse("http://127.0.0.1:11470").unwrap(),
+                    values: REPORTED_SETTINGS,
+                }) as Box<dyn Any + Send>)
+                .boxed_env()
+            }
+            Request { url, method, .. }
+                if method == "POST" && url == "http://127.0.0.1:11470/settings" =>
+            {
+                future::ok(Box::new(SuccessResponse { success: True }) as Box<dyn Any + Send>)
+                    .boxed_env()
+            }
+            Request { url, .. } if url == "http://127.0.0.1:11470/casting" => {
+                future::ok(Box::<Vec<PlaybackDevice>>::default() as Box<dyn Any + Send>).boxed_env()
+            }
+            Request { url, .. } if url == "http://127.0.0.1:11470/network-info" => {
+                future::ok(Box::new(NetworkInfo {
+                    available_interfaces: vec![],
+                }) as Box<dyn Any + Send>)
+                .boxed_env()
+            }
+            _ => default_fetch_handler(request),
+        }
+    }
+
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+    let (streaming_server, ..) = StreamingServer::new::<TestEnv>(&Profile::default());
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx::default(),
+            streaming_server,
+        },
+        vec![],
+        1000,
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::StreamingServer(ActionStreamingServer::Reload),
+        });
+    });
+    let next_settings = StreamingServerSettings {
+        cache_size: Some(2_147_483_648.0),
+        ..REPORTED_SETTINGS
+    };
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::StreamingServer(ActionStreamingServer::UpdateSettings(
+                next_settings.to_owned(),
+            )),
+        });
+    });
+    assert_eq!(
+        REQUESTS
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|request| request.method == "POST")
+            .count(),
+        1,
+        "Settings with an unchanged out-of-range value should be sent"
+    );
+    let model = runtime.model().unwrap();
+    assert!(model.streaming_server.settings_errors.is_empty());
+    assert_eq!(
+        model.streaming_server.settings,
+        Loadable::Ready(next_settings)
+    );
+}
@@ -0,0 +1,106 @@
+use crate::models::common::Loadable;
+use crate::models::ctx::Ctx;
+use crate::models::subtitles_converter::{Selected, SubtitlesConverter};
+use crate::runtime::msg::{Action, ActionLoad};
+use crate::runtime::{EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture};
+use crate::types::resource::Stream;
+use crate::types::streams::{StreamItemState, StreamsBucket, StreamsItem, StreamsItemKey};
+use crate::types::subtitles::{SubtitlesEncoding, SubtitlesFormat};
+use crate::unit_tests::{default_fetch_handler, Request, TestEnv, FETCH_HANDLER};
+use chrono::{TimeZone, Utc};
+use futures::future;
+use std::any::Any;
+use stremio_derive::Model;
+
+#[test]
+fn convert_srt_to_webvtt_with_delay() {
+    #[derive(Model, Default, Clone, Debug)]
+    #[model(TestEnv)]
+    struct TestModel {
+        ctx: Ctx,
+        subtitles_converter: SubtitlesConverter,
+    }
+    fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+        match request {
+            Request { url, .. } if url == "https://subtitles/1.srt" => {
+                // "Привет" encoded in Windows-1251
+                let greeting: [u8; 6] = [0xCF, 0xF0, 0xE8, 0xE2, 0xE5, 0xF2];
+                let srt = [
+                    b"1\r\n00:00:01,000 --> 00:00:02,500\r\n<font color=\"red\">".as_slice(),
+                    greeting.as_slice(),
+                    b"</font>\r\n\r\n2\r\n00:00:03,000 --> 00:00:04,000\r\n{\\an8}<i>Line</i>\r\nTwo\r\n"
+                        .as_slice(),
+                ]
+                .concat();
+                future::ok(Box::new(srt) as Box<dyn Any + Send>).boxed_env()
+            }
+            _ => default_fetch_handler(request),
+        }
+    }
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+    let stream_key = StreamsItemKey {
+        meta_id: "tt1".to_owned(),
+        video_id: "tt1".to_owned(),
+    };
+    let mut streams = StreamsBucket::default();
+    streams.items.insert(
+        stream_key.to_owned(),
+        StreamsItem {
+            stream: Stream::youtube("yt_id:1").unwrap(),
+            r#type: "movie".to_owned(),
+            meta_id: "tt1".to_owned(),
+            video_id: "tt1".to_owned(),
+            meta_transport_url: "https://addon/manifest.json".parse().unwrap(),
+            stream_transport_url: "https://addon/manifest.json".parse().unwrap(),
+            state: Some(StreamItemState {
+                subtitle_track: None,
+                subtitle_delay: Some(-1500),
+                audio_track: None,
+                audio_delay: None,
+                playback_speed: None,
+                player_type: None,
+            }),
+            mtime: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+        },
+    );
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(
+        TestModel {
+            ctx: Ctx {
+                streams,
+                ..Default::default()
+            },
+            subtitles_converter: Default::default(),
+        },
+        vec![],
+        1000,
+    );
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Load(ActionLoad::SubtitlesConverter(Selected {
+                url: "https://subtitles/1.srt".parse().unwrap(),
+                format: SubtitlesFormat::WebVTT,
+                stream_key: Some(stream_key),
+            })),
+        })
+    });
+    let model = runtime.model().unwrap();
+    match &model.subtitles_converter.file {
+        Some(Loadable::Ready(file)) => {
+            assert_eq!(file.format, SubtitlesFormat::SRT);
+            assert_eq!(file.encoding, SubtitlesEncoding::Windows1251);
+            assert_eq!(file.cues.len(), 2);
+        }
+        file => panic!("Subtitles file should be parsed: {file:?}"),
+    }
+    assert_eq!(
+        model.subtitles_converter.content.as_deref(),
+        Some(
+            "WEBVTT\n\n\
+            00:00:00.000 --> 00:00:01.000\nПривет\n\n\
+            00:00:01.500 --> 00:00:02.500\n<i>Line</i>\nTwo\n\n"
+        ),
+        "The delay should be applied and the unsupported tags removed"
+    );
+}
@@ -0,0 +1,383 @@
+use std::any::Any;
+
+use futures::future;
+use serde::Serialize;
+use sha1::{Digest, Sha1};
+use stremio_derive::Model;
+use url::Url;
+
+use crate::models::common::Loadable;
+use crate::models::ctx::Ctx;
+use crate::models::torrent_details::{Selected, TorrentDetails};
+use crate::runtime::msg::{Action, ActionLoad, ActionTorrentDetails};
+use crate::runtime::{EnvError, EnvFutureExt, Runtime, RuntimeAction, TryEnvFuture};
+use crate::types::resource::StreamSource;
+use crate::types::streaming_server::{File, Growler, Options, PeerSearch, Statistics, SwarmCap};
+use crate::types::torrent::TorrentInfoError;
+use crate::unit_tests::{default_fetch_handler, Request, TestEnv, FETCH_HANDLER};
+
+const INFO_HASH: &str = "6d0a1e5b7e0ef5c2d7ae8fbee3e1fa6b6c6fd16e";
+
+#[derive(Model, Default, Clone, Debug)]
+#[model(TestEnv)]
+struct TestModel {
+    ctx: Ctx,
+    torrent_details: TorrentDetails,
+}
+
+fn statistics(files: Vec<File>) -> Statistics {
+    Statistics {
+        name: "Movie".to_owned(),
+        info_hash: INFO_HASH.to_owned(),
+        files,
+        sources: vec![],
+        opts: Options {
+            connections: 0,
+            dht: true,
+            growler: Growler { flood: 0, pulse: 0 },
+            handshake_timeout: 0,
+            path: String::new(),
+            peer_search: PeerSearch {
+                max: 0,
+                min: 0,
+                sources: vec![],
+            },
+            swarm_cap: SwarmCap {
+                max_speed: 0.0,
+                min_peers: 0,
+            },
+            timeout: 0,
+            tracker: true,
+            r#virtual: true,
+        },
+        download_speed: 0.0,
+        upload_speed: 0.0,
+        downloaded: 0,
+        uploaded: 0,
+        unchoked: 0,
+        peers: 0,
+        queued: 0,
+        unique: 0,
+        connection_tries: 0,
+        peer_search_running: false,
+        stream_len: 0,
+        stream_name: String::new(),
+        stream_progress: 0.0,
+        swarm_connections: 0,
+        swarm_paused: false,
+        swarm_size: 0,
+    }
+}
+
+fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+    match request {
+        Request { url, method, .. }
+            if url == format!("http://127.0.0.1:11470/{INFO_HASH}/create") && method == "POST" =>
+        {
+            future::ok(Box::new(statistics(vec![
+                File {
+                    name: "Movie.2024.1080p.mkv".to_owned(),
+                    path: "Movie/Movie.2024.1080p.mkv".to_owned(),
+                    length: 2_000_000_000,
+                    offset: 0,
+                },
+                File {
+                    name: "Movie.2024.1080p.srt".to_owned(),
+                    path: "Movie/Movie.2024.1080p.srt".to_owned(),
+                    length: 100_000,
+                    offset: 2_000_000_000,
+                },
+            ])) as Box<dyn Any + Send>)
+            .boxed_env()
+        }
+        _ => default_fetch_handler(request),
+    }
+}
+
+#[test]
+fn inspect_torrent_file() {
+    #[derive(Serialize)]
+    struct InfoFile {
+        length: u64,
+        path: Vec<String>,
+    }
+    #[derive(Serialize)]
+    struct Info {
+        files: Vec<InfoFile>,
+        name: String,
+    }
+    #[derive(Serialize)]
+    struct Torrent {
+        announce: String,
+        info: Info,
+    }
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    let info = Info {
+        files: vec![
+            InfoFile {
+                length: 30_000_000,
+                path: vec!["Sample".to_owned(), "sample.mkv".to_owned()],
+            },
+            InfoFile {
+                length: 1_500_000_000,
+                path: vec!["Movie.2024.1080p.mkv".to_owned()],
+            },
+            InfoFile {
+                length: 2_000,
+                path: vec!["Movie.2024.1080p.nfo".to_owned()],
+            },
+        ],
+        name: "Movie.2024.1080p".to_owned(),
+    };
+    let mut hasher = Sha1::new();
+    hasher.update(serde_bencode::to_bytes(&info).unwrap());
+    let info_hash = hex::encode(hasher.finalize());
+    let torrent = serde_bencode::to_bytes(&Torrent {
+        announce: "udp://tracker.example:1337/announce".to_owned(),
+        info,
+    })
+    .unwrap();
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(TestModel::default(), vec![], 1000);
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Load(ActionLoad::TorrentDetails(Selected::File(torrent))),
+        })
+    });
+    {
+        let model = runtime.model().unwrap();
+        let info = model
+            .torrent_details
+            .info
+            .as_ref()
+            .and_then(|info| info.ready())
+            .expect("Torrent file should be decoded");
+        assert_eq!(hex::encode(info.info_hash), info_hash);
+        assert_eq!(info.name.as_deref(), Some("Movie.2024.1080p"));
+        assert_eq!(info.size, Some(1_530_002_000));
+        assert_eq!(
+            info.files
+                .iter()
+                .map(|file| (file.path.as_str(), file.offset))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Movie.2024.1080p/Sample/sample.mkv", 0),
+                ("Movie.2024.1080p/Movie.2024.1080p.mkv", 30_000_000),
+                ("Movie.2024.1080p/Movie.2024.1080p.nfo", 1_530_000_000),
+            ]
+        );
+        assert_eq!(
+            info.announce,
+            vec!["udp://tracker.example:1337/announce".to_owned()]
+        );
+        assert_eq!(
+            model.torrent_details.selected_file,
+            Some(1),
+            "The largest video should be selected"
+        );
+        assert_eq!(
+            model
+                .torrent_details
+                .meta_item
+                .as_ref()
+                .map(|meta_item| meta_item.id.to_owned()),
+            Some(format!("bt:{info_hash}"))
+        );
+    }
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::TorrentDetails(ActionTorrentDetails::SelectFile(0)),
+        })
+    });
+    assert!(matches!(
+        runtime
+            .model()
+            .unwrap()
+            .torrent_details
+            .stream
+            .as_ref()
+            .map(|stream| &stream.source),
+        Some(StreamSource::Torrent {
+            file_idx: Some(0),
+            ..
+        })
+    ));
+}
+
+#[test]
+fn inspect_magnet() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+    let magnet = Url::parse(&format!(
+        "magnet:?xt=urn:btih:{INFO_HASH}&dn=Movie+2024&tr=udp%3A%2F%2Ftracker.example%3A1337%2Fannounce"
+    ))
+    .unwrap();
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(TestModel::default(), vec![], 1000);
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Load(ActionLoad::TorrentDetails(Selected::Magnet(magnet))),
+        })
+    });
+    let model = runtime.model().unwrap();
+    let info = model
+        .torrent_details
+        .info
+        .as_ref()
+        .and_then(|info| info.ready())
+        .expect("Magnet should be decoded");
+    assert_eq!(hex::encode(info.info_hash), INFO_HASH);
+    assert_eq!(info.name.as_deref(), Some("Movie 2024"));
+    assert_eq!(
+        info.announce,
+        vec!["udp://tracker.example:1337/announce".to_owned()]
+    );
+    assert_eq!(
+        info.files.len(),
+        2,
+        "Files should be fetched from the streaming server"
+    );
+    assert_eq!(info.size, Some(2_000_100_000));
+    assert_eq!(model.torrent_details.selected_file, Some(0));
+}
+
+#[test]
+fn inspect_magnet_duplicate_trackers() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+    let magnet = Url::parse(&format!(
+        "magnet:?xt=urn:btih:{INFO_HASH}&tr=udp%3A%2F%2Fa.example%3A1337&tr=udp%3A%2F%2Fb.example%3A1337&tr=udp%3A%2F%2Fa.example%3A1337"
+    ))
+    .unwrap();
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(TestModel::default(), vec![], 1000);
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Load(ActionLoad::TorrentDetails(Selected::Magnet(magnet))),
+        })
+    });
+    assert_eq!(
+        runtime
+            .model()
+            .unwrap()
+            .torrent_details
+            .info
+            .as_ref()
+            .and_then(|info| info.ready())
+            .map(|info| info.announce.to_owned()),
+        Some(vec![
+            "udp://a.example:1337".to_owned(),
+            "udp://b.example:1337".to_owned(),
+        ]),
+        "Trackers should be listed once"
+    );
+}
+
+#[test]
+fn inspect_magnet_files_error() {
+    fn fetch_handler(request: Request) -> TryEnvFuture<Box<dyn Any + Send>> {
+        match request {
+            Request { url, method, .. }
+                if url == format!("http://127.0.0.1:11470/{INFO_HASH}/create")
+                    && method == "POST" =>
+            {
+                future::err(EnvError::Fetch("Connection refused".to_owned())).boxed_env()
+            }
+            _ => default_fetch_handler(request),
+        }
+    }
+
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+    let magnet = Url::parse(&format!("magnet:?xt=urn:btih:{INFO_HASH}")).unwrap();
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(TestModel::default(), vec![], 1000);
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Load(ActionLoad::TorrentDetails(Selected::Magnet(magnet))),
+        })
+    });
+    let model = runtime.model().unwrap();
+    assert!(
+        matches!(
+            model.torrent_details.info,
+            Some(Loadable::Err(TorrentInfoError::Files(_)))
+        ),
+        "The magnet should fail when its files can't be fetched"
+    );
+    assert_eq!(model.torrent_details.stream, None);
+}
+
+#[test]
+fn inspect_base32_magnet() {
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    *FETCH_HANDLER.write().unwrap() = Box::new(fetch_handler);
+    let magnet =
+        Url::parse("magnet:?xt=urn:btih:NUFB4W36B324FV5OR67OHYP2NNWG7ULO&dn=Movie+2024").unwrap();
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(TestModel::default(), vec![], 1000);
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Load(ActionLoad::TorrentDetails(Selected::Magnet(magnet))),
+        })
+    });
+    let model = runtime.model().unwrap();
+    let info = model
+        .torrent_details
+        .info
+        .as_ref()
+        .and_then(|info| info.ready())
+        .expect("Base32 magnet should be decoded");
+    assert_eq!(hex::encode(info.info_hash), INFO_HASH);
+    assert_eq!(
+        info.files.len(),
+        2,
+        "Files should be fetched from the streaming server"
+    );
+}
+
+#[test]
+fn inspect_torrent_file_overflowing_length() {
+    #[derive(Serialize)]
+    struct InfoFile {
+        length: u64,
+        path: Vec<String>,
+    }
+    #[derive(Serialize)]
+    struct Info {
+        files: Vec<InfoFile>,
+        name: String,
+    }
+    #[derive(Serialize)]
+    struct Torrent {
+        info: Info,
+    }
+    let _env_mutex = TestEnv::reset().expect("Should have exclusive lock to TestEnv");
+    let torrent = serde_bencode::to_bytes(&Torrent {
+        info: Info {
+            files: (1..=3)
+                .map(|part| InfoFile {
+                    length: i64::MAX as u64,
+                    path: vec![format!("Movie.2024.1080p.part{part}.mkv")],
+                })
+                .collect(),
+            name: "Movie.2024.1080p".to_owned(),
+        },
+    })
+    .unwrap();
+    let (runtime, _rx) = Runtime::<TestEnv, _>::new(TestModel::default(), vec![], 1000);
+    TestEnv::run(|| {
+        runtime.dispatch(RuntimeAction {
+            field: None,
+            action: Action::Load(ActionLoad::TorrentDetails(Selected::File(torrent))),
+        })
+    });
+    assert!(
+        matches!(
+            runtime.model().unwrap().torrent_details.info,
+            Some(Loadable::Err(TorrentInfoError::Torrent(_)))
+        ),
+        "Overflowing file lengths should fail to decode"
+    );
+}